@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use std::ffi::OsString;
+
+/// Expands `@FILE` arguments into the arguments listed in FILE
+///
+/// Windows limits the command line to about 32K characters, which is easy
+/// to hit when hundreds of file paths are passed to wc or grep. Such a list
+/// can be saved into a file and passed as `tool @args.txt` instead.
+///
+/// Argument file format:
+/// - one argument per line, surrounding whitespace is trimmed
+/// - empty lines and lines that start with `#` are skipped
+/// - `@` inside of an argument file is not expanded again
+///
+/// Arguments after `--` are passed as is, the same goes for a lone `@`.
+/// The first argument is the program name and it is never expanded.
+///
+/// Tools that pass their arguments on verbatim don't use it: echo prints them
+/// and env, timeout and watch hand them over to the command they run.
+///
+/// # Errors
+/// Error is returned when an argument file can't be read.
+pub fn expand_args(args: impl IntoIterator<Item = OsString>) -> Result<Vec<OsString>> {
+    let mut result = Vec::new();
+    let mut expand = true;
+
+    for (index, arg) in args.into_iter().enumerate() {
+        if index == 0 || !expand {
+            result.push(arg);
+            continue;
+        }
+
+        if arg == "--" {
+            expand = false;
+            result.push(arg);
+            continue;
+        }
+
+        match arg.to_str().and_then(|text| text.strip_prefix('@')) {
+            Some(path) if !path.is_empty() => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|error| anyhow!("Can't read argument file '{path}', error {error}"))?;
+                result.extend(parse_args_file(&text));
+            }
+            _ => result.push(arg),
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_args_file(text: &str) -> impl Iterator<Item = OsString> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(OsString::from)
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{expand_args, parse_args_file};
+    use std::ffi::OsString;
+
+    fn os(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args_file() {
+        let text = "# files to count\r\n-l\r\n\r\n  a.txt  \r\n#b.txt\r\nc d.txt\n";
+        let args: Vec<OsString> = parse_args_file(text).collect();
+        assert_eq!(args, os(&["-l", "a.txt", "c d.txt"]));
+
+        assert_eq!(parse_args_file("").count(), 0);
+        assert_eq!(parse_args_file("\n# only a comment\n").count(), 0);
+    }
+
+    #[test]
+    fn test_expand_args() {
+        let path = std::env::temp_dir().join(format!("common-args-{}.txt", std::process::id()));
+        std::fs::write(&path, "-c\nfox.txt\n").unwrap();
+        let argfile = format!("@{}", path.display());
+
+        let res = expand_args(os(&["wc", &argfile, "b.txt"]));
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), os(&["wc", "-c", "fox.txt", "b.txt"]));
+
+        // Program name, lone @ and arguments after -- are not expanded
+        let res = expand_args(os(&[&argfile, "@", "--", &argfile]));
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), os(&[&argfile, "@", "--", &argfile]));
+
+        std::fs::remove_file(&path).unwrap();
+
+        let res = expand_args(os(&["wc", "@missing-args-file.txt"]));
+        assert!(res.is_err());
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .starts_with("Can't read argument file 'missing-args-file.txt'"));
+    }
+}
@@ -0,0 +1,98 @@
+/// Order in which items fill a multi-column grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    Down,   // 1 4 7 / 2 5 8 / 3 6 - the way ls and pr do it by default
+    Across, // 1 2 3 / 4 5 6 / 7 8 - pr -a
+}
+
+/// Splits items into rows of a grid with the given number of columns
+///
+/// When items can't be split evenly the leftmost columns get one more item,
+/// so a short last page of `pr -3` looks balanced instead of having
+/// a full first column and a nearly empty last one.
+pub fn grid<T: Copy>(items: &[T], columns: usize, order: Order) -> Vec<Vec<T>> {
+    let columns = columns.max(1);
+    let rows = items.len().div_ceil(columns);
+
+    match order {
+        Order::Across => items.chunks(columns).map(<[T]>::to_vec).collect(),
+        Order::Down => {
+            let base = items.len() / columns;
+            let extra = items.len() % columns;
+            let height = |column: usize| base + usize::from(column < extra);
+            let start = |column: usize| column * base + column.min(extra);
+
+            (0..rows)
+                .map(|row| {
+                    (0..columns)
+                        .filter(|&column| row < height(column))
+                        .map(|column| items[start(column) + row])
+                        .collect()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Joins cells of one grid row so that every column starts at the same position
+///
+/// Cells longer than `column_width` characters are truncated,
+/// the last cell is not padded to avoid trailing spaces.
+pub fn format_row(cells: &[&str], column_width: usize, separator: &str) -> String {
+    let mut result = String::new();
+
+    for (index, cell) in cells.iter().enumerate() {
+        if index > 0 {
+            result.push_str(separator);
+        }
+
+        let cell: String = cell.chars().take(column_width).collect();
+        result.push_str(&cell);
+
+        if index + 1 < cells.len() {
+            let length = cell.chars().count();
+            result.extend(std::iter::repeat_n(' ', column_width - length));
+        }
+    }
+
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{format_row, grid, Order};
+
+    #[test]
+    fn test_grid_down() {
+        let items: Vec<u32> = (1..=9).collect();
+        assert_eq!(grid(&items, 3, Order::Down), [[1, 4, 7], [2, 5, 8], [3, 6, 9]]);
+
+        // 10 items in 3 columns are split as 4, 3 and 3
+        let items: Vec<u32> = (1..=10).collect();
+        assert_eq!(
+            grid(&items, 3, Order::Down),
+            [vec![1, 5, 8], vec![2, 6, 9], vec![3, 7, 10], vec![4]]
+        );
+
+        assert_eq!(grid(&[1, 2], 3, Order::Down), [[1, 2]]);
+        assert!(grid::<u32>(&[], 3, Order::Down).is_empty());
+    }
+
+    #[test]
+    fn test_grid_across() {
+        let items: Vec<u32> = (1..=7).collect();
+        assert_eq!(
+            grid(&items, 3, Order::Across),
+            [vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn test_format_row() {
+        assert_eq!(format_row(&["a", "bb", "c"], 3, " "), "a   bb  c");
+        assert_eq!(format_row(&["abcdef", "x"], 3, " | "), "abc | x");
+        assert_eq!(format_row(&["a", "abcdef"], 3, " "), "a   abc");
+        assert_eq!(format_row(&[], 3, " "), "");
+    }
+}
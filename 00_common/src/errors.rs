@@ -0,0 +1,79 @@
+use std::{
+    fmt::{self, Debug, Display},
+    io::Write,
+};
+
+/// Where a tool reports the errors that don't stop it, like a file that can't be opened
+///
+/// It is stderr by default, flags like grep -s silence it and
+/// code that uses a tool as a library can pass its own writer to capture the messages.
+pub struct ErrorWriter {
+    writer: Option<Box<dyn Write>>,
+}
+
+impl ErrorWriter {
+    pub fn stderr() -> Self {
+        ErrorWriter { writer: Some(Box::new(std::io::stderr())) }
+    }
+
+    pub fn silent() -> Self {
+        ErrorWriter { writer: None }
+    }
+
+    pub fn new(writer: impl Write + 'static) -> Self {
+        ErrorWriter { writer: Some(Box::new(writer)) }
+    }
+
+    /// Writes the error on its own line, failing to do so is not an error of the tool
+    pub fn report(&mut self, error: impl Display) {
+        if let Some(writer) = &mut self.writer {
+            let _ = writeln!(writer, "{error}");
+        }
+    }
+}
+
+impl Default for ErrorWriter {
+    fn default() -> Self {
+        ErrorWriter::stderr()
+    }
+}
+
+impl Debug for ErrorWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = if self.writer.is_some() { "writer" } else { "silent" };
+        f.debug_tuple("ErrorWriter").field(&target).finish()
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::ErrorWriter;
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    #[derive(Clone, Default)]
+    struct Captured(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let captured = Captured::default();
+        let mut errors = ErrorWriter::new(captured.clone());
+        errors.report("first");
+        errors.report(format_args!("file '{}' is gone", "a.txt"));
+        assert_eq!(*captured.0.borrow(), b"first\nfile 'a.txt' is gone\n");
+
+        let mut errors = ErrorWriter::silent();
+        errors.report("nowhere");
+        assert_eq!(format!("{errors:?}"), r#"ErrorWriter("silent")"#);
+    }
+}
@@ -0,0 +1,45 @@
+/// Header that head and tail print above every file when there are several of them
+pub const DEFAULT_FORMAT: &str = "==> {path} <==";
+
+/// Expands the header tokens: {path} is the file path and {n} is its number counted from 1
+///
+/// Unknown tokens are kept as is, and the path is inserted verbatim
+/// even if it looks like a token itself.
+pub fn format(template: &str, path: &str, number: usize) -> String {
+    let mut result = String::with_capacity(template.len() + path.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(tail) = rest.strip_prefix("{path}") {
+            result.push_str(path);
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("{n}") {
+            result.push_str(&number.to_string());
+            rest = tail;
+        } else {
+            result.push('{');
+            rest = &rest[1..];
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{format, DEFAULT_FORMAT};
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(DEFAULT_FORMAT, "a.txt", 1), "==> a.txt <==");
+        assert_eq!(format("#{n} {path}:", "b.txt", 12), "#12 b.txt:");
+        assert_eq!(format("{path} {path}", "{n}", 3), "{n} {n}");
+        assert_eq!(format("{x} {n", "c", 1), "{x} {n");
+        assert_eq!(format("", "c", 1), "");
+    }
+}
@@ -0,0 +1,39 @@
+/// Quotes the text as a JSON string
+///
+/// Tools here print flat JSON objects, one per line, so a full serializer is not needed.
+/// Quotes, backslashes and control characters are escaped, the rest is kept as is.
+pub fn quote(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+
+    for char in text.chars() {
+        match char {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            control if control < ' ' => result.push_str(&format!("\\u{:04x}", control as u32)),
+            _ => result.push(char),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::quote;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("plain"), r#""plain""#);
+        assert_eq!(quote(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote(r"C:\temp"), r#""C:\\temp""#);
+        assert_eq!(quote("a\tb\r\n"), r#""a\tb\r\n""#);
+        assert_eq!(quote("\x1b[0m"), r#""\u001b[0m""#);
+        assert_eq!(quote("Öne"), r#""Öne""#);
+    }
+}
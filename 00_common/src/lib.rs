@@ -0,0 +1,17 @@
+//! Code shared between the command line tools of this repository.
+//!
+//! Every tool is a standalone crate, so anything that more than one
+//! of them needs lives here and is pulled in as a path dependency.
+
+pub mod args;
+pub mod columns;
+pub mod errors;
+pub mod header;
+pub mod json;
+#[cfg(feature = "listing")]
+pub mod listing;
+pub mod locale;
+pub mod process;
+pub mod ranges;
+pub mod reader;
+pub mod units;
@@ -0,0 +1,145 @@
+use crate::locale::Locale;
+use chrono::{DateTime, Datelike, Utc};
+use std::{fs::Metadata, io, path::Path};
+use tabular::{Row, Table};
+
+const READ: u32 = 0b100;
+const WRITE: u32 = 0b010;
+const EXECUTE: u32 = 0b001;
+const OTHER: u32 = 0;
+const GROUP: u32 = 3;
+const USER: u32 = 6;
+
+/// Formats entries the way ls -l does, one aligned row per entry
+///
+/// Metadata is passed in, so a caller that already has it doesn't stat the files again.
+/// Symlinks are shown as links only when the metadata was read without following them.
+pub fn format_long<'a>(
+    entries: impl IntoIterator<Item = (&'a Path, &'a Metadata)>,
+    locale: &Locale,
+) -> io::Result<String> {
+    //         1   2     3     4     5     6     7     8
+    let fmt = "{:<}{:<}  {:>}  {:<}  {:<}  {:>}  {:<}  {:<}";
+    let mut table = Table::new(fmt);
+
+    for (path, meta) in entries {
+        let kind = if meta.is_dir() {
+            "d"
+        } else if meta.is_symlink() {
+            "l"
+        } else {
+            "-"
+        };
+        let owner = Owner::of(meta);
+        let length = meta.len();
+        let modified = meta.modified()?;
+        let modified: DateTime<Utc> = modified.into();
+        let modified = format!(
+            "{}-{}-{}",
+            modified.format("%Y"),
+            locale.month_short(modified.month0()),
+            modified.format("%d %H:%M")
+        );
+
+        table.add_row(
+            Row::new()
+                .with_cell(kind) // 1 - directory, link or else
+                .with_cell(format_mode(owner.mode)) // 2 - rwx permissions
+                .with_cell(owner.links) // 3 - number of hard links
+                .with_cell(owner.user) // 4 - onwer user name
+                .with_cell(owner.group) // 5 - owner group name
+                .with_cell(length) // 6 - file size in bytes
+                .with_cell(modified) // 7 - last modified date
+                .with_cell(path.display()), // 8 - path
+        );
+    }
+
+    Ok(format!("{table}"))
+}
+
+/// Permission bits as the rwxrwxrwx string of user, group and others
+pub fn format_mode(mode: u32) -> String {
+    let render = |part: u32| -> String {
+        let mut result = String::with_capacity(3);
+
+        let print = |mask: u32, char: char| -> char {
+            if part & mask != 0 {
+                char
+            } else {
+                '-'
+            }
+        };
+
+        result.push(print(READ, 'r'));
+        result.push(print(WRITE, 'w'));
+        result.push(print(EXECUTE, 'x'));
+        result
+    };
+
+    let mut result = String::with_capacity(9);
+    result.push_str(&render(mode >> USER));
+    result.push_str(&render(mode >> GROUP));
+    result.push_str(&render(mode >> OTHER));
+    result
+}
+
+// Columns that come from the unix inode
+struct Owner {
+    mode: u32,
+    links: u64,
+    user: String,
+    group: String,
+}
+
+impl Owner {
+    #[cfg(unix)]
+    fn of(meta: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        Owner {
+            mode: meta.mode(),
+            links: meta.nlink(),
+            user: users::get_user_by_uid(meta.uid())
+                .map(|u| u.name().to_string_lossy().into_owned())
+                .unwrap_or(meta.uid().to_string()),
+            group: users::get_group_by_gid(meta.gid())
+                .map(|g| g.name().to_string_lossy().into_owned())
+                .unwrap_or(meta.gid().to_string()),
+        }
+    }
+
+    // Windows has only the read-only attribute, owners are not shown
+    #[cfg(not(unix))]
+    fn of(meta: &Metadata) -> Self {
+        let read = if meta.is_dir() { 0o555 } else { 0o444 };
+        let write = if meta.permissions().readonly() { 0 } else { 0o200 };
+
+        Owner { mode: read | write, links: 1, user: "-".into(), group: "-".into() }
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{format_long, format_mode};
+    use crate::locale::ENGLISH;
+    use std::path::Path;
+
+    #[test]
+    fn test_format_mode() {
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+        assert_eq!(format_mode(0o421), "r---w---x");
+    }
+
+    #[test]
+    fn test_format_long() {
+        let path = Path::new("Cargo.toml");
+        let meta = path.metadata().unwrap();
+
+        let out = format_long([(path, &meta)], &ENGLISH).unwrap();
+        let parts: Vec<&str> = out.split_whitespace().collect();
+        assert!(parts[0].starts_with('-') && parts[0].len() == 10);
+        assert_eq!(parts.get(4), Some(&meta.len().to_string().as_str()));
+        assert_eq!(parts.last(), Some(&"Cargo.toml"));
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{bail, Result};
+
+/// Month and weekday names for one language
+///
+/// Weekdays are stored starting from Monday, the same way as
+/// `chrono::Weekday::num_days_from_monday` counts them.
+#[derive(Debug, PartialEq)]
+pub struct Locale {
+    pub name: &'static str,
+    pub months: [&'static str; 12],
+    pub months_short: [&'static str; 12],
+    pub weekdays_short: [&'static str; 7],
+}
+
+pub const ENGLISH: Locale = Locale {
+    name: "en",
+    months: [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    weekdays_short: ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+};
+
+pub const GERMAN: Locale = Locale {
+    name: "de",
+    months: [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun",
+        "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+    weekdays_short: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+};
+
+pub const SPANISH: Locale = Locale {
+    name: "es",
+    months: [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ],
+    months_short: [
+        "ene", "feb", "mar", "abr", "may", "jun",
+        "jul", "ago", "sep", "oct", "nov", "dic",
+    ],
+    weekdays_short: ["lu", "ma", "mi", "ju", "vi", "sá", "do"],
+};
+
+pub const FRENCH: Locale = Locale {
+    name: "fr",
+    months: [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+    months_short: [
+        "janv", "févr", "mars", "avr", "mai", "juin",
+        "juil", "août", "sept", "oct", "nov", "déc",
+    ],
+    weekdays_short: ["lu", "ma", "me", "je", "ve", "sa", "di"],
+};
+
+pub const ITALIAN: Locale = Locale {
+    name: "it",
+    months: [
+        "gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno",
+        "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre",
+    ],
+    months_short: [
+        "gen", "feb", "mar", "apr", "mag", "giu",
+        "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+    weekdays_short: ["lu", "ma", "me", "gi", "ve", "sa", "do"],
+};
+
+pub const RUSSIAN: Locale = Locale {
+    name: "ru",
+    months: [
+        "Январь", "Февраль", "Март", "Апрель", "Май", "Июнь",
+        "Июль", "Август", "Сентябрь", "Октябрь", "Ноябрь", "Декабрь",
+    ],
+    months_short: [
+        "янв", "фев", "мар", "апр", "май", "июн",
+        "июл", "авг", "сен", "окт", "ноя", "дек",
+    ],
+    weekdays_short: ["Пн", "Вт", "Ср", "Чт", "Пт", "Сб", "Вс"],
+};
+
+/// All supported locales, English goes first since it is the fallback
+pub const LOCALES: [&Locale; 6] = [&ENGLISH, &GERMAN, &SPANISH, &FRENCH, &ITALIAN, &RUSSIAN];
+
+impl Locale {
+    /// Finds locale by POSIX-like name: `de`, `de_DE`, `de_DE.UTF-8`, `C`
+    pub fn find(name: &str) -> Option<&'static Locale> {
+        let language = name
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "c" | "posix" => Some(&ENGLISH),
+            language => LOCALES.into_iter().find(|locale| locale.name == language),
+        }
+    }
+
+    /// Locale from `LC_ALL`, `LC_TIME` or `LANG` environment variables,
+    /// the first one that is set wins. Unknown locales fall back to English.
+    pub fn from_env() -> &'static Locale {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::find(&value))
+            .unwrap_or(&ENGLISH)
+    }
+
+    /// Explicitly requested locale (e.g. via `--locale`) or the one from environment
+    ///
+    /// # Errors
+    /// Error is returned when the requested locale is not supported.
+    pub fn resolve(requested: Option<&str>) -> Result<&'static Locale> {
+        let Some(name) = requested else {
+            return Ok(Locale::from_env());
+        };
+
+        match Locale::find(name) {
+            Some(locale) => Ok(locale),
+            None => {
+                let supported = LOCALES.map(|locale| locale.name).join(", ");
+                bail!("Unsupported locale '{name}', supported are: {supported}")
+            }
+        }
+    }
+
+    /// Full month name, `month0` is zero-based
+    pub fn month(&self, month0: u32) -> &'static str {
+        self.months[month0 as usize % 12]
+    }
+
+    /// Abbreviated month name, `month0` is zero-based
+    pub fn month_short(&self, month0: u32) -> &'static str {
+        self.months_short[month0 as usize % 12]
+    }
+
+    /// Two letter weekday name, weekdays are counted from Monday
+    pub fn weekday_short(&self, days_from_monday: u32) -> &'static str {
+        self.weekdays_short[days_from_monday as usize % 7]
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{Locale, ENGLISH, FRENCH, GERMAN, RUSSIAN};
+
+    #[test]
+    fn test_find() {
+        assert_eq!(Locale::find("de"), Some(&GERMAN));
+        assert_eq!(Locale::find("de_AT"), Some(&GERMAN));
+        assert_eq!(Locale::find("fr_FR.UTF-8"), Some(&FRENCH));
+        assert_eq!(Locale::find("ru-RU"), Some(&RUSSIAN));
+        assert_eq!(Locale::find("EN_us"), Some(&ENGLISH));
+        assert_eq!(Locale::find("C.UTF-8"), Some(&ENGLISH));
+        assert_eq!(Locale::find("POSIX"), Some(&ENGLISH));
+        assert_eq!(Locale::find("xx_XX"), None);
+        assert_eq!(Locale::find(""), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let res = Locale::resolve(Some("de_DE"));
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), &GERMAN);
+
+        let res = Locale::resolve(Some("klingon"));
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Unsupported locale 'klingon', supported are: en, de, es, fr, it, ru"
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(GERMAN.month(2), "März");
+        assert_eq!(FRENCH.month_short(11), "déc");
+        assert_eq!(ENGLISH.weekday_short(6), "Su");
+        assert_eq!(RUSSIAN.weekday_short(0), "Пн");
+    }
+}
@@ -0,0 +1,50 @@
+use std::process::ExitStatus;
+
+/// Exit code a shell would report for a finished child process
+///
+/// Child killed by a signal is reported as 128 + signal number,
+/// so tools that run other commands (env, timeout) can pass it on.
+#[cfg(unix)]
+pub fn exit_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.code().or_else(|| status.signal().map(|signal| 128 + signal)).unwrap_or(1)
+}
+
+/// Exit code a shell would report for a finished child process
+#[cfg(not(unix))]
+pub fn exit_code(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(1)
+}
+
+/// Exit code for a command that could not be started, the same as in GNU tools:
+/// 127 when the command is not found, 126 when it can't be run
+pub fn spawn_error_code(error: &std::io::Error) -> i32 {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => 127,
+        _ => 126,
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{exit_code, spawn_error_code};
+    use std::io::{Error, ErrorKind};
+
+    #[test]
+    fn test_spawn_error_code() {
+        assert_eq!(spawn_error_code(&Error::from(ErrorKind::NotFound)), 127);
+        assert_eq!(spawn_error_code(&Error::from(ErrorKind::PermissionDenied)), 126);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Raw wait status keeps the exit code in the second byte and the signal in the first
+        assert_eq!(exit_code(ExitStatusExt::from_raw(3 << 8)), 3);
+        assert_eq!(exit_code(ExitStatusExt::from_raw(9)), 137);
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{bail, Result};
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+
+/// Parses comma separated positions and ranges like 1,3-5 into zero based indexes
+///
+/// Positions are counted from 1 the same as in cut. A range like 5-1 stays reversed,
+/// it is up to the tool whether to select it backwards.
+///
+/// # Errors
+/// Error is returned when a position is not a positive number or a range has more than two parts.
+pub fn parse(ranges: &str) -> Result<Vec<RangeInclusive<usize>>> {
+    ranges.split(',').map(|x| parse_range(x.trim())).collect()
+}
+
+fn parse_range(range: &str) -> Result<RangeInclusive<usize>> {
+    let result: Result<Vec<NonZeroUsize>, _> = range.split('-').map(str::parse).collect();
+
+    // Input: inclusive range as indexes, positive indexes
+    // Output: inclusive range as range, zero-based indexes
+    let construct = |start, end| -> Result<RangeInclusive<usize>> {
+        Ok(usize::from(start) - 1..=usize::from(end) - 1)
+    };
+
+    match result {
+        Err(error) => bail!("Invalid range '{}' - {}", range, error),
+        Ok(parts) => match parts.len() {
+            1 => construct(parts[0], parts[0]),
+            2 => construct(parts[0], parts[1]),
+            _ => bail!(
+                "Invalid range '{}' - wrong number of range parts {}",
+                range,
+                parts.len()
+            ),
+        },
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_parse() {
+        assert_eq!(parse("2").unwrap(), vec![1..=1]);
+        assert_eq!(parse("1, 3-4").unwrap(), vec![0..=0, 2..=3]);
+        assert_eq!(parse("5-1").unwrap(), vec![4..=0]);
+
+        let res = parse("0");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid range '0' - number would be zero for non-zero type"
+        );
+
+        let res = parse("1-2-3");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid range '1-2-3' - wrong number of range parts 3"
+        );
+    }
+}
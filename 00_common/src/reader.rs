@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use std::io::{self, BufRead, BufReader};
+
+/// Synchronous source of records separated by a terminator byte
+///
+/// The terminator is kept at the end of the record the same way
+/// `BufRead::read_line` keeps the line ending, so output can
+/// reproduce the input byte by byte.
+pub trait RecordRead {
+    /// Appends the next record to `buf`, returns 0 at the end of input
+    ///
+    /// # Errors
+    /// Error is returned when the underlying reader fails.
+    fn read_record(&mut self, buf: &mut Vec<u8>) -> io::Result<usize>;
+}
+
+/// Asynchronous counterpart of [`RecordRead`]
+///
+/// Follow-style workloads (e.g. `tail -f` over many files) can poll
+/// thousands of sources from a single runtime thread with it.
+#[cfg(feature = "async")]
+pub trait AsyncRecordRead {
+    /// Appends the next record to `buf`, returns 0 at the end of input
+    ///
+    /// # Errors
+    /// Error is returned when the underlying reader fails.
+    fn read_record(
+        &mut self,
+        buf: &mut Vec<u8>,
+    ) -> impl std::future::Future<Output = io::Result<usize>> + Send;
+}
+
+/// Reader that splits the input into records by a terminator byte,
+/// `b'\n'` for lines and `b'\0'` for NUL-terminated records
+pub struct RecordReader<R> {
+    inner: R,
+    terminator: u8,
+}
+
+impl<R> RecordReader<R> {
+    pub fn new(inner: R, terminator: u8) -> Self {
+        Self { inner, terminator }
+    }
+
+    pub fn lines(inner: R) -> Self {
+        Self::new(inner, b'\n')
+    }
+
+    pub fn terminator(&self) -> u8 {
+        self.terminator
+    }
+
+    /// Underlying reader, e.g. to seek a file or to check its metadata
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> RecordRead for RecordReader<R> {
+    fn read_record(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.inner.read_until(self.terminator, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncBufRead + Unpin + Send> AsyncRecordRead for RecordReader<R> {
+    async fn read_record(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        use tokio::io::AsyncBufReadExt;
+        self.inner.read_until(self.terminator, buf).await
+    }
+}
+
+/// Opens file for reading, stdin is -
+///
+/// # Errors
+/// Error is returned when the file can't be opened, it includes the path.
+pub fn open(path: &str) -> Result<Box<dyn BufRead>> {
+    match path {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(
+            std::fs::File::open(path).map_err(|e| anyhow!("{path}: {e}"))?,
+        ))),
+    }
+}
+
+/// Asynchronous counterpart of [`open`], stdin is -
+///
+/// # Errors
+/// Error is returned when the file can't be opened, it includes the path.
+#[cfg(feature = "async")]
+pub async fn open_async(path: &str) -> Result<Box<dyn tokio::io::AsyncBufRead + Unpin + Send>> {
+    use tokio::io::BufReader;
+
+    match path {
+        "-" => Ok(Box::new(BufReader::new(tokio::io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(
+            tokio::fs::File::open(path)
+                .await
+                .map_err(|e| anyhow!("{path}: {e}"))?,
+        ))),
+    }
+}
+
+/// Single threaded runtime for the async mode
+///
+/// A current thread runtime is enough since the workloads are I/O bound,
+/// and it keeps the async mode as cheap to start as the sync one.
+///
+/// # Errors
+/// Error is returned when the runtime can't be created.
+#[cfg(feature = "async")]
+pub fn runtime() -> Result<tokio::runtime::Runtime> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?)
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{open, RecordRead, RecordReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_record() {
+        let mut reader = RecordReader::new(Cursor::new(b"a\0bc\0d"), b'\0');
+        let mut buf = Vec::new();
+
+        assert_eq!(reader.read_record(&mut buf).unwrap(), 2);
+        assert_eq!(buf, b"a\0");
+
+        buf.clear();
+        assert_eq!(reader.read_record(&mut buf).unwrap(), 3);
+        assert_eq!(buf, b"bc\0");
+
+        buf.clear();
+        assert_eq!(reader.read_record(&mut buf).unwrap(), 1);
+        assert_eq!(buf, b"d");
+
+        buf.clear();
+        assert_eq!(reader.read_record(&mut buf).unwrap(), 0);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_open() {
+        let res = open("missing-file.txt");
+        assert!(res.is_err());
+        assert!(res
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("missing-file.txt: "));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_read_record_async() {
+        use super::{runtime, AsyncRecordRead};
+
+        let records = runtime().unwrap().block_on(async {
+            let mut reader = RecordReader::lines(&b"one\ntwo\n"[..]);
+            let mut records = Vec::new();
+            let mut buf = Vec::new();
+
+            // Slices are both BufRead and AsyncBufRead, so the trait is named explicitly
+            while AsyncRecordRead::read_record(&mut reader, &mut buf).await.unwrap() > 0 {
+                records.push(String::from_utf8(std::mem::take(&mut buf)).unwrap());
+            }
+
+            records
+        });
+
+        assert_eq!(records, ["one\n", "two\n"]);
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::{anyhow, bail, Result};
+
+const PREFIXES: [char; 8] = ['K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y'];
+
+/// Unit system of human readable numbers like 1.5K
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    None, // 1500 - plain number, suffixes are rejected
+    Si,   // 1.5K - powers of 1000
+    Iec,  // 1.5K - powers of 1024, the way ls -h and du -h show sizes
+    IecI, // 1.5Ki - powers of 1024 with explicit i suffix
+    Auto, // K is a power of 1000 and Ki is a power of 1024, only for parsing
+}
+
+impl Scale {
+    fn base(self) -> f64 {
+        match self {
+            Scale::Si => 1000.0,
+            _ => 1024.0,
+        }
+    }
+}
+
+/// Formats the number the same way as GNU numfmt --to does
+///
+/// Scaled numbers are rounded away from zero and shown with one decimal
+/// below 10, so the result is never smaller than the real value: 1001 is 1.1K
+/// with SI units. Numbers below the base stay unscaled.
+pub fn format(value: f64, scale: Scale) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs();
+
+    if matches!(scale, Scale::None | Scale::Auto) {
+        return format!("{sign}{}", plain(value));
+    }
+
+    let base = scale.base();
+    if value < base {
+        return format!("{sign}{}", value.ceil());
+    }
+
+    let mut power = 0;
+    let mut scaled = value;
+    while scaled >= base && power < PREFIXES.len() {
+        scaled /= base;
+        power += 1;
+    }
+
+    // Rounding up may carry over into the next digit or even the next prefix
+    let mut text = if scaled < 10.0 {
+        let rounded = (scaled * 10.0).ceil() / 10.0;
+        if rounded < 10.0 {
+            format!("{rounded:.1}")
+        } else {
+            "10".to_string()
+        }
+    } else {
+        scaled.ceil().to_string()
+    };
+    if scaled.ceil() >= base && power < PREFIXES.len() {
+        power += 1;
+        text = "1.0".to_string();
+    }
+
+    let suffix = if scale == Scale::IecI { "i" } else { "" };
+    format!("{sign}{text}{}{suffix}", PREFIXES[power - 1])
+}
+
+// Whole numbers are shown without the fraction even if there was a float error
+fn plain(value: f64) -> String {
+    let rounded = value.round();
+    if (value - rounded).abs() < 1e-6 {
+        format!("{rounded}")
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Parses a number with an optional unit suffix, the same way as GNU numfmt --from does
+///
+/// # Errors
+/// Error is returned when the number is malformed or the suffix doesn't match the scale.
+pub fn parse(text: &str, scale: Scale) -> Result<f64> {
+    let split = text
+        .char_indices()
+        .find(|&(index, c)| !(c.is_ascii_digit() || c == '.' || (index == 0 && c == '-')))
+        .map_or(text.len(), |(index, _)| index);
+    let (number, suffix) = text.split_at(split);
+
+    let Ok(number) = number.parse::<f64>() else {
+        bail!("Invalid number: '{text}'");
+    };
+    if suffix.is_empty() {
+        return Ok(number);
+    }
+    if scale == Scale::None {
+        bail!("Rejecting suffix in input: '{text}' (consider using --from)");
+    }
+
+    let mut chars = suffix.chars();
+    let prefix = chars.next().expect("Suffix is not empty").to_ascii_uppercase();
+    let Some(power) = PREFIXES.iter().position(|&known| known == prefix) else {
+        bail!("Invalid suffix in input: '{text}'");
+    };
+
+    let base = match (scale, chars.as_str()) {
+        (Scale::Si, "") => 1000.0,
+        (Scale::Iec, "") => 1024.0,
+        (Scale::IecI, "i") => 1024.0,
+        (Scale::IecI, "") => bail!("Missing 'i' suffix in input: '{text}' (e.g Ki/Mi/Gi)"),
+        (Scale::Auto, "") => 1000.0,
+        (Scale::Auto, "i") => 1024.0,
+        _ => bail!("Invalid suffix in input: '{text}'"),
+    };
+
+    Ok(number * f64::powi(base, power as i32 + 1))
+}
+
+/// Parses a byte count with the suffixes of GNU dd and truncate
+///
+/// The prefixes are the ones of `Scale::Iec`, so 1K is 1024. KiB is the same as K and
+/// KB is a power of 1000. Only whole counts are accepted and they are parsed exactly.
+///
+/// # Errors
+/// Error is returned when the count is malformed or doesn't fit u64.
+pub fn parse_size(text: &str) -> Result<u64> {
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (digits, suffix) = text.split_at(split);
+    if digits.is_empty() {
+        bail!("Invalid size: '{text}'");
+    }
+
+    // B comes only after a prefix, 1B and 1iB are not sizes
+    let mut chars = suffix.chars();
+    let (base, power): (u64, u32) = match chars.next() {
+        None => (1, 0),
+        Some(prefix) => {
+            let prefix = prefix.to_ascii_uppercase();
+            let Some(power) = PREFIXES.iter().position(|&known| known == prefix) else {
+                bail!("Invalid size: '{text}'");
+            };
+            match chars.as_str() {
+                "" | "iB" => (1024, power as u32 + 1),
+                "B" => (1000, power as u32 + 1),
+                _ => bail!("Invalid size: '{text}'"),
+            }
+        }
+    };
+
+    digits
+        .parse::<u64>()
+        .ok()
+        .zip(base.checked_pow(power))
+        .and_then(|(number, multiplier)| number.checked_mul(multiplier))
+        .ok_or_else(|| anyhow!("Size is too large: '{text}'"))
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{format, parse, parse_size, Scale};
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(999.0, Scale::Si), "999");
+        assert_eq!(format(1000.0, Scale::Si), "1.0K");
+        assert_eq!(format(1001.0, Scale::Si), "1.1K");
+        assert_eq!(format(9949.0, Scale::Si), "10K");
+        assert_eq!(format(99999.0, Scale::Si), "100K");
+        assert_eq!(format(999999.0, Scale::Si), "1.0M");
+        assert_eq!(format(123456789.0, Scale::Si), "124M");
+        assert_eq!(format(-1500.0, Scale::Si), "-1.5K");
+        assert_eq!(format(1023.0, Scale::Iec), "1023");
+        assert_eq!(format(1025.0, Scale::Iec), "1.1K");
+        assert_eq!(format(1048575.0, Scale::Iec), "1.0M");
+        assert_eq!(format(1536.0, Scale::IecI), "1.5Ki");
+        assert_eq!(format(1260.0000000000002, Scale::None), "1260");
+        assert_eq!(format(1.5, Scale::None), "1.5");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("1500", Scale::None).unwrap(), 1500.0);
+        assert_eq!(parse("1.5K", Scale::Si).unwrap(), 1500.0);
+        assert_eq!(parse("2M", Scale::Si).unwrap(), 2_000_000.0);
+        assert_eq!(parse("1K", Scale::Iec).unwrap(), 1024.0);
+        assert_eq!(parse("1Ki", Scale::IecI).unwrap(), 1024.0);
+        assert_eq!(parse("1K", Scale::Auto).unwrap(), 1000.0);
+        assert_eq!(parse("1Ki", Scale::Auto).unwrap(), 1024.0);
+        assert_eq!(parse("-2k", Scale::Si).unwrap(), -2000.0);
+
+        let res = parse("abc", Scale::Si);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid number: 'abc'");
+
+        let res = parse("1K", Scale::None);
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Rejecting suffix in input: '1K' (consider using --from)"
+        );
+
+        let res = parse("1.5X", Scale::Si);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid suffix in input: '1.5X'");
+
+        let res = parse("1K", Scale::IecI);
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Missing 'i' suffix in input: '1K' (e.g Ki/Mi/Gi)"
+        );
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("1k").unwrap(), 1024);
+        assert_eq!(parse_size("1KB").unwrap(), 1000);
+        assert_eq!(parse_size("2KiB").unwrap(), 2048);
+        assert_eq!(parse_size("1G").unwrap(), 1 << 30);
+        assert_eq!(parse_size("18446744073709551615").unwrap(), u64::MAX);
+        assert_eq!(parse_size("9007199254740993K").unwrap(), (1 << 63) + 1024);
+
+        for text in ["", "K", "10.5", "-1", "1B", "1iB", "1Q", "1KK", "1Ki"] {
+            let res = parse_size(text);
+            assert!(res.is_err());
+            assert_eq!(res.unwrap_err().to_string(), format!("Invalid size: '{text}'"));
+        }
+
+        assert!(parse_size("1Y").is_err());
+
+        let res = parse_size("100E");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Size is too large: '100E'");
+
+        let res = parse_size("18446744073709551616");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Size is too large: '18446744073709551616'");
+    }
+}
@@ -1,4 +1,6 @@
+use ansi_term::{Colour, Style};
 use clap::{arg, Command};
+use std::io::{BufWriter, IsTerminal, Write};
 
 fn main() {
     let mut matches = Command::new("echo")
@@ -7,15 +9,250 @@ fn main() {
         .about("Prints arguments to the standard output")
         .args([
             arg!(<text> ... "Input text"),
-            arg!(-n --no_newline "Do not print newline")
+            arg!(-n --no_newline "Do not print newline"),
+            arg!(-z --zero "End output with NUL instead of newline, e.g. for xargs -0"),
+            arg!(-s --separator <STR> "String between arguments, -e applies to it too")
+                .default_value(" "),
+            arg!(--style "Expand markup like {red}error{reset}, see the tags below"),
+            arg!(-r --repeat <N> "Print the text N times")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+            arg!(-e --escapes "Interpret backslash escapes like \\n, \\t, \\xHH, \\0NNN and \\c")
+                .overrides_with("no_escapes"),
+            arg!(-E --no_escapes "Don't interpret backslash escapes, this is the default")
+                .overrides_with("escapes"),
         ])
+        .after_help(
+            "Style tags:\n  \
+            {black} {red} {green} {yellow} {blue} {purple} {cyan} {white}\n  \
+            {bold} {dimmed} {italic} {underline} {blink} {reverse} {reset}\n\n\
+            Tags are removed when output is not a terminal, unknown tags are printed as is",
+        )
         .get_matches();
 
     let text: Vec<String> = matches.remove_many("text").expect("No text provided").collect();
     let newline = !matches.get_flag("no_newline");
+    let escapes = matches.get_flag("escapes");
+    let ending = if matches.get_flag("zero") { b'\0' } else { b'\n' };
+    let separator: String = matches.remove_one("separator").expect("Default value is provided");
+    let repeat: usize = matches.remove_one("repeat").expect("Default value is provided");
+
+    let mut text = text.join(&separator);
+    let mut reset = String::new();
+    if matches.get_flag("style") {
+        (text, reset) = expand_style(&text, std::io::stdout().is_terminal());
+    }
+    let (mut output, stopped) = if escapes {
+        interpret_escapes(&text)
+    } else {
+        (text.into_bytes(), false)
+    };
+
+    // Reset goes after the escapes, so \c can't cut it off and leave the terminal colored
+    output.extend_from_slice(reset.as_bytes());
 
     // That difference can be spotted only in bash
     // pwsh 7.3 always adds newline even if you redirect output to a file or a variable
-    let ending = if newline { "\n" } else { "" };
-    print!("{}{}", text.join(" "), ending);
+    let terminator = (newline && !stopped).then_some(ending);
+
+    // Without the newline repetitions are joined with the separator so they don't run together
+    let delimiter = match (newline, escapes) {
+        (true, _) => vec![ending],
+        (false, true) => interpret_escapes(&separator).0,
+        (false, false) => separator.into_bytes(),
+    };
+
+    // Nothing is printed after \c, so there is nothing to repeat either
+    let repeat = if stopped { repeat.min(1) } else { repeat };
+
+    // Escapes like \xFF produce bytes that are not valid UTF-8, so they are written as is
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+    if let Err(error) = write_repeated(&mut writer, &output, repeat, &delimiter, terminator) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+// Repetitions go through one buffer, so a large count doesn't turn into a write call per copy
+fn write_repeated(
+    writer: &mut impl Write,
+    text: &[u8],
+    repeat: usize,
+    delimiter: &[u8],
+    terminator: Option<u8>,
+) -> std::io::Result<()> {
+    for index in 0..repeat {
+        if index > 0 {
+            writer.write_all(delimiter)?;
+        }
+        writer.write_all(text)?;
+    }
+
+    match terminator {
+        Some(terminator) if repeat > 0 => writer.write_all(&[terminator])?,
+        _ => {}
+    }
+
+    writer.flush()
+}
+
+/// Expands {tag} markup of --style into ANSI escape sequences
+///
+/// Tags accumulate, so {bold}{red} gives bold red text until {reset}.
+/// Styles that are still on at the end are returned as a reset that has to be printed
+/// after the text so they don't leak into the terminal.
+/// When colored is false known tags are just removed.
+fn expand_style(text: &str, colored: bool) -> (String, String) {
+    #[cfg(windows)]
+    let _ = ansi_term::enable_ansi_support();
+
+    let mut result = String::with_capacity(text.len());
+    let mut style = Style::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let tag = rest.find('}').and_then(|end| Some((apply_tag(style, &rest[1..end])?, end)));
+        let Some((next, end)) = tag else {
+            result.push('{');
+            rest = &rest[1..];
+            continue;
+        };
+
+        if colored {
+            result.push_str(&style.infix(next).to_string());
+        }
+        style = next;
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    let reset = if colored { style.suffix().to_string() } else { String::new() };
+    (result, reset)
+}
+
+fn apply_tag(style: Style, tag: &str) -> Option<Style> {
+    Some(match tag {
+        "black" => style.fg(Colour::Black),
+        "red" => style.fg(Colour::Red),
+        "green" => style.fg(Colour::Green),
+        "yellow" => style.fg(Colour::Yellow),
+        "blue" => style.fg(Colour::Blue),
+        "purple" => style.fg(Colour::Purple),
+        "cyan" => style.fg(Colour::Cyan),
+        "white" => style.fg(Colour::White),
+        "bold" => style.bold(),
+        "dimmed" => style.dimmed(),
+        "italic" => style.italic(),
+        "underline" => style.underline(),
+        "blink" => style.blink(),
+        "reverse" => style.reverse(),
+        "reset" => Style::new(),
+        _ => return None,
+    })
+}
+
+/// Interprets backslash escapes the same way as bash builtin echo -e
+///
+/// Returns the interpreted bytes and whether \c was met, it stops
+/// all the following output including the trailing newline.
+fn interpret_escapes(text: &str) -> (Vec<u8>, bool) {
+    let mut output = Vec::with_capacity(text.len());
+    let mut bytes = text.as_bytes().iter().copied().peekable();
+
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            output.push(byte);
+            continue;
+        }
+
+        let Some(escape) = bytes.next() else {
+            output.push(b'\\');
+            break;
+        };
+
+        match escape {
+            b'a' => output.push(0x07),
+            b'b' => output.push(0x08),
+            b'c' => return (output, true),
+            b'e' | b'E' => output.push(0x1b),
+            b'f' => output.push(0x0c),
+            b'n' => output.push(b'\n'),
+            b'r' => output.push(b'\r'),
+            b't' => output.push(b'\t'),
+            b'v' => output.push(0x0b),
+            b'\\' => output.push(b'\\'),
+            b'0' => {
+                let value = take_digits(&mut bytes, 8, 3);
+                output.push(value.unwrap_or(0) as u8);
+            }
+            b'x' => match take_digits(&mut bytes, 16, 2) {
+                Some(value) => output.push(value as u8),
+                None => output.extend_from_slice(b"\\x"),
+            },
+            unknown => output.extend_from_slice(&[b'\\', unknown]),
+        }
+    }
+
+    (output, false)
+}
+
+// Up to max digits in the radix, None if there were no digits at all
+fn take_digits(
+    bytes: &mut std::iter::Peekable<impl Iterator<Item = u8>>,
+    radix: u32,
+    max: usize,
+) -> Option<u32> {
+    let mut value = None;
+
+    for _ in 0..max {
+        let Some(digit) = bytes.peek().and_then(|&byte| (byte as char).to_digit(radix)) else {
+            break;
+        };
+        bytes.next();
+        value = Some(value.unwrap_or(0) * radix + digit);
+    }
+
+    value
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{expand_style, interpret_escapes};
+
+    #[test]
+    fn test_interpret_escapes() {
+        assert_eq!(interpret_escapes("a\\tb\\nc"), (b"a\tb\nc".to_vec(), false));
+        assert_eq!(interpret_escapes("back\\\\slash"), (b"back\\slash".to_vec(), false));
+        assert_eq!(interpret_escapes("\\x41\\x4a2\\xg"), (b"AJ2\\xg".to_vec(), false));
+        assert_eq!(interpret_escapes("\\0101\\0\\01234"), (b"A\0S4".to_vec(), false));
+        assert_eq!(interpret_escapes("\\xff"), (vec![0xff], false));
+        assert_eq!(interpret_escapes("stop\\c here"), (b"stop".to_vec(), true));
+        assert_eq!(interpret_escapes("\\q and \\"), (b"\\q and \\".to_vec(), false));
+    }
+
+    #[test]
+    fn test_expand_style() {
+        assert_eq!(
+            expand_style("{red}error{reset}: text", true),
+            ("\x1b[31merror\x1b[0m: text".to_string(), String::new())
+        );
+        assert_eq!(
+            expand_style("{bold}{red}x", true),
+            ("\x1b[1m\x1b[31mx".to_string(), "\x1b[0m".to_string())
+        );
+        assert_eq!(
+            expand_style("{red}error{reset}: text", false),
+            ("error: text".to_string(), String::new())
+        );
+        assert_eq!(expand_style("{red}x", false), ("x".to_string(), String::new()));
+        assert_eq!(
+            expand_style("{unknown} {red", true),
+            ("{unknown} {red".to_string(), String::new())
+        );
+        assert_eq!(expand_style("{{green}}", false), ("{}".to_string(), String::new()));
+    }
 }
@@ -1,3 +1,6 @@
+// The first tests pass their arguments as vectors
+#![allow(clippy::useless_vec)]
+
 use assert_cmd::Command;
 use std::fs;
 
@@ -39,6 +42,73 @@ fn hello2_no_newline() -> TestResult {
     run(&vec!["-n", "Hello", "there"], "tests/expected/hello2.n.txt")
 }
 
+#[test]
+fn escapes() -> TestResult {
+    run(&["-e", "Tab\\there", "new\\nline", "\\\\back"], "tests/expected/escapes.e.txt")
+}
+
+#[test]
+fn escapes_disabled() -> TestResult {
+    run(&["-E", "Tab\\there", "new\\nline"], "tests/expected/escapes.E.txt")
+}
+
+#[test]
+fn escapes_last_flag_wins() -> TestResult {
+    run(&["-e", "-E", "Tab\\there", "new\\nline"], "tests/expected/escapes.E.txt")
+}
+
+#[test]
+fn escapes_codes() -> TestResult {
+    run(&["-e", "\\x48\\x69 \\0157\\0153"], "tests/expected/codes.e.txt")
+}
+
+#[test]
+fn escapes_stop() -> TestResult {
+    run(&["-e", "Stop\\c", "never shown"], "tests/expected/stop.e.txt")
+}
+
+#[test]
+fn separator() -> TestResult {
+    run(&["--separator", ", ", "a", "b", "c"], "tests/expected/separator.comma.txt")
+}
+
+#[test]
+fn separator_escaped() -> TestResult {
+    run(&["-e", "-s", "\\n", "one", "two", "three"], "tests/expected/separator.newline.txt")
+}
+
+#[test]
+fn repeat() -> TestResult {
+    run(&["-r", "3", "Hello", "there"], "tests/expected/repeat3.txt")
+}
+
+#[test]
+fn repeat_no_newline() -> TestResult {
+    run(&["-n", "--repeat", "3", "-s", ", ", "ab"], "tests/expected/repeat3.n.txt")
+}
+
+#[test]
+fn repeat_zero() -> TestResult {
+    let mut cmd = Command::cargo_bin("echo")?;
+    cmd.args(["-r", "0", "Hello"]).assert().success().stdout("");
+    Ok(())
+}
+
+#[test]
+fn zero() -> TestResult {
+    run(&["-z", "a", "b"], "tests/expected/zero.txt")
+}
+
+#[test]
+fn zero_repeat() -> TestResult {
+    run(&["-z", "-r", "2", "x"], "tests/expected/repeat2.zero.txt")
+}
+
+#[test]
+fn style_not_terminal() -> TestResult {
+    run(&["--style", "{red}error{reset}:", "disk", "full"], "tests/expected/style.piped.txt")
+}
+
 fn run(args: &[&str], path: &str) -> TestResult {
     let expected = fs::read_to_string(path)?;
     let mut cmd = Command::cargo_bin("echo")?;
@@ -0,0 +1,64 @@
+//! Compares throughput of this cat with GNU cat found in PATH
+//!
+//! Input is a 1GB text file in the temp folder, it is generated once and reused.
+//! Size can be changed with `CAT_BENCH_SIZE=<bytes> cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const DEFAULT_SIZE: u64 = 1 << 30;
+const LINE: &str = "The quick brown fox jumps over the lazy dog\tand then sleeps for a while\n";
+
+fn input_file(size: u64) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cat-bench-{size}.txt"));
+    if path.metadata().is_ok_and(|metadata| metadata.len() == size) {
+        return path;
+    }
+
+    let mut writer = BufWriter::new(File::create(&path).expect("Can't create bench input"));
+    let mut written = 0;
+    while written < size {
+        let chunk = &LINE.as_bytes()[..LINE.len().min((size - written) as usize)];
+        writer.write_all(chunk).expect("Can't write bench input");
+        written += chunk.len() as u64;
+    }
+    writer.flush().expect("Can't write bench input");
+    path
+}
+
+fn cat(program: &str, args: &[&str], path: &Path) {
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdout(Stdio::null())
+        .status()
+        .expect("Can't run cat");
+    assert!(status.success());
+}
+
+fn throughput(c: &mut Criterion) {
+    let size = std::env::var("CAT_BENCH_SIZE")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(DEFAULT_SIZE);
+    let path = input_file(size);
+
+    let mut group = c.benchmark_group("cat");
+    group.sample_size(10).throughput(Throughput::Bytes(size));
+
+    for args in [&[][..], &["-n"], &["-A"]] {
+        let name = if args.is_empty() { "plain".to_string() } else { args.join(" ") };
+        group.bench_function(format!("this {name}"), |b| {
+            b.iter(|| cat(env!("CARGO_BIN_EXE_cat"), args, &path))
+        });
+        group.bench_function(format!("gnu {name}"), |b| b.iter(|| cat("cat", args, &path)));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
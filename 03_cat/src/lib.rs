@@ -1,14 +1,77 @@
-use std::{error::Error, io::{BufRead, BufReader}};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fs::OpenOptions,
+    io::{BufRead, BufReader, BufWriter, Cursor, Read, Write},
+};
 
 use clap::{arg, Command};
+use reverse::ReverseLines;
+
+mod reverse;
 
 type DynErrorResult<T> = Result<T, Box<dyn Error>>;
+type LineResult = std::io::Result<Vec<u8>>;
+
+// Chunk size for reading, plain concatenation copies the input in chunks of that size
+const BUFFER_SIZE: usize = 64 * 1024;
 
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
     number_lines: bool,
     number_nonblank_lines: bool,
+    number_format: NumberFormat,
+    squeeze_blank: bool,
+    show_ends: bool,
+    show_tabs: bool,
+    show_nonprinting: bool,
+    output: Option<String>,
+    append: bool,
+    quiet_errors: bool,
+    reverse: bool,
+    from: u64,
+    to: Option<u64>,
+    unbuffered: bool,
+    crlf: bool,
+    lf: bool,
+}
+
+/// How line numbers of -n and -b look, the defaults are the same as in GNU cat
+#[derive(Debug)]
+struct NumberFormat {
+    width: usize,
+    zero_pad: bool,
+    separator: String,
+    start: i64,
+    increment: i64,
+}
+
+impl NumberFormat {
+    fn write(&self, writer: &mut impl Write, number: i64) -> std::io::Result<()> {
+        let width = self.width;
+        if self.zero_pad {
+            write!(writer, "{number:0width$}{}", self.separator)
+        } else {
+            write!(writer, "{number:>width$}{}", self.separator)
+        }
+    }
+}
+
+impl Config {
+    // None of the flags needs to look at the lines
+    fn is_passthrough(&self) -> bool {
+        !(self.number_lines
+            || self.number_nonblank_lines
+            || self.squeeze_blank
+            || self.show_ends
+            || self.show_tabs
+            || self.show_nonprinting
+            || self.from > 1
+            || self.to.is_some()
+            || self.crlf
+            || self.lf)
+    }
 }
 
 /// # Errors
@@ -24,8 +87,61 @@ pub fn get_args() -> DynErrorResult<Config> {
                 .conflicts_with("number_nonblank_lines"),
             arg!(-b --number_nonblank_lines "Number only nonblank lines")
                 .conflicts_with("number_lines"),
+            arg!(--number_width <WIDTH> "Width of line numbers, leading 0 pads them with zeros")
+                .value_parser(parse_width)
+                .default_value("6"),
+            arg!(--number_separator <STR> "String between line number and line")
+                .default_value("\t"),
+            arg!(--number_start <N> "Number of the first line")
+                .value_parser(clap::value_parser!(i64))
+                .allow_hyphen_values(true)
+                .default_value("1"),
+            arg!(--number_increment <N> "Step between line numbers")
+                .value_parser(clap::value_parser!(i64))
+                .allow_hyphen_values(true)
+                .default_value("1"),
+            arg!(-s --squeeze_blank "Suppress repeated empty output lines"),
+            arg!(-E --show_ends "Display $ at end of each line"),
+            arg!(-T --show_tabs "Display TAB characters as ^I"),
+            arg!(-v --show_nonprinting "Use ^ and M- notation, except for LFD and TAB"),
+            arg!(-A --show_all "Equivalent to -vET"),
+            arg!(-o --output <FILE> "Write to the file instead of stdout, stdout is -"),
+            arg!(--append "Append to the output file instead of overwriting it")
+                .requires("output"),
+            arg!(-q --quiet_errors "Skip unreadable files silently and still exit with success"),
+            arg!(--reverse "Print lines of each file from the last to the first, like tac")
+                .conflicts_with_all(["from", "to"]),
+            arg!(--from <N> "Print lines of each file starting from this one")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("1"),
+            arg!(--to <M> "Print lines of each file up to this one, inclusive")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+            arg!(-u --unbuffered "Flush output after every line, e.g. to watch a slow pipe"),
+            arg!(--crlf "Convert line endings to Windows CRLF").conflicts_with("lf"),
+            arg!(--lf "Convert line endings to Unix LF").conflicts_with("crlf"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let (width, zero_pad) = matches.remove_one("number_width").expect("Default value is provided");
+    let number_format = NumberFormat {
+        width,
+        zero_pad,
+        separator: matches
+            .remove_one("number_separator")
+            .expect("Default value is provided"),
+        start: matches
+            .remove_one("number_start")
+            .expect("Default value is provided"),
+        increment: matches
+            .remove_one("number_increment")
+            .expect("Default value is provided"),
+    };
+
+    let from = matches.remove_one("from").expect("Default value is provided");
+    let to: Option<u64> = matches.remove_one("to");
+    if let Some(to) = to.filter(|&to| to < from) {
+        return Err(format!("Line range --from {from} --to {to} is empty").into());
+    }
 
     Ok(Config {
         files: matches
@@ -34,43 +150,361 @@ pub fn get_args() -> DynErrorResult<Config> {
             .collect(),
         number_lines: matches.get_flag("number_lines"),
         number_nonblank_lines: matches.get_flag("number_nonblank_lines"),
+        number_format,
+        squeeze_blank: matches.get_flag("squeeze_blank"),
+        show_ends: matches.get_flag("show_ends") || matches.get_flag("show_all"),
+        show_tabs: matches.get_flag("show_tabs") || matches.get_flag("show_all"),
+        show_nonprinting: matches.get_flag("show_nonprinting") || matches.get_flag("show_all"),
+        output: matches.remove_one("output"),
+        append: matches.get_flag("append"),
+        quiet_errors: matches.get_flag("quiet_errors"),
+        reverse: matches.get_flag("reverse"),
+        from,
+        to,
+        unbuffered: matches.get_flag("unbuffered"),
+        crlf: matches.get_flag("crlf"),
+        lf: matches.get_flag("lf"),
     })
 }
 
+// Width like 03 means zero padding, the same way as in printf
+fn parse_width(text: &str) -> Result<(usize, bool), String> {
+    let width = text
+        .parse()
+        .map_err(|error| format!("Invalid width '{text}' - {error}"))?;
+    Ok((width, text.len() > 1 && text.starts_with('0')))
+}
+
 /// # Errors
 /// Error is returned when program could not process files
 pub fn run(config: Config) -> DynErrorResult<()> {
+    // Formatting every line straight into stdout is slow, output is buffered instead
+    let mut writer = BufWriter::with_capacity(BUFFER_SIZE, open_write(&config)?);
+    let output = config
+        .output
+        .as_ref()
+        .filter(|path| *path != "-")
+        .and_then(|path| std::fs::canonicalize(path).ok());
+
+    let mut failed = false;
+    let mut report = |message: String| {
+        failed = true;
+        if !config.quiet_errors {
+            eprintln!("{message}");
+        }
+    };
+
+    // Stdin is shared by all - occurrences, so whatever it has buffered is not lost between them
+    // and an interactive stdin is read until Ctrl+D once per occurrence
+    let input: Box<dyn Read> = Box::new(std::io::stdin());
+    let mut stdin = BufReader::with_capacity(BUFFER_SIZE, input);
+
+    // Numbering and squeezing continue from one input to the next, the same as in GNU cat
+    let mut state = LineState::new(&config);
+
     for path in &config.files {
-        match open(path) {
-            Ok(reader) => process(reader, &config),
-            Err(error) => eprintln!("Can't open file {path}, error {error}"),
+        // Reading the output file would never end when appending to it
+        if path != "-" && output.is_some() && std::fs::canonicalize(path).ok() == output {
+            report(format!("Can't read file {path}, it is also the output file"));
+            continue;
+        }
+
+        let result = if config.reverse {
+            open_reverse(path, &mut stdin)
+                .map(|lines| process_reverse(lines, &mut state, &mut writer, &config))
+        } else if path == "-" {
+            Ok(process(&mut stdin, &mut state, &mut writer, &config))
+        } else {
+            open(path).map(|mut reader| process(&mut reader, &mut state, &mut writer, &config))
+        };
+
+        match result {
+            Ok(Err(error)) => report(format!("Can't read file {path}, error {error}")),
+            Err(error) => report(format!("Can't open file {path}, error {error}")),
+            Ok(Ok(())) => {}
         }
     }
+
+    writer.flush()?;
+
+    // The rest of the files is still concatenated, only the exit code tells about the failure
+    if failed && !config.quiet_errors {
+        std::process::exit(1);
+    }
+
     Ok(())
 }
 
-fn open(path: &str) -> DynErrorResult<Box<dyn BufRead>> {
-    match path {
-        "-" => Ok(Box::new(BufReader::new(std::io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(std::fs::File::open(path)?)))
-    }
+// Concrete BufReader is returned so that process can check whether the input is drained
+fn open(path: &str) -> DynErrorResult<BufReader<Box<dyn Read>>> {
+    let input: Box<dyn Read> = Box::new(std::fs::File::open(path)?);
+    Ok(BufReader::with_capacity(BUFFER_SIZE, input))
 }
 
-fn process(mut reader: Box<dyn BufRead>, config: &Config) {
-    let mut buf = String::new();
-    let mut index = 0;
+// Stdin can't be read backwards, so it is the only input that is loaded into memory as a whole
+fn open_reverse(
+    path: &str,
+    stdin: &mut impl Read,
+) -> DynErrorResult<Box<dyn Iterator<Item = LineResult>>> {
+    if path == "-" {
+        let mut input = Vec::new();
+        stdin.read_to_end(&mut input)?;
+        return Ok(Box::new(ReverseLines::new(Cursor::new(input))?));
+    }
+    Ok(Box::new(ReverseLines::new(std::fs::File::open(path)?)?))
+}
 
-    while let Ok(read_bytes) = reader.read_line(&mut buf) {
-        if read_bytes == 0 {
-            break;
+// Same approach as in uniq, - or no path at all means stdout
+fn open_write(config: &Config) -> DynErrorResult<Box<dyn Write>> {
+    match &config.output {
+        Some(path) if path != "-" => {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .append(config.append)
+                .truncate(!config.append)
+                .open(path)
+                .map_err(|error| format!("Can't open output file {path}, error {error}"))?;
+            Ok(Box::new(file))
         }
+        _ => Ok(Box::new(std::io::stdout().lock())),
+    }
+}
+
+fn process(
+    reader: &mut BufReader<Box<dyn Read>>,
+    state: &mut LineState,
+    writer: &mut impl Write,
+    config: &Config,
+) -> DynErrorResult<()> {
+    // Plain concatenation copies chunks as is, so binary files stay intact and it's fast
+    if config.is_passthrough() {
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                return Ok(());
+            }
+            writer.write_all(chunk)?;
+            let length = chunk.len();
+            reader.consume(length);
 
-        if config.number_lines || (config.number_nonblank_lines && !buf.trim().is_empty()) {
-            index += 1;
-            print!("{index:>6}\t");
+            // Whatever was read is shown before waiting for more, e.g. for interactive stdin,
+            // so -u has nothing to add here
+            writer.flush()?;
         }
+    }
+
+    let mut buf = Vec::new();
+    let mut line_number = 0;
 
-        print!("{buf}");
+    // Raw bytes are read since -v has to show bytes that are not valid UTF-8
+    // Nothing is read after the --to line, so slicing the head of a big file is fast
+    while config.to.is_none_or(|to| line_number < to) && reader.read_until(b'\n', &mut buf)? > 0 {
+        line_number += 1;
+        if line_number >= config.from {
+            state.write_line(&buf, config, writer)?;
+        }
         buf.clear();
+
+        // Input is drained and the next read may block, so the buffered lines are shown now
+        if config.unbuffered || reader.buffer().is_empty() {
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn process_reverse(
+    lines: impl Iterator<Item = LineResult>,
+    state: &mut LineState,
+    writer: &mut impl Write,
+    config: &Config,
+) -> DynErrorResult<()> {
+    for line in lines {
+        state.write_line(&line?, config, writer)?;
+        if config.unbuffered {
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// What -n, -b and -s remember from the previous lines
+struct LineState {
+    number: Option<i64>, // None after the numbers went past the range of i64
+    previous_empty: bool,
+}
+
+impl LineState {
+    fn new(config: &Config) -> Self {
+        Self {
+            number: Some(config.number_format.start),
+            previous_empty: false,
+        }
+    }
+
+    fn write_line(
+        &mut self,
+        line: &[u8],
+        config: &Config,
+        writer: &mut impl Write,
+    ) -> std::io::Result<()> {
+        let line: &[u8] = &convert_ending(line, config);
+
+        // Only lines without any characters are squeezed, the same as in GNU cat
+        let empty = line == b"\n" || line == b"\r\n";
+        if config.squeeze_blank && empty && self.previous_empty {
+            return Ok(());
+        }
+        self.previous_empty = empty;
+
+        let blank = line.iter().all(u8::is_ascii_whitespace);
+        if config.number_lines || (config.number_nonblank_lines && !blank) {
+            let Some(number) = self.number else {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "line number is out of range",
+                ));
+            };
+            config.number_format.write(writer, number)?;
+            self.number = number.checked_add(config.number_format.increment);
+        }
+
+        show_line(line, config, writer)
+    }
+}
+
+// Line endings of --crlf and --lf, the last line without a newline doesn't get one
+fn convert_ending<'a>(line: &'a [u8], config: &Config) -> Cow<'a, [u8]> {
+    let ending: &[u8] = match (config.crlf, config.lf) {
+        (true, _) => b"\r\n",
+        (_, true) => b"\n",
+        _ => return Cow::Borrowed(line),
+    };
+    let Some(content) = line.strip_suffix(b"\n") else {
+        return Cow::Borrowed(line);
+    };
+
+    let content = content.strip_suffix(b"\r").unwrap_or(content);
+    if line.len() == content.len() + ending.len() {
+        return Cow::Borrowed(line);
+    }
+    Cow::Owned([content, ending].concat())
+}
+
+/// Writes the line the way -E, -T and -v ask to show it
+fn show_line(line: &[u8], config: &Config, output: &mut impl Write) -> std::io::Result<()> {
+    // Lines that need no changes are written in one call
+    if !(config.show_ends || config.show_tabs || config.show_nonprinting) {
+        return output.write_all(line);
+    }
+
+    let content = line.strip_suffix(b"\n");
+    let mut visible = content.unwrap_or(line);
+
+    // Carriage return of CRLF is visible with -E, the same as in GNU cat 9
+    let crlf = config.show_ends && content.is_some_and(|content| content.ends_with(b"\r"));
+    if crlf {
+        visible = &visible[..visible.len() - 1];
+    }
+
+    for &byte in visible {
+        match byte {
+            b'\t' if config.show_tabs => output.write_all(b"^I")?,
+            b'\t' => output.write_all(&[byte])?,
+            _ if config.show_nonprinting => show_nonprinting(byte, output)?,
+            _ => output.write_all(&[byte])?,
+        }
+    }
+
+    if content.is_some() {
+        if crlf {
+            output.write_all(b"^M")?;
+        }
+        if config.show_ends {
+            output.write_all(b"$")?;
+        }
+        output.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+// Caret notation of GNU cat -v, bytes with the high bit set get the M- prefix
+fn show_nonprinting(byte: u8, output: &mut impl Write) -> std::io::Result<()> {
+    let byte = if byte >= 128 {
+        output.write_all(b"M-")?;
+        byte - 128
+    } else {
+        byte
+    };
+
+    match byte {
+        0..=31 => output.write_all(&[b'^', byte + 64]),
+        127 => output.write_all(b"^?"),
+        _ => output.write_all(&[byte]),
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_width, show_line, Config, NumberFormat};
+
+    fn show(line: &[u8], show_ends: bool, show_tabs: bool, show_nonprinting: bool) -> Vec<u8> {
+        let config = Config {
+            files: Vec::new(),
+            number_lines: false,
+            number_nonblank_lines: false,
+            number_format: NumberFormat {
+                width: 6,
+                zero_pad: false,
+                separator: "\t".to_string(),
+                start: 1,
+                increment: 1,
+            },
+            squeeze_blank: false,
+            show_ends,
+            show_tabs,
+            show_nonprinting,
+            output: None,
+            append: false,
+            quiet_errors: false,
+            reverse: false,
+            from: 1,
+            to: None,
+            unbuffered: false,
+            crlf: false,
+            lf: false,
+        };
+        let mut output = Vec::new();
+        show_line(line, &config, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_show_line() {
+        assert_eq!(show(b"a\tb\r\n", false, false, false), b"a\tb\r\n");
+        assert_eq!(show(b"a\tb\r\n", true, false, false), b"a\tb^M$\n");
+        assert_eq!(show(b"a\tb\r\n", false, true, false), b"a^Ib\r\n");
+        assert_eq!(show(b"a\tb\r\n", true, true, true), b"a^Ib^M$\n");
+        assert_eq!(show(b"no newline", true, false, false), b"no newline");
+        assert_eq!(show(b"\0\x1b\x7f", false, false, true), b"^@^[^?");
+        assert_eq!(show(b"\x80\xe2\xff\t", false, false, true), b"M-^@M-bM-^?\t");
+    }
+
+    #[test]
+    fn test_parse_width() {
+        assert_eq!(parse_width("6").unwrap(), (6, false));
+        assert_eq!(parse_width("03").unwrap(), (3, true));
+        assert_eq!(parse_width("0").unwrap(), (0, false));
+
+        let res = parse_width("wide");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err(),
+            "Invalid width 'wide' - invalid digit found in string"
+        );
     }
 }
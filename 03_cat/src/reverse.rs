@@ -0,0 +1,108 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Lines of a seekable input from the last one to the first, the way tac shows them
+///
+/// The input is read backwards in chunks, so however big the file is only
+/// the current chunk and the line that crosses the chunk border stay in memory.
+/// Chunks are kept apart and every byte is scanned once, so long lines stay linear.
+/// Every line keeps its newline, the last line may have none.
+pub struct ReverseLines<R> {
+    input: R,
+    chunk_size: usize,
+    position: u64,
+    // Bytes that are not returned yet, the last chunk goes first in the input
+    chunks: Vec<Vec<u8>>,
+    // Length of the start of the first chunk that may still contain a line break
+    unscanned: usize,
+}
+
+impl<R: Read + Seek> ReverseLines<R> {
+    pub fn new(input: R) -> io::Result<Self> {
+        Self::with_chunk_size(input, CHUNK_SIZE)
+    }
+
+    fn with_chunk_size(mut input: R, chunk_size: usize) -> io::Result<Self> {
+        let position = input.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            input,
+            chunk_size,
+            position,
+            chunks: Vec::new(),
+            unscanned: 0,
+        })
+    }
+
+    // Adds the chunk that goes before the kept bytes
+    fn read_previous_chunk(&mut self) -> io::Result<()> {
+        let size = self.position.min(self.chunk_size as u64);
+        self.position -= size;
+
+        let mut chunk = vec![0; size as usize];
+        self.input.seek(SeekFrom::Start(self.position))?;
+        self.input.read_exact(&mut chunk)?;
+
+        // Newline at the very end belongs to the last line, it doesn't start a new one
+        self.unscanned = if self.chunks.is_empty() { chunk.len() - 1 } else { chunk.len() };
+        self.chunks.push(chunk);
+        Ok(())
+    }
+
+    fn next_line(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            let first = self.chunks.last().map_or(&[][..], |chunk| &chunk[..self.unscanned]);
+            if let Some(index) = first.iter().rposition(|&byte| byte == b'\n') {
+                let mut first = self.chunks.pop().expect("Line break is in the first chunk");
+                let mut line = first.split_off(index + 1);
+                line.extend(self.chunks.drain(..).rev().flatten());
+
+                // The line break that is left ends the previous line
+                self.chunks.push(first);
+                self.unscanned = index;
+                return Ok(Some(line));
+            }
+
+            if self.position == 0 {
+                let rest: Vec<u8> = self.chunks.drain(..).rev().flatten().collect();
+                return Ok((!rest.is_empty()).then_some(rest));
+            }
+
+            self.read_previous_chunk()?;
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for ReverseLines<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_line().transpose()
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::ReverseLines;
+    use std::io::Cursor;
+
+    fn reverse(text: &str, chunk_size: usize) -> Vec<String> {
+        ReverseLines::with_chunk_size(Cursor::new(text.as_bytes()), chunk_size)
+            .unwrap()
+            .map(|line| String::from_utf8(line.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_reverse_lines() {
+        for chunk_size in [1, 2, 3, 100] {
+            assert_eq!(reverse("a\nbb\nccc\n", chunk_size), ["ccc\n", "bb\n", "a\n"]);
+            assert_eq!(reverse("a\nbb\nccc", chunk_size), ["ccc", "bb\n", "a\n"]);
+            assert_eq!(reverse("\n\nx\n", chunk_size), ["x\n", "\n", "\n"]);
+            assert_eq!(reverse("single", chunk_size), ["single"]);
+            assert_eq!(reverse("long line\nx\n", chunk_size), ["x\n", "long line\n"]);
+            assert!(reverse("", chunk_size).is_empty());
+        }
+    }
+}
@@ -11,6 +11,10 @@ const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const SPIDERS: &str = "tests/inputs/spiders.txt";
 const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const BLANKS: &str = "tests/inputs/blanks.txt";
+const SPECIAL: &str = "tests/inputs/special.txt";
+const BINARY: &str = "tests/inputs/binary.bin";
+const MIXED: &str = "tests/inputs/mixed.txt";
 
 // ------------------------------------------------------------------------------------
 #[test]
@@ -46,16 +50,30 @@ fn skips_bad_file() -> TestResult {
     let missing = gen_missing_file();
     let expected = format!("{missing}.*\\bos error 2\\b");
     Command::cargo_bin(PRG)?
-        .arg(&missing)
+        .args([&missing, FOX])
         .assert()
-        .success()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?)
         .stderr(predicate::str::is_match(expected)?);
     Ok(())
 }
 
+// ------------------------------------------------------------------------------------
+#[test]
+fn skips_bad_file_quietly() -> TestResult {
+    let missing = gen_missing_file();
+    Command::cargo_bin(PRG)?
+        .args(["--quiet_errors", &missing, FOX])
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?)
+        .stderr("");
+    Ok(())
+}
+
 // ------------------------------------------------------------------------------------
 fn run(args: &[&str], expected_file: &str) -> TestResult {
-    let expected = fs::read_to_string(expected_file)?;
+    let expected = fs::read(expected_file)?;
     Command::cargo_bin(PRG)?
         .args(args)
         .assert()
@@ -199,3 +217,307 @@ fn all_n() -> TestResult {
 fn all_b() -> TestResult {
     run(&[FOX, SPIDERS, BUSTLE, "-b"], "tests/expected/all.b.out")
 }
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn blanks() -> TestResult {
+    run(&[BLANKS], "tests/expected/blanks.txt.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn blanks_s() -> TestResult {
+    run(&["-s", BLANKS], "tests/expected/blanks.txt.s.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn blanks_sn() -> TestResult {
+    run(&["--squeeze_blank", "-n", BLANKS], "tests/expected/blanks.txt.sn.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_ends() -> TestResult {
+    run(&["-E", SPECIAL], "tests/expected/special.txt.E.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_tabs() -> TestResult {
+    run(&["-T", SPECIAL], "tests/expected/special.txt.T.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_nonprinting() -> TestResult {
+    run(&["-v", SPECIAL], "tests/expected/special.txt.v.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_all() -> TestResult {
+    run(&["-A", SPECIAL], "tests/expected/special.txt.A.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_all_n() -> TestResult {
+    run(&["-n", "--show_all", SPECIAL], "tests/expected/special.txt.nA.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn binary() -> TestResult {
+    run(&[BINARY], BINARY)
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn binary_stdin() -> TestResult {
+    let input = fs::read(BINARY)?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(input.clone())
+        .assert()
+        .success()
+        .stdout(input);
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("cat-{}-{name}", std::process::id()))
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn output_file() -> TestResult {
+    let path = temp_file("output");
+    fs::write(&path, "previous content\n")?;
+
+    Command::cargo_bin(PRG)?
+        .args([FOX, SPIDERS, BUSTLE, "-o"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(fs::read_to_string(&path)?, fs::read_to_string("tests/expected/all.out")?);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn output_file_append() -> TestResult {
+    let path = temp_file("append");
+    let _ = fs::remove_file(&path);
+
+    for file in [FOX, SPIDERS, BUSTLE] {
+        Command::cargo_bin(PRG)?
+            .args([file, "--append", "--output"])
+            .arg(&path)
+            .assert()
+            .success();
+    }
+
+    assert_eq!(fs::read_to_string(&path)?, fs::read_to_string("tests/expected/all.out")?);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn output_file_is_input() -> TestResult {
+    let path = temp_file("same");
+    fs::write(&path, "content\n")?;
+
+    Command::cargo_bin(PRG)?
+        .arg(&path)
+        .args([FOX, "--append", "-o"])
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("it is also the output file"));
+
+    let expected = format!("content\n{}", fs::read_to_string(FOX)?);
+    assert_eq!(fs::read_to_string(&path)?, expected);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn dies_append_without_output() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--append", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--output <FILE>"));
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn spiders_n_zero_padded() -> TestResult {
+    run(
+        &["-n", "--number_width", "03", "--number_separator", ": ", SPIDERS],
+        "tests/expected/spiders.txt.n.zero3.out",
+    )
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_b_start_increment() -> TestResult {
+    run(
+        &[
+            "-b",
+            "--number_start=10",
+            "--number_increment=10",
+            "--number_width=4",
+            "--number_separator= | ",
+            BUSTLE,
+        ],
+        "tests/expected/the-bustle.txt.b.start10.out",
+    )
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn number_out_of_range() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--number_start", &i64::MAX.to_string()])
+        .write_stdin("a\nb\n")
+        .assert()
+        .failure()
+        .stdout(format!("{:>6}\ta\n", i64::MAX))
+        .stderr(predicate::str::contains("line number is out of range"));
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_reverse() -> TestResult {
+    run(&["--reverse", BUSTLE], "tests/expected/the-bustle.txt.reverse.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_stdin_reverse() -> TestResult {
+    run_stdin(BUSTLE, &["--reverse"], "tests/expected/the-bustle.txt.reverse.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn empty_reverse() -> TestResult {
+    run(&["--reverse", EMPTY], "tests/expected/empty.txt.reverse.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn blanks_reverse_sn() -> TestResult {
+    run(&["--reverse", "-sn", BLANKS], "tests/expected/blanks.txt.reverse.sn.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn spiders_fox_reverse() -> TestResult {
+    run(&["--reverse", SPIDERS, FOX], "tests/expected/spiders_fox.reverse.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_from_to() -> TestResult {
+    run(&["--from", "3", "--to", "5", BUSTLE], "tests/expected/the-bustle.txt.from3.to5.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_from_n() -> TestResult {
+    run(&["--from=2", "-n", BUSTLE], "tests/expected/the-bustle.txt.from2.n.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_stdin_to() -> TestResult {
+    run_stdin(BUSTLE, &["--to=2"], "tests/expected/the-bustle.txt.to2.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn fox_from_after_end() -> TestResult {
+    run(&["--from", "5", FOX], "tests/expected/empty.txt.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn dies_empty_line_range() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--from", "5", "--to", "4", FOX])
+        .assert()
+        .failure()
+        .stderr("Line range --from 5 --to 4 is empty\n");
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_stdin_unbuffered_n() -> TestResult {
+    run_stdin(BUSTLE, &["-u", "-n"], "tests/expected/the-bustle.txt.n.stdin.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_reverse_unbuffered() -> TestResult {
+    run(&["--unbuffered", "--reverse", BUSTLE], "tests/expected/the-bustle.txt.reverse.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn mixed_lf() -> TestResult {
+    run(&["--lf", MIXED], "tests/expected/mixed.txt.lf.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn mixed_lf_ends() -> TestResult {
+    run(&["--lf", "-E", MIXED], "tests/expected/mixed.txt.lf.E.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn mixed_crlf() -> TestResult {
+    run(&["--crlf", MIXED], "tests/expected/mixed.txt.crlf.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn special_crlf_keeps_last_line() -> TestResult {
+    let output = Command::cargo_bin(PRG)?.args(["--crlf", SPECIAL]).output()?;
+    assert!(output.stdout.ends_with(b"\r\nLast line without newline"));
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn dies_crlf_and_lf() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--crlf", "--lf", MIXED])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn stdin_twice_n() -> TestResult {
+    run_stdin(SPIDERS, &["-n", "-", FOX, "-"], "tests/expected/spiders_stdin_fox_stdin.n.out")
+}
+
+// ------------------------------------------------------------------------------------
+#[test]
+fn bustle_stdin_twice_to() -> TestResult {
+    // Second - continues where the first one stopped, buffered input is not lost
+    run_stdin(BUSTLE, &["--to=1", "-", "-"], "tests/expected/the-bustle.txt.to1.twice.out")
+}
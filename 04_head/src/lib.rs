@@ -1,16 +1,24 @@
-use std::{error::Error, io::{BufRead, BufReader}};
+use std::{collections::VecDeque, error::Error, io::{BufRead, BufReader, Read, Write}};
 use clap::{arg, Command};
-
-const PAGE_SIZE: usize = 4096;
-const BUFFER_SIZE: usize = PAGE_SIZE * 2;
+use rayon::prelude::*;
 
 type DynErrorResult<T> = Result<T, Box<dyn Error>>;
 
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
-    lines: usize,
-    bytes: Option<usize>,
+    lines: Count,
+    bytes: Option<Count>,
+    terminator: u8,
+    header_format: String,
+    skip: usize,
+    parallel: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Count {
+    First(usize),      // 5 - print the first lines or bytes
+    AllButLast(usize), // -5 - print everything except the last lines or bytes, as GNU head does
 }
 
 pub fn get_args() -> DynErrorResult<Config> {
@@ -21,15 +29,26 @@ pub fn get_args() -> DynErrorResult<Config> {
         .args([
             arg!([FILES] ... "Files to preview, stdin is -")
                 .default_value("-"),
-            arg!(-n --lines <LINES> "Number of lines to show")
-                .value_parser(clap::value_parser!(usize))
+            arg!(-n --lines <LINES> "Number of lines to show, -N shows all but the last N lines")
+                .value_parser(parse_count)
+                .allow_hyphen_values(true)
                 .default_value("10")
                 .conflicts_with("bytes"),
-            arg!(-c --bytes <BYTES> "Number of bytes to show")
-                .value_parser(clap::value_parser!(usize))
+            arg!(-c --bytes <BYTES> "Number of bytes to show, -N shows all but the last N bytes")
+                .value_parser(parse_count)
+                .allow_hyphen_values(true)
                 .conflicts_with("lines"),
+            arg!(--skip <N> "Drop the first N lines before showing the next ones, e.g. to page")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("0")
+                .conflicts_with("bytes"),
+            arg!(-P --parallel "Read files in parallel, previews are shown in the given order"),
+            arg!(--header_format <FORMAT> "Header of multiple files, {path} and {n} are replaced")
+                .allow_hyphen_values(true)
+                .default_value(common::header::DEFAULT_FORMAT),
+            arg!(-z --zero_terminated "Lines end with NUL instead of newline, e.g. for -print0"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     Ok(Config {
         files: matches
@@ -41,33 +60,120 @@ pub fn get_args() -> DynErrorResult<Config> {
             .expect("No number of lines provided"),
         bytes: matches
             .remove_one("bytes"),
+        terminator: if matches.get_flag("zero_terminated") { b'\0' } else { b'\n' },
+        header_format: matches
+            .remove_one("header_format")
+            .expect("Default value is provided"),
+        skip: matches.remove_one("skip").expect("Default value is provided"),
+        parallel: matches.get_flag("parallel"),
     })
 }
 
+fn parse_count(text: &str) -> Result<Count, std::num::ParseIntError> {
+    match text.strip_prefix('-') {
+        Some(count) => Ok(Count::AllButLast(count.parse()?)),
+        None => Ok(Count::First(text.parse()?)),
+    }
+}
+
 // cargo run -- -n (ls .\tests\inputs\*.txt)
 // cargo run -- -n (walker .\tests\inputs\ -a)
 pub fn run(config: Config) -> DynErrorResult<()> {
-    let mutifile_handling = config.files.len() > 1;
-    let mut entry_separator_needed = false;
+    let failed = if config.parallel {
+        show_parallel(&config)?
+    } else {
+        show_serial(&config)?
+    };
 
-    for path in &config.files {
-        if entry_separator_needed {
-            println!();
-        }
+    // The rest of the files is still shown, only the exit code tells about the failure
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+// Returns whether some of the files could not be shown
+fn show_serial(config: &Config) -> DynErrorResult<bool> {
+    let mut stdout = std::io::stdout();
+    let mut shown = 0;
+    let mut failed = false;
 
-        entry_separator_needed = true;
+    for (index, path) in config.files.iter().enumerate() {
+        // Files that can't be opened get no header, the same as in GNU head
+        let reader = match open(path) {
+            Ok(reader) => reader,
+            Err(error) => {
+                eprintln!("Can't open file '{}', error {}", &path, error);
+                failed = true;
+                continue;
+            }
+        };
 
-        if mutifile_handling {
-            println!("==> {path} <==");
+        print_header(config, path, index, shown);
+        shown += 1;
+
+        if let Err(error) = process_file(reader, &mut stdout, config) {
+            eprintln!("Can't read file '{path}', error {error}");
+            failed = true;
         }
+    }
+
+    Ok(failed)
+}
+
+// Files are read in parallel, but previews are shown in the order of the arguments
+fn show_parallel(config: &Config) -> DynErrorResult<bool> {
+    let previews: Vec<_> = config
+        .files
+        .par_iter()
+        .with_max_len(1)
+        .map(|path| preview(path, config))
+        .collect();
+
+    let mut stdout = std::io::stdout();
+    let mut shown = 0;
+    let mut failed = false;
+
+    for (index, (path, preview)) in config.files.iter().zip(previews).enumerate() {
+        let (content, error) = match preview {
+            Ok(preview) => preview,
+            Err(error) => {
+                eprintln!("Can't open file '{path}', error {error}");
+                failed = true;
+                continue;
+            }
+        };
+
+        print_header(config, path, index, shown);
+        shown += 1;
+        stdout.write_all(&content)?;
 
-        match open(path) {
-            Ok(reader) => process_file(path, reader, &config),
-            Err(error) => eprintln!("Can't open file '{}', error {}", &path, error),
+        if let Some(error) = error {
+            eprintln!("Can't read file '{path}', error {error}");
+            failed = true;
         }
     }
 
-    Ok(())
+    Ok(failed)
+}
+
+// Preview is kept in memory until its turn comes, a read error still shows what was read before it
+fn preview(path: &str, config: &Config) -> Result<(Vec<u8>, Option<std::io::Error>), String> {
+    let reader = open(path).map_err(|error| error.to_string())?;
+    let mut content = Vec::new();
+    let result = process_file(reader, &mut content, config);
+    Ok((content, result.err()))
+}
+
+fn print_header(config: &Config, path: &str, index: usize, shown: usize) {
+    if shown > 0 {
+        println!();
+    }
+
+    if config.files.len() > 1 {
+        println!("{}", common::header::format(&config.header_format, path, index + 1));
+    }
 }
 
 fn open(path: &str) -> DynErrorResult<Box<dyn BufRead>> {
@@ -77,32 +183,121 @@ fn open(path: &str) -> DynErrorResult<Box<dyn BufRead>> {
     }
 }
 
-fn process_file(path: &str, mut reader: Box<dyn BufRead>, config: &Config) {
-    let buffer_size = config.bytes.unwrap_or(BUFFER_SIZE);
-    let mut buffer = vec![0; buffer_size];
+// Input is read only as far as needed, however long its lines are
+fn process_file(
+    mut reader: Box<dyn BufRead>,
+    writer: &mut impl Write,
+    config: &Config,
+) -> std::io::Result<()> {
+    skip_lines(&mut reader, config.skip, config.terminator)?;
+
+    match (config.bytes, config.lines) {
+        (Some(Count::First(count)), _) => write_first_bytes(&mut reader, writer, count),
+        (None, Count::First(count)) => {
+            write_first_lines(&mut reader, writer, count, config.terminator)
+        }
+        (Some(Count::AllButLast(count)), _) => {
+            write_all_but_last_bytes(&mut reader, writer, count)
+        }
+        (None, Count::AllButLast(count)) => {
+            write_all_but_last_lines(&mut reader, writer, count, config.terminator)
+        }
+    }
+}
+
+// Skipped lines are not kept in memory, however long they are
+fn skip_lines(reader: &mut impl BufRead, mut count: usize, terminator: u8) -> std::io::Result<()> {
+    while count > 0 {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        let length = match chunk.iter().position(|&byte| byte == terminator) {
+            Some(index) => {
+                count -= 1;
+                index + 1
+            }
+            None => chunk.len(),
+        };
+        reader.consume(length);
+    }
+
+    Ok(())
+}
+
+// Bytes are copied as is, so a multibyte character may be cut the same way as in GNU head
+fn write_first_bytes(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    count: usize,
+) -> std::io::Result<()> {
+    std::io::copy(&mut reader.by_ref().take(count as u64), writer)?;
+    Ok(())
+}
 
-    // Alternatively we could do the following:
-    // reader.bytes().take(buffer_size as u64);
+fn write_first_lines(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    count: usize,
+    terminator: u8,
+) -> std::io::Result<()> {
+    let mut line = Vec::new();
 
-    match reader.read(&mut buffer) {
-        Ok(len) => process_buffer(&buffer[0..len], config),
-        Err(error) => eprintln!("Can't open file '{path}', error {error}"),
+    for _ in 0..count {
+        if reader.read_until(terminator, &mut line)? == 0 {
+            break;
+        }
+        writer.write_all(&line)?;
+        line.clear();
     }
+
+    Ok(())
 }
 
-fn process_buffer(buffer: &[u8], config: &Config) {
-    let text = String::from_utf8_lossy(buffer);
-    let mut line_count = 0;
+// The end of the input is not known in advance, so the last count lines are held back
+// in a ring buffer, a line is written once it is known not to be one of them
+fn write_all_but_last_lines(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    count: usize,
+    terminator: u8,
+) -> std::io::Result<()> {
+    let mut window = VecDeque::new();
 
-    for char in text.chars() {
-        print!("{char}");
+    loop {
+        let mut line = Vec::new();
+        if reader.read_until(terminator, &mut line)? == 0 {
+            return Ok(());
+        }
 
-        if char == '\n' {
-            line_count += 1;
+        window.push_back(line);
+        if window.len() > count {
+            writer.write_all(&window.pop_front().expect("Window is not empty"))?;
         }
+    }
+}
 
-        if line_count >= config.lines {
-            break;
+// Same as for lines, only the last count bytes are kept in memory
+fn write_all_but_last_bytes(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    count: usize,
+) -> std::io::Result<()> {
+    let mut window = VecDeque::new();
+
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        window.extend(chunk);
+        let length = chunk.len();
+        reader.consume(length);
+
+        if window.len() > count {
+            let excess: Vec<u8> = window.drain(..window.len() - count).collect();
+            writer.write_all(&excess)?;
         }
     }
-}
\ No newline at end of file
+}
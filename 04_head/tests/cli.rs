@@ -1,11 +1,7 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::io::prelude::*;
-use std::{
-    error::Error,
-    fs::{self, File},
-};
+use std::{error::Error, fs};
 
 type TestResult = Result<(), Box<dyn Error>>;
 
@@ -15,6 +11,7 @@ const ONE: &str = "./tests/inputs/one.txt";
 const TWO: &str = "./tests/inputs/two.txt";
 const THREE: &str = "./tests/inputs/three.txt";
 const TEN: &str = "./tests/inputs/ten.txt";
+const ZERO: &str = "./tests/inputs/zero.txt";
 
 // --------------------------------------------------
 fn random_string() -> String {
@@ -86,6 +83,8 @@ fn skips_bad_file() -> TestResult {
     Command::cargo_bin(PRG)?
         .args([EMPTY, &bad, ONE])
         .assert()
+        .code(1)
+        .stdout(fs::read("tests/expected/all.bad.out")?)
         .stderr(predicate::str::is_match(expected)?);
 
     Ok(())
@@ -93,17 +92,14 @@ fn skips_bad_file() -> TestResult {
 
 // --------------------------------------------------
 fn run(args: &[&str], expected_file: &str) -> TestResult {
-    // Extra work here due to lossy UTF
-    let mut file = File::open(expected_file)?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    let expected = String::from_utf8_lossy(&buffer);
+    // Bytes are compared since -c may cut a multibyte character
+    let expected = fs::read(expected_file)?;
 
     Command::cargo_bin(PRG)?
         .args(args)
         .assert()
         .success()
-        .stdout(predicate::eq(expected.as_bytes() as &[u8]));
+        .stdout(expected);
 
     Ok(())
 }
@@ -114,18 +110,14 @@ fn run_stdin(
     input_file: &str,
     expected_file: &str,
 ) -> TestResult {
-    // Extra work here due to lossy UTF
-    let mut file = File::open(expected_file)?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    let expected = String::from_utf8_lossy(&buffer);
-    let input = fs::read_to_string(input_file)?;
+    let expected = fs::read(expected_file)?;
+    let input = fs::read(input_file)?;
 
     Command::cargo_bin(PRG)?
         .write_stdin(input)
         .args(args)
         .assert()
-        .stdout(predicate::eq(expected.as_bytes() as &[u8]));
+        .stdout(expected);
 
     Ok(())
 }
@@ -419,3 +411,138 @@ fn multiple_files_c4() -> TestResult {
         "tests/expected/all.c4.out",
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn ten_n_all_but_3() -> TestResult {
+    run(&[TEN, "-n", "-3"], "tests/expected/ten.txt.n-3.out")
+}
+
+#[test]
+fn ten_n_all_but_0() -> TestResult {
+    run(&[TEN, "-n", "-0"], "tests/expected/ten.txt.n-0.out")
+}
+
+#[test]
+fn ten_n_all_but_20() -> TestResult {
+    run(&[TEN, "-n=-20"], "tests/expected/ten.txt.n-20.out")
+}
+
+#[test]
+fn ten_c_all_but_5() -> TestResult {
+    run(&[TEN, "-c", "-5"], "tests/expected/ten.txt.c-5.out")
+}
+
+#[test]
+fn ten_n_all_but_3_stdin() -> TestResult {
+    run_stdin(&["-n", "-3"], TEN, "tests/expected/ten.txt.n-3.out")
+}
+
+#[test]
+fn one_c_all_but_3_stdin() -> TestResult {
+    run_stdin(&["--bytes=-3"], ONE, "tests/expected/one.txt.c-3.out")
+}
+
+#[test]
+fn multiple_files_n_all_but_2() -> TestResult {
+    run(&["-n", "-2", ONE, TWO, TEN], "tests/expected/all.n-2.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn long_lines() -> TestResult {
+    let line = format!("{}\n", "x".repeat(10_000));
+    Command::cargo_bin(PRG)?
+        .args(["-n", "2"])
+        .write_stdin(line.repeat(3))
+        .assert()
+        .success()
+        .stdout(line.repeat(2));
+
+    Ok(())
+}
+
+#[test]
+fn long_bytes() -> TestResult {
+    let input = "x".repeat(100_000);
+    Command::cargo_bin(PRG)?
+        .args(["-c", "50000"])
+        .write_stdin(input.as_str())
+        .assert()
+        .success()
+        .stdout(input[..50_000].to_string());
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero() -> TestResult {
+    run(&["-z", ZERO], "tests/expected/zero.txt.z.out")
+}
+
+#[test]
+fn zero_n2() -> TestResult {
+    run(&["-z", "-n", "2", ZERO], "tests/expected/zero.txt.z.n2.out")
+}
+
+#[test]
+fn zero_n_all_but_1_stdin() -> TestResult {
+    run_stdin(&["--zero_terminated", "-n", "-1"], ZERO, "tests/expected/zero.txt.z.n-1.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn multiple_files_header_format() -> TestResult {
+    run(
+        &["-n", "1", "--header_format", "--- {n}: {path}", ONE, TWO],
+        "tests/expected/all.n1.header.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn ten_skip3_n3() -> TestResult {
+    run(&[TEN, "--skip", "3", "-n", "3"], "tests/expected/ten.txt.skip3.n3.out")
+}
+
+#[test]
+fn ten_skip8_n_all_but_1_stdin() -> TestResult {
+    run_stdin(&["--skip=8", "-n", "-1"], TEN, "tests/expected/ten.txt.skip8.n-1.out")
+}
+
+#[test]
+fn ten_skip_past_end() -> TestResult {
+    run(&[TEN, "--skip", "20"], "tests/expected/empty.txt.out")
+}
+
+#[test]
+fn dies_skip_and_bytes() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--skip", "1", "-c", "2", TEN])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn multiple_files_parallel() -> TestResult {
+    run(&["-P", "-n", "2", EMPTY, ONE, TWO, THREE, TEN], "tests/expected/all.n2.out")
+}
+
+#[test]
+fn skips_bad_file_parallel() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}.*os error 2");
+    Command::cargo_bin(PRG)?
+        .args(["--parallel", EMPTY, &bad, ONE])
+        .assert()
+        .code(1)
+        .stdout(fs::read("tests/expected/all.bad.out")?)
+        .stderr(predicate::str::is_match(expected)?);
+
+    Ok(())
+}
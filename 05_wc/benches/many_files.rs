@@ -0,0 +1,58 @@
+//! Compares this wc on thousands of small files with and without parallel counting
+//! and with GNU wc found in PATH
+//!
+//! Files are generated once in the temp folder and reused.
+//! Their number can be changed with `WC_BENCH_FILES=<count> cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const DEFAULT_FILES: usize = 5000;
+const LINE: &str = "The quick brown fox jumps over the lazy dog\tand then sleeps for a while\n";
+
+fn input_files(count: usize) -> Vec<PathBuf> {
+    let folder = std::env::temp_dir().join(format!("wc-bench-{count}"));
+    let files: Vec<PathBuf> = (0..count).map(|index| folder.join(format!("{index}.txt"))).collect();
+    if files.iter().all(|file| file.exists()) {
+        return files;
+    }
+
+    fs::create_dir_all(&folder).expect("Can't create bench folder");
+    for (index, file) in files.iter().enumerate() {
+        fs::write(file, LINE.repeat(index % 100 + 1)).expect("Can't write bench input");
+    }
+    files
+}
+
+fn wc(program: &str, threads: Option<&str>, files: &[PathBuf]) {
+    let mut command = Command::new(program);
+    if let Some(threads) = threads {
+        command.env("RAYON_NUM_THREADS", threads);
+    }
+
+    let status = command.args(files).stdout(Stdio::null()).status().expect("Can't run wc");
+    assert!(status.success());
+}
+
+fn many_files(c: &mut Criterion) {
+    let count = std::env::var("WC_BENCH_FILES")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(DEFAULT_FILES);
+    let files = input_files(count);
+
+    let mut group = c.benchmark_group(format!("wc {count} files"));
+    group.sample_size(10);
+
+    let this = env!("CARGO_BIN_EXE_wc");
+    group.bench_function("this parallel", |b| b.iter(|| wc(this, None, &files)));
+    group.bench_function("this serial", |b| b.iter(|| wc(this, Some("1"), &files)));
+    group.bench_function("gnu", |b| b.iter(|| wc("wc", None, &files)));
+
+    group.finish();
+}
+
+criterion_group!(benches, many_files);
+criterion_main!(benches);
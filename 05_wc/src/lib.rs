@@ -1,11 +1,19 @@
-use clap::{arg, Command};
+use clap::{arg, builder::PossibleValuesParser, Command};
+use rayon::prelude::*;
+use regex::Regex;
+use unicode_width::UnicodeWidthChar;
+use walkdir::WalkDir;
 use std::{
     error::Error,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
+    path::{Path, MAIN_SEPARATOR},
 };
 
 type DynErrorResult<T> = Result<T, Box<dyn Error>>;
 
+// Bigger than the BufReader capacity, so reads of -l go straight into it
+const LINES_CHUNK_SIZE: usize = 128 * 1024;
+
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
@@ -13,14 +21,42 @@ pub struct Config {
     words: bool,
     bytes: bool,
     chars: bool,
+    max_line_length: bool,
+    word_regex: Option<Regex>,
+    recursive: bool,
+    total: Total,
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Total {
+    Auto,   // total row only when more than one file was counted
+    Always, // total row even for a single file
+    Never,  // only rows of the files
+    Only,   // only the total row without a name, handy in scripts
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Table, // aligned columns
+    Csv,   // header row with the column names, then a row per file
+    Json,  // object per line with the counted columns, e.g. for jq
+}
+
+/// Row to count and print, directories walked with -r come after everything inside them
+#[derive(Debug)]
+enum Entry {
+    File { path: String, depth: usize },
+    Directory { path: String, depth: usize },
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Stats {
     lines: usize,
     words: usize,
     bytes: usize,
     chars: usize,
+    max_line_length: usize,
 }
 
 impl std::ops::AddAssign for Stats {
@@ -29,6 +65,7 @@ impl std::ops::AddAssign for Stats {
         self.words += other.words;
         self.chars += other.chars;
         self.bytes += other.bytes;
+        self.max_line_length = self.max_line_length.max(other.max_line_length);
     }
 }
 
@@ -41,71 +78,269 @@ pub fn get_args() -> DynErrorResult<Config> {
             arg!([files] ... "Files to process, stdin is -").default_value("-"),
             arg!(-l --lines "Count lines as number of EOL sequences"),
             arg!(-w --words "Count words as strings separated by whitespace"),
-            arg!(-c --chars "Count number of characters"),
+            arg!(-m --chars "Count number of characters"),
+            arg!(c: -c "Count number of bytes, or characters with --c_chars"),
             arg!(-b --bytes "Count number of bytes"),
+            arg!(--c_chars "Old behavior: -c counts characters, no flags count all four columns"),
+            arg!(-L --max_line_length "Show display width of the longest line"),
+            arg!(--word_regex <RE> "Words are matches of the regex, e.g. [A-Za-z0-9_]+ for code"),
+            arg!(-r --recursive "Count files in directories and subdirectories with subtotals"),
+            arg!(--files0_from <FILE> "Read NUL separated file names from the file, stdin is -")
+                .conflicts_with("files"),
+            arg!(--total <WHEN> "When to print the total row")
+                .value_parser(PossibleValuesParser::new(["auto", "always", "never", "only"]))
+                .default_value("auto"),
+            arg!(--format <FORMAT> "Output format, csv and json name the columns")
+                .value_parser(PossibleValuesParser::new(["table", "csv", "json"]))
+                .default_value("table"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
-    let mut config = Config {
-        files: matches
+    let total: String = matches.remove_one("total").expect("Default value is provided");
+    let format: String = matches.remove_one("format").expect("Default value is provided");
+    let word_regex = matches
+        .remove_one::<String>("word_regex")
+        .map(|text| {
+            Regex::new(&text).map_err(|error| format!("Invalid --word_regex '{text}', {error}"))
+        })
+        .transpose()?;
+    let files = match matches.remove_one::<String>("files0_from") {
+        Some(list) => read_files0(&list)?,
+        None => matches
             .remove_many("files")
             .expect("No file paths provided")
             .collect(),
+    };
+
+    let c_chars = matches.get_flag("c_chars");
+    let c = matches.get_flag("c");
+
+    let mut config = Config {
+        files,
         lines: matches.get_flag("lines"),
         words: matches.get_flag("words"),
-        chars: matches.get_flag("chars"),
-        bytes: matches.get_flag("bytes"),
+        chars: matches.get_flag("chars") || (c && c_chars),
+        bytes: matches.get_flag("bytes") || (c && !c_chars),
+        max_line_length: matches.get_flag("max_line_length"),
+        word_regex,
+        recursive: matches.get_flag("recursive"),
+        total: parse_total(&total),
+        format: parse_format(&format),
     };
 
-    // If no flags are provided, use all flags
+    // If no flags are provided, use the same columns as GNU wc
     //
-    // That is not backward compatible since wc was written in a day when chars and bytes
-    // meant the same (and that is reflected in the parameter name -c for byte count).
-    // Then Unicode came and suddenly there was a need to distinguish between bytes and chars.
-    // -c was occupied and thus -m was added for bytes. For backward compatible reasons
-    // 3 output column remained and that made the last column ambiguous.
+    // wc was written in a day when chars and bytes meant the same (and that is reflected
+    // in the parameter name -c for byte count). Then Unicode came and suddenly there was
+    // a need to distinguish between bytes and chars. -c was occupied and thus -m was added
+    // for chars. For backward compatible reasons 3 output columns remained by default.
     //
-    // Nowadays UTF-8 is common and if we don't want to be backward compatible and instead
-    // make wc program anew we should use -b for bytes and -c for chars and by default output
-    // 4 columns to solve the ambiguity.
-    if !config.lines && !config.words && !config.chars && !config.bytes {
+    // This wc used to be made anew with -b for bytes, -c for chars and 4 columns by default
+    // to solve the ambiguity. That is still available with --c_chars, otherwise it is
+    // a drop-in replacement of the system wc.
+    if !config.lines && !config.words && !config.chars && !config.bytes && !config.max_line_length {
         config.lines = true;
         config.words = true;
-        config.chars = true;
+        config.chars = c_chars;
         config.bytes = true;
     }
 
     Ok(config)
 }
 
+fn parse_total(name: &str) -> Total {
+    match name {
+        "auto" => Total::Auto,
+        "always" => Total::Always,
+        "never" => Total::Never,
+        "only" => Total::Only,
+        unknown => unreachable!("Unsupported total mode {unknown}"),
+    }
+}
+
+fn parse_format(name: &str) -> Format {
+    match name {
+        "table" => Format::Table,
+        "csv" => Format::Csv,
+        "json" => Format::Json,
+        unknown => unreachable!("Unsupported format {unknown}"),
+    }
+}
+
+// File list of find -print0, it doesn't hit the command line length limit
+fn read_files0(list: &str) -> DynErrorResult<Vec<String>> {
+    let mut content = Vec::new();
+    open(list)
+        .and_then(|mut reader| Ok(reader.read_to_end(&mut content)?))
+        .map_err(|error| format!("Can't read file list '{list}', error {error}"))?;
+
+    // Trailing NUL ends the last name, it doesn't start an empty one
+    let names = content.strip_suffix(b"\0").unwrap_or(&content);
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    names
+        .split(|&byte| byte == 0)
+        .map(|name| {
+            String::from_utf8(name.to_vec())
+                .map_err(|_| format!("File name is not valid UTF-8 in '{list}'").into())
+        })
+        .collect()
+}
+
 // cargo run -- -n (ls .\tests\inputs\*.txt)
 // cargo run -- -n (walker .\tests\inputs\ -a)
 pub fn run(config: Config) -> DynErrorResult<()> {
-    let mut totals = Stats { lines: 0, words: 0, bytes: 0, chars: 0 };
+    let mut totals = Stats::default();
     let mut files_processed = 0;
+    let mut output = Output::new(&config)?;
+    let (entries, mut failed) = list_entries(&config);
 
-    for path in &config.files {
-        match open(path) {
-            Err(error) => eprintln!("Can't open file '{}', error {}", &path, error),
-            Ok(reader) =>
-            {
-                let stats = process_stats(reader)?;
-                output_stats(&stats, path, &config);
+    // Files are counted in parallel, but rows are printed in the order of the arguments
+    let counts: Vec<_> = entries
+        .par_iter()
+        .with_max_len(1)
+        .map(|entry| match entry {
+            Entry::File { path, .. } => Some(count_file(path, &config)),
+            Entry::Directory { .. } => None,
+        })
+        .collect();
+
+    // Subtotals of the directories being walked, indexed by their depth
+    let mut subtotals: Vec<Stats> = Vec::new();
+
+    for (entry, count) in entries.iter().zip(counts) {
+        let (name, stats, depth) = match (entry, count) {
+            (Entry::File { path, depth }, Some(Ok(stats))) => {
                 files_processed += 1;
                 totals += stats;
-            },
+                (path.to_string(), stats, *depth)
+            }
+            (Entry::File { .. }, Some(Err(error))) => {
+                eprintln!("{error}");
+                failed = true;
+                continue;
+            }
+            (Entry::Directory { path, depth }, _) => {
+                let stats = subtotals.get_mut(*depth).map(std::mem::take).unwrap_or_default();
+                (directory_name(path), stats, *depth)
+            }
+            (Entry::File { .. }, None) => unreachable!("Every file is counted"),
+        };
+
+        if depth > 0 {
+            if subtotals.len() < depth {
+                subtotals.resize(depth, Stats::default());
+            }
+            subtotals[depth - 1] += stats;
         }
+
+        if config.total != Total::Only {
+            output.write(&stats, &name, &config)?;
+        }
+    }
+
+    // Total alone is just the numbers in a table, structured formats still name the row
+    let total = match (config.total, config.format) {
+        (Total::Only, Format::Table) => Some(""),
+        (Total::Only | Total::Always, _) => Some("total"),
+        (Total::Auto, _) if files_processed > 1 => Some("total"),
+        (Total::Auto | Total::Never, _) => None,
+    };
+    if let Some(name) = total {
+        output.write(&totals, name, &config)?;
     }
 
-    if files_processed > 1 {
-        output_stats(&totals, "total", &config);
+    output.finish()?;
+
+    // The rest of the files is still counted, only the exit code tells about the failure
+    if failed {
+        std::process::exit(1);
     }
 
     Ok(())
 }
 
-fn process_stats(mut reader: impl BufRead) -> DynErrorResult<Stats> {
-    let mut result = Stats { lines: 0, words: 0, bytes: 0, chars: 0 };
+// Directories are walked only with -r, otherwise they fail to be read as any other file
+// Also returns whether some of the directories could not be walked
+fn list_entries(config: &Config) -> (Vec<Entry>, bool) {
+    let mut entries = Vec::new();
+    let mut failed = false;
+
+    for path in &config.files {
+        if !config.recursive || !Path::new(path).is_dir() {
+            entries.push(Entry::File { path: path.clone(), depth: 0 });
+            continue;
+        }
+
+        let walker = WalkDir::new(path).contents_first(true).sort_by_file_name();
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    eprintln!("Can't walk directory '{path}', error {error}");
+                    failed = true;
+                    continue;
+                }
+            };
+
+            let name = entry.path().to_string_lossy().to_string();
+            let depth = entry.depth();
+            if entry.file_type().is_file() {
+                entries.push(Entry::File { path: name, depth });
+            } else if entry.file_type().is_dir() {
+                entries.push(Entry::Directory { path: name, depth });
+            }
+        }
+    }
+
+    (entries, failed)
+}
+
+// Trailing separator tells a subtotal apart from a file
+fn directory_name(path: &str) -> String {
+    if path.ends_with(MAIN_SEPARATOR) {
+        path.to_string()
+    } else {
+        format!("{path}{MAIN_SEPARATOR}")
+    }
+}
+
+// Errors are turned into messages here since the boxed error can't leave the worker thread
+fn count_file(path: &str, config: &Config) -> Result<Stats, String> {
+    let reader = open(path).map_err(|error| format!("Can't open file '{path}', error {error}"))?;
+    let only_lines = !config.words && !config.chars && !config.bytes && !config.max_line_length;
+
+    let stats = if only_lines {
+        count_lines(reader).map(|lines| Stats { lines, ..Stats::default() }).map_err(Into::into)
+    } else {
+        process_stats(reader, config.word_regex.as_ref())
+    };
+    stats.map_err(|error| format!("Can't read file '{path}', error {error}"))
+}
+
+// Lines are not assembled and checked for UTF-8, newlines are counted in big raw chunks
+fn count_lines(mut reader: impl Read) -> std::io::Result<usize> {
+    let mut buffer = vec![0; LINES_CHUNK_SIZE];
+    let mut lines = 0;
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(lines),
+            Ok(read) => lines += bytecount::count(&buffer[..read], b'\n'),
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+// Regex is compiled once in get_args and reused for every line of every file
+fn process_stats(
+    mut reader: impl BufRead,
+    word_regex: Option<&Regex>,
+) -> DynErrorResult<Stats> {
+    let mut result = Stats::default();
     let mut line = String::new();
 
     loop {
@@ -116,8 +351,12 @@ fn process_stats(mut reader: impl BufRead) -> DynErrorResult<Stats> {
 
         result.bytes += bytes;
         result.chars += line.chars().count();
-        result.words += line.split_whitespace().count();
-        result.lines += 1;
+        result.words += match word_regex {
+            Some(regex) => regex.find_iter(&line).count(),
+            None => line.split_whitespace().count(),
+        };
+        result.lines += line.ends_with('\n') as usize;
+        result.max_line_length = result.max_line_length.max(line_width(&line));
 
         line.clear();
     }
@@ -125,15 +364,102 @@ fn process_stats(mut reader: impl BufRead) -> DynErrorResult<Stats> {
     Ok(result)
 }
 
+/// Display width of the line the same way as GNU wc -L counts it
+///
+/// Wide characters like CJK take two columns and control characters take none.
+/// Tab advances to the next multiple of 8, carriage return and form feed start over.
+fn line_width(line: &str) -> usize {
+    let mut longest = 0;
+    let mut width = 0;
+
+    for char in line.chars() {
+        match char {
+            '\n' => break,
+            '\t' => width = (width / 8 + 1) * 8,
+            '\r' | '\x0c' => {
+                longest = longest.max(width);
+                width = 0;
+            }
+            _ => width += char.width().unwrap_or(0),
+        }
+    }
+
+    longest.max(width)
+}
+
+/// Destination of the rows in the chosen --format
+enum Output {
+    Table,
+    Csv(Box<csv::Writer<std::io::Stdout>>),
+    Json,
+}
+
+impl Output {
+    fn new(config: &Config) -> DynErrorResult<Self> {
+        Ok(match config.format {
+            Format::Table => Output::Table,
+            Format::Json => Output::Json,
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(std::io::stdout());
+                let names = columns(&Stats::default(), config).into_iter().map(|(name, _)| name);
+                writer.write_record(std::iter::once("file").chain(names))?;
+                Output::Csv(Box::new(writer))
+            }
+        })
+    }
+
+    fn write(&mut self, stats: &Stats, name: &str, config: &Config) -> DynErrorResult<()> {
+        match self {
+            Output::Table => output_stats(stats, name, config),
+            Output::Csv(writer) => {
+                let values = columns(stats, config).into_iter().map(|(_, value)| value.to_string());
+                writer.write_record(std::iter::once(name.to_string()).chain(values))?;
+            }
+            Output::Json => {
+                let fields: String = columns(stats, config)
+                    .into_iter()
+                    .map(|(column, value)| format!(",\"{column}\":{value}"))
+                    .collect();
+                println!("{{\"file\":{}{fields}}}", common::json::quote(name));
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> DynErrorResult<()> {
+        if let Output::Csv(mut writer) = self {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// Selected counters in the order of the output columns
+fn columns(stats: &Stats, config: &Config) -> Vec<(&'static str, usize)> {
+    [
+        ("lines", config.lines, stats.lines),
+        ("words", config.words, stats.words),
+        ("chars", config.chars, stats.chars),
+        ("bytes", config.bytes, stats.bytes),
+        ("max_line_length", config.max_line_length, stats.max_line_length),
+    ]
+    .into_iter()
+    .filter(|&(_, show, _)| show)
+    .map(|(name, _, value)| (name, value))
+    .collect()
+}
+
+// Row without a name is just the numbers, e.g. for --total=only
 fn output_stats(stats: &Stats, name: &str, config: &Config)
 {
+    let separator = if name.is_empty() { "" } else { " " };
     println!(
-        "{}{}{}{} {}",
+        "{}{}{}{}{}{separator}{name}",
         format_field(stats.lines, config.lines),
         format_field(stats.words, config.words),
         format_field(stats.chars, config.chars),
         format_field(stats.bytes, config.bytes),
-        name
+        format_field(stats.max_line_length, config.max_line_length),
     );
 }
 
@@ -154,13 +480,14 @@ fn open(path: &str) -> DynErrorResult<Box<dyn BufRead>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{process_stats, format_field, Stats};
+    use super::{count_lines, process_stats, format_field, line_width, Stats};
+    use regex::Regex;
     use std::io::Cursor;
 
     #[test]
     fn test_stats() {
         let text = "I don't want the world. I just want your half.\r\n";
-        let stats = process_stats(Cursor::new(text));
+        let stats = process_stats(Cursor::new(text), None);
 
         assert!(stats.is_ok());
         let expected = Stats {
@@ -168,10 +495,41 @@ mod tests {
             words: 10,
             chars: 48,
             bytes: 48,
+            max_line_length: 46,
         };
         assert_eq!(stats.unwrap(), expected);
     }
 
+    #[test]
+    fn test_stats_word_regex() {
+        let text = "let x_1 = foo(bar, 42);\n// done\n";
+        let regex = Regex::new("[A-Za-z0-9_]+").unwrap();
+        let stats = process_stats(Cursor::new(text), Some(&regex)).unwrap();
+        assert_eq!(stats.words, 6);
+        assert_eq!(stats.lines, 2);
+    }
+
+    #[test]
+    fn test_count_lines() {
+        assert_eq!(count_lines(Cursor::new("")).unwrap(), 0);
+        assert_eq!(count_lines(Cursor::new("a\nb\n\n")).unwrap(), 3);
+        assert_eq!(count_lines(Cursor::new("no newline")).unwrap(), 0);
+
+        // Both ways of counting agree on the last line without a newline, as GNU wc does
+        let stats = process_stats(Cursor::new("a\nb"), None).unwrap();
+        assert_eq!(stats.lines, count_lines(Cursor::new("a\nb")).unwrap());
+        assert_eq!(stats.lines, 1);
+    }
+
+    #[test]
+    fn test_line_width() {
+        assert_eq!(line_width("plain text\n"), 10);
+        assert_eq!(line_width("日本語\r\n"), 6);
+        assert_eq!(line_width("a\tb"), 9);
+        assert_eq!(line_width("long line\rshort"), 9);
+        assert_eq!(line_width("\x1b[0m"), 3);
+    }
+
     #[test]
     fn test_format_field() {
         assert_eq!(format_field(1, false), "");
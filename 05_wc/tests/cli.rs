@@ -9,6 +9,7 @@ const PRG: &str = "wc";
 const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const ATLAMAL: &str = "tests/inputs/atlamal.txt";
+const WIDE: &str = "tests/inputs/wide.txt";
 
 // --------------------------------------------------
 fn gen_bad_file() -> String {
@@ -44,11 +45,25 @@ fn skips_bad_file() -> TestResult {
     Command::cargo_bin(PRG)?
         .arg(bad)
         .assert()
-        .success()
+        .code(1)
         .stderr(predicate::str::is_match(expected)?);
     Ok(())
 }
 
+// --------------------------------------------------
+#[test]
+fn counts_rest_after_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = fs::read_to_string("tests/expected/fox.bad.atlamal.out")?;
+    Command::cargo_bin(PRG)?
+        .args([FOX, &bad, ATLAMAL])
+        .assert()
+        .code(1)
+        .stdout(expected)
+        .stderr(predicate::str::is_match(format!("{bad}.*os error 2"))?);
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn empty() -> TestResult {
@@ -73,6 +88,18 @@ fn fox_chars() -> TestResult {
     run(&["--chars", FOX], "tests/expected/fox.txt.m.out")
 }
 
+// --------------------------------------------------
+#[test]
+fn fox_c_bytes() -> TestResult {
+    run(&["-c", FOX], "tests/expected/fox.txt.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_lines_words_chars() -> TestResult {
+    run(&["-lwm", FOX], "tests/expected/fox.txt.lwm.out")
+}
+
 // --------------------------------------------------
 #[test]
 fn fox_words() -> TestResult {
@@ -199,3 +226,184 @@ fn test_all_words_lines() -> TestResult {
 fn test_all_bytes_lines() -> TestResult {
     run(&["-bl", EMPTY, FOX, ATLAMAL], "tests/expected/all.cl.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn test_all_lines_args_file() -> TestResult {
+    run(&["@tests/inputs/args.txt"], "tests/expected/all.l.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("Can't read argument file '{bad}'.*os error 2");
+    Command::cargo_bin(PRG)?
+        .arg(format!("@{bad}"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_max_line_length() -> TestResult {
+    run(&["-L", FOX], "tests/expected/fox.txt.L.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_max_line_length() -> TestResult {
+    run(&["--max_line_length", ATLAMAL], "tests/expected/atlamal.txt.L.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn wide_max_line_length() -> TestResult {
+    run(&["-L", WIDE], "tests/expected/wide.txt.L.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_max_line_length() -> TestResult {
+    run(&["-l", "-L", EMPTY, FOX, ATLAMAL], "tests/expected/all.lL.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_files0_from() -> TestResult {
+    run(&["--files0_from", "tests/inputs/files0.txt"], "tests/expected/all.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_files0_from_stdin() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/all.l.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["-l", "--files0_from=-"])
+        .write_stdin(fs::read("tests/inputs/files0.txt")?)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_files0_from_and_files() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--files0_from", "tests/inputs/files0.txt", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_files0_from() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("Can't read file list '{bad}'.*os error 2");
+    Command::cargo_bin(PRG)?
+        .args(["--files0_from", &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all_total_only() -> TestResult {
+    run(&["--total", "only", EMPTY, FOX, ATLAMAL], "tests/expected/all.total.only.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_total_never() -> TestResult {
+    run(&["-l", "--total=never", EMPTY, FOX, ATLAMAL], "tests/expected/all.l.total.never.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_lines_total_always() -> TestResult {
+    run(&["-l", "--total=always", FOX], "tests/expected/fox.txt.l.total.always.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_total_auto() -> TestResult {
+    run(&["--total=auto", FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_words_csv() -> TestResult {
+    run(&["-lw", "--format", "csv", EMPTY, FOX, ATLAMAL], "tests/expected/all.lw.csv.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_json() -> TestResult {
+    run(&["--format=json", EMPTY, FOX, ATLAMAL], "tests/expected/all.json.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_max_line_length_json_total_only() -> TestResult {
+    run(
+        &["-L", "--format=json", "--total=only", EMPTY, FOX, ATLAMAL],
+        "tests/expected/all.L.json.total.only.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn all_chars() -> TestResult {
+    run(&["-m", EMPTY, FOX, ATLAMAL], "tests/expected/all.m.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_c_chars() -> TestResult {
+    run(&["--c_chars", EMPTY, FOX, ATLAMAL], "tests/expected/all.c_chars.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_c_chars() -> TestResult {
+    run(&["--c_chars", "-c", FOX], "tests/expected/fox.txt.m.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_words_regex() -> TestResult {
+    run(
+        &["-w", "--word_regex", "[A-Za-z0-9_]+", ATLAMAL],
+        "tests/expected/atlamal.txt.word_regex.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_word_regex() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--word_regex", "*", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --word_regex '*'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn tree_recursive() -> TestResult {
+    run(&["-r", "tests/inputs/tree"], "tests/expected/tree.r.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tree_recursive_lines_with_file() -> TestResult {
+    run(&["-l", "--recursive", FOX, "tests/inputs/tree/"], "tests/expected/tree.r.l.out")
+}
@@ -0,0 +1,132 @@
+use std::io::{self, Write};
+
+/// Part of the --count_format template
+#[derive(Debug, Clone, PartialEq)]
+pub enum Piece {
+    Text(String),
+    Count(usize),   // {count} or {count:N} right aligned to N columns
+    Percent(usize), // {percent} or {percent:N}, share of all input lines
+    Line,           // {line} without its line ending
+}
+
+/// Default template for the columns that were asked for, -c alone looks the same as before
+pub fn default_template(count: bool, percent: bool) -> String {
+    let mut template = String::new();
+    if count {
+        template.push_str("{count:4} ");
+    }
+    if percent {
+        template.push_str("{percent:6} ");
+    }
+    template.push_str("{line}");
+    template
+}
+
+/// Splits the template into pieces once, so lines don't parse it again
+///
+/// Unknown tokens and tokens with a bad width are kept as text.
+pub fn parse(template: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let token = rest.find('}').and_then(|end| Some((parse_token(&rest[1..end])?, end)));
+        match token {
+            Some((piece, end)) => {
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(piece);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    pieces
+}
+
+fn parse_token(token: &str) -> Option<Piece> {
+    let (name, width) = match token.split_once(':') {
+        Some((name, width)) => (name, width.parse().ok()?),
+        None => (token, 0),
+    };
+
+    match name {
+        "count" => Some(Piece::Count(width)),
+        "percent" => Some(Piece::Percent(width)),
+        "line" if width == 0 => Some(Piece::Line),
+        _ => None,
+    }
+}
+
+/// Writes the line as the template says, the line ending goes last
+pub fn write(
+    writer: &mut dyn Write,
+    pieces: &[Piece],
+    line: &[u8],
+    terminator: u8,
+    count: usize,
+    total: usize,
+) -> io::Result<()> {
+    let (body, ending) = match line.strip_suffix(&[terminator]) {
+        Some(body) => (body, &line[body.len()..]),
+        None => (line, &[][..]),
+    };
+
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => writer.write_all(text.as_bytes())?,
+            Piece::Count(width) => write!(writer, "{count:>width$}")?,
+            Piece::Percent(width) => {
+                let percent = format!("{:.1}%", count as f64 * 100.0 / total.max(1) as f64);
+                write!(writer, "{percent:>width$}")?;
+            }
+            Piece::Line => writer.write_all(body)?,
+        }
+    }
+
+    writer.write_all(ending)
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{default_template, parse, write, Piece};
+
+    fn render(template: &str, line: &str, count: usize, total: usize) -> String {
+        let mut output = Vec::new();
+        write(&mut output, &parse(template), line.as_bytes(), b'\n', count, total).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("{count:4} {line}"),
+            [Piece::Count(4), Piece::Text(" ".to_string()), Piece::Line]
+        );
+        assert_eq!(parse("{x} {count:y}"), [Piece::Text("{x} {count:y}".to_string())]);
+        assert_eq!(parse("{percent}"), [Piece::Percent(0)]);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(render(&default_template(true, false), "a\n", 2, 4), "   2 a\n");
+        assert_eq!(render(&default_template(true, true), "a\n", 1, 3), "   1  33.3% a\n");
+        assert_eq!(render("{line}\t{count}", "last", 10, 10), "last\t10");
+        assert_eq!(render("{percent:7}|{line}", "b\n", 0, 0), "   0.0%|b\n");
+    }
+}
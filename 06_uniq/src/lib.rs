@@ -1,16 +1,38 @@
+mod count_format;
+mod spill;
+
 use clap::{arg, Command};
+use common::reader::{RecordRead, RecordReader};
+use common::units::{self, Scale};
+use count_format::Piece;
+use spill::{Group, Spill};
 use std::{
+    collections::HashMap,
     error::Error,
-    io::{BufRead, BufReader, Write, self}, fs::File,
+    io::{BufRead, BufReader, BufWriter, Write, self}, fs::{self, File},
+    path::Path,
 };
 
 type DynErrorResult<T> = Result<T, Box<dyn Error>>;
 
+// HashMap entry and Vec headers of a line kept by --global, roughly
+const GROUP_OVERHEAD: usize = 64;
+
 #[derive(Debug)]
 pub struct Config {
     in_file: String,
     out_file: Option<String>,
+    in_place: Option<String>,
     count: bool,
+    percent: bool,
+    count_format: Vec<Piece>,
+    repeated: bool,
+    unique: bool,
+    check_chars: Option<usize>,
+    terminator: u8,
+    global: bool,
+    buffer_size: usize,
+    temp_dir: Option<String>,
 }
 
 pub fn get_args() -> DynErrorResult<Config> {
@@ -21,73 +43,305 @@ pub fn get_args() -> DynErrorResult<Config> {
         .args([
             arg!([INPUT_FILE] "Input file to process, stdin is -").default_value("-"),
             arg!(-o --output [OUTPUT_FILE] "Output file, stdout if absent"),
+            arg!(--in_place [SUFFIX] "Replace the input file, keep a backup with the =SUFFIX")
+                .require_equals(true)
+                .default_missing_value("")
+                .conflicts_with("output"),
             arg!(-c --count "Print duplication count for every line"),
+            arg!(--percent "Print share of all input lines for every line"),
+            arg!(--count_format <TEMPLATE> "Line with {count}, {percent}, {line}, implies -c")
+                .allow_hyphen_values(true),
+            arg!(-d --repeated "Print only duplicated lines, one for each group"),
+            arg!(-u --unique "Print only lines that are not duplicated"),
+            arg!(-w --check_chars <N> "Compare no more than N first characters of lines")
+                .value_parser(clap::value_parser!(usize)),
+            arg!(--global "Remove duplicates anywhere in the input, not only adjacent ones"),
+            arg!(-S --buffer_size <SIZE> "Memory for --global lines before they spill to disk")
+                .value_parser(parse_size)
+                .default_value("1G"),
+            arg!(-T --temp_dir <DIR> "Folder for spilled --global lines, system temp if absent"),
+            arg!(-z --zero_terminated "Lines end with NUL instead of newline, e.g. for -print0"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let count = matches.get_flag("count") || matches.contains_id("count_format");
+    let percent = matches.get_flag("percent");
+    let template = matches
+        .remove_one("count_format")
+        .unwrap_or_else(|| count_format::default_template(count, percent));
 
     Ok(Config {
         in_file: matches.remove_one("INPUT_FILE").expect("Input file not provided"),
         out_file: matches.remove_one("output"),
-        count: matches.get_flag("count"),
+        in_place: matches.remove_one("in_place"),
+        count,
+        percent,
+        count_format: count_format::parse(&template),
+        repeated: matches.get_flag("repeated"),
+        unique: matches.get_flag("unique"),
+        check_chars: matches.remove_one("check_chars"),
+        global: matches.get_flag("global"),
+        buffer_size: matches.remove_one("buffer_size").expect("Default value is provided"),
+        temp_dir: matches.remove_one("temp_dir"),
+        terminator: if matches.get_flag("zero_terminated") { b'\0' } else { b'\n' },
     })
 }
 
+// Sizes like 512K or 2G are powers of 1024 the same way as in sort -S
+fn parse_size(text: &str) -> Result<usize, String> {
+    units::parse(text, Scale::Iec)
+        .map(|size| size as usize)
+        .map_err(|error| error.to_string())
+}
+
 // cargo run -- -n (ls .\tests\inputs\*.txt)
 // cargo run -- -n (walker .\tests\inputs\ -a)
 pub fn run(config: Config) -> DynErrorResult<()> {
+    if let Some(suffix) = &config.in_place {
+        return process_in_place(&config, suffix);
+    }
 
     let mut writer = open_write(&config)?;
 
     match open_read(&config) {
         Err(error) => panic!("Can't open file '{}', error {}", &config.in_file, error),
-        Ok(reader) => process_unuque(reader, &mut writer, &config)?,
+        Ok(reader) => process(reader, &mut writer, &config),
+    }
+}
+
+fn process(reader: impl BufRead, writer: &mut dyn Write, config: &Config) -> DynErrorResult<()> {
+    if config.global {
+        process_global(reader, writer, config)
+    } else {
+        process_unuque(reader, writer, config)
+    }
+}
+
+// Result goes to a temp file next to the input that then replaces it with a rename,
+// so the input is either intact or fully processed even if uniq is interrupted
+fn process_in_place(config: &Config, suffix: &str) -> DynErrorResult<()> {
+    let path = Path::new(&config.in_file);
+    if config.in_file == "-" {
+        return Err("Can't edit stdin in place".into());
+    }
+
+    let reader = open_read(config)
+        .map_err(|error| format!("Can't open file '{}', error {}", config.in_file, error))?;
+    let folder = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+    let temp = tempfile::NamedTempFile::new_in(folder.unwrap_or(Path::new(".")))?;
+
+    let mut writer = BufWriter::new(temp);
+    process(reader, &mut writer, config)?;
+    let temp = writer.into_inner().map_err(|error| error.into_error())?;
+    fs::set_permissions(temp.path(), fs::metadata(path)?.permissions())?;
+
+    if !suffix.is_empty() {
+        fs::copy(path, format!("{}{suffix}", config.in_file))?;
     }
+    temp.persist(path)?;
 
     Ok(())
 }
 
-fn process_unuque(mut reader: impl BufRead, writer: &mut dyn Write, config: &Config) -> DynErrorResult<()> {
-    let mut tracked = String::new();
-    let mut current = String::new();
+fn process_unuque(
+    reader: impl BufRead,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> DynErrorResult<()> {
+    let mut reader = RecordReader::new(reader, config.terminator);
+    // Nothing is tracked before the first line, an empty first key is a key too
+    let mut tracked: Option<Vec<u8>> = None;
+    let mut current = Vec::new();
     let mut count = 0;
 
-    let mut output_line = |line: &str, count: usize| -> DynErrorResult<()> {
-        if count > 0 {
-            let count_str = if config.count {format!("{count:>4} ")} else {String::new()};
-            write!(writer, "{count_str}{line}")?;
+    // With --percent groups are printed once the total number of lines is known
+    let mut groups: Vec<(Vec<u8>, usize)> = Vec::new();
+    let mut end_group = |line: &mut Vec<u8>, count: usize| -> DynErrorResult<()> {
+        if config.percent && count > 0 {
+            groups.push((std::mem::take(line), count));
+            Ok(())
+        } else {
+            output_line(writer, line, count, 0, config)
         }
-
-        Ok(())
     };
 
     loop {
-        // Read line together with line endings
+        // Read line together with line endings, lines don't need to be valid UTF-8
         current.clear();
-        let bytes = reader.read_line(&mut current)?;
+        let bytes = reader.read_record(&mut current)?;
         if bytes == 0 {
             break;
         }
 
-        if tracked.trim_end() == current.trim_end() {
-            // Encountered a duplicate line
-            count += 1;
-        }
-        else {
-            // Output previosly tracked line
-            output_line(&tracked, count)?;
+        match &mut tracked {
+            Some(line) if compare_key(line, config) == compare_key(&current, config) => {
+                // Encountered a duplicate line
+                count += 1;
+            }
+            Some(line) => {
+                // Output previosly tracked line
+                end_group(line, count)?;
 
-            // Start tracking the new line
-            tracked = current.clone();
-            count = 1;
+                // Start tracking the new line
+                std::mem::swap(line, &mut current);
+                count = 1;
+            }
+            None => {
+                tracked = Some(std::mem::take(&mut current));
+                count = 1;
+            }
         }
     }
 
     // The last line was not dumped in the loop
-    output_line(&tracked, count)?;
+    if let Some(line) = &mut tracked {
+        end_group(line, count)?;
+    }
+
+    let total = groups.iter().map(|(_, count)| count).sum();
+    for (line, count) in groups {
+        output_line(writer, &line, count, total, config)?;
+    }
 
     Ok(())
 }
 
+// Groups are printed in the order of their first lines. Counts are known only at the end
+// of the input, so without them lines are printed right away and only their keys are kept.
+// Once the kept lines outgrow --buffer_size the rest goes through the temp files.
+fn process_global(
+    reader: impl BufRead,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> DynErrorResult<()> {
+    let mut reader = RecordReader::new(reader, config.terminator);
+    let needs_counts = config.count || config.percent || config.repeated || config.unique;
+    let mut seen: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut groups: Vec<(Vec<u8>, usize)> = Vec::new();
+    let mut spill: Option<Spill> = None;
+    let mut line = Vec::new();
+    let mut total = 0;
+    let mut used = 0;
+
+    loop {
+        line.clear();
+        if reader.read_record(&mut line)? == 0 {
+            break;
+        }
+        total += 1;
+
+        let key = compare_key(&line, config);
+        if let Some(spill) = &mut spill {
+            let (key, line) = (key.to_vec(), line.clone());
+            spill.add(&Group { index: total as u64, count: 1, printed: false, key, line })?;
+            continue;
+        }
+
+        match seen.get(key) {
+            Some(&index) if needs_counts => groups[index].1 += 1,
+            Some(_) => {}
+            None if needs_counts => {
+                used += key.len() + line.len() + GROUP_OVERHEAD;
+                seen.insert(key.to_vec(), groups.len());
+                groups.push((line.clone(), 1));
+            }
+            None => {
+                used += key.len() + GROUP_OVERHEAD;
+                seen.insert(key.to_vec(), 0);
+                output_line(writer, &line, 1, total, config)?;
+            }
+        }
+
+        if used > config.buffer_size {
+            spill = Some(spill_groups(seen, groups, needs_counts, config)?);
+            (seen, groups) = (HashMap::new(), Vec::new());
+        }
+    }
+
+    match spill {
+        None => {
+            for (line, count) in groups {
+                output_line(writer, &line, count, total, config)?;
+            }
+        }
+        Some(spill) => {
+            for group in spill.finish()? {
+                let group = group?;
+                if !group.printed {
+                    output_line(writer, &group.line, group.count as usize, total, config)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Lines kept so far go first, their order is before any line that is still to be read
+fn spill_groups(
+    seen: HashMap<Vec<u8>, usize>,
+    mut groups: Vec<(Vec<u8>, usize)>,
+    needs_counts: bool,
+    config: &Config,
+) -> DynErrorResult<Spill> {
+    let mut spill = Spill::new(config.temp_dir.as_deref().map(Path::new))
+        .map_err(|error| format!("Can't create temp files for --global, error {error}"))?;
+
+    for (key, index) in seen {
+        let group = match needs_counts {
+            true => {
+                let (line, count) = std::mem::take(&mut groups[index]);
+                Group { index: index as u64, count: count as u64, printed: false, key, line }
+            }
+            false => Group { index: 0, count: 1, printed: true, key, line: Vec::new() },
+        };
+        spill.add(&group)?;
+    }
+
+    Ok(spill)
+}
+
+// Whether the line is printed is known only when its group ends and the count is final
+fn output_line(
+    writer: &mut dyn Write,
+    line: &[u8],
+    count: usize,
+    total: usize,
+    config: &Config,
+) -> DynErrorResult<()> {
+    let filtered = (config.repeated && count < 2) || (config.unique && count > 1);
+    if count == 0 || filtered {
+        return Ok(());
+    }
+
+    if config.count || config.percent {
+        count_format::write(writer, &config.count_format, line, config.terminator, count, total)?;
+    } else {
+        writer.write_all(line)?;
+    }
+
+    Ok(())
+}
+
+// Part of the line that decides whether it duplicates the previous one
+fn compare_key<'a>(line: &'a [u8], config: &Config) -> &'a [u8] {
+    let line = line.strip_suffix(&[config.terminator]).unwrap_or(line).trim_ascii_end();
+
+    match config.check_chars {
+        Some(count) => char_prefix(line, count),
+        None => line,
+    }
+}
+
+// Characters start at any byte that is not a UTF-8 continuation byte
+fn char_prefix(line: &[u8], count: usize) -> &[u8] {
+    let mut starts = line.iter().enumerate().filter(|(_, &byte)| byte & 0xC0 != 0x80);
+    match starts.nth(count) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
 fn open_read(config: &Config) -> DynErrorResult<Box<dyn BufRead>> {
     match config.in_file.as_str() {
         "-" => Ok(Box::new(BufReader::new(io::stdin()))),
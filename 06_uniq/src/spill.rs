@@ -0,0 +1,217 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::RandomState, BinaryHeap, HashMap},
+    fs::File,
+    hash::BuildHasher,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+use tempfile::TempDir;
+
+// Every partition is loaded into memory alone, so the input needs to be this many times
+// bigger than --buffer_size before a partition stops fitting as well
+const PARTITIONS: usize = 128;
+
+/// Group of equal lines that --global keeps when they don't fit into memory
+#[derive(Debug, PartialEq)]
+pub struct Group {
+    pub index: u64,    // order of the first line, groups are printed by it
+    pub count: u64,    // number of lines in the group
+    pub printed: bool, // the line was already printed before the spill
+    pub key: Vec<u8>,
+    pub line: Vec<u8>, // first line of the group, empty when it was printed
+}
+
+/// Hash partitioned temp files of the --global groups
+///
+/// Equal keys always land in the same partition, so partitions are deduplicated
+/// one by one and then merged back into the order of the first lines.
+pub struct Spill {
+    folder: TempDir,
+    partitions: Vec<BufWriter<File>>,
+    hasher: RandomState,
+}
+
+impl Spill {
+    pub fn new(temp_dir: Option<&Path>) -> io::Result<Self> {
+        let folder = match temp_dir {
+            Some(path) => tempfile::Builder::new().prefix("uniq").tempdir_in(path)?,
+            None => tempfile::Builder::new().prefix("uniq").tempdir()?,
+        };
+
+        let partitions = (0..PARTITIONS)
+            .map(|index| Ok(BufWriter::new(File::create(partition_path(&folder, index))?)))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self { folder, partitions, hasher: RandomState::new() })
+    }
+
+    pub fn add(&mut self, group: &Group) -> io::Result<()> {
+        let partition = self.hasher.hash_one(&group.key) as usize % PARTITIONS;
+        write_group(&mut self.partitions[partition], group)
+    }
+
+    /// Groups of all partitions in the order of their first lines
+    pub fn finish(self) -> io::Result<Merge> {
+        let mut runs = Vec::with_capacity(PARTITIONS);
+
+        for (index, partition) in self.partitions.into_iter().enumerate() {
+            partition.into_inner().map_err(|error| error.into_error())?;
+            let groups = dedup_partition(&partition_path(&self.folder, index))?;
+
+            let path = self.folder.path().join(format!("run{index}"));
+            let mut run = BufWriter::new(File::create(&path)?);
+            for group in &groups {
+                write_group(&mut run, group)?;
+            }
+            run.flush()?;
+
+            drop(groups);
+            std::fs::remove_file(partition_path(&self.folder, index))?;
+            runs.push(BufReader::new(File::open(path)?));
+        }
+
+        Merge::new(self.folder, runs)
+    }
+}
+
+fn partition_path(folder: &TempDir, index: usize) -> std::path::PathBuf {
+    folder.path().join(format!("partition{index}"))
+}
+
+// Groups of the same key are joined into the earliest one
+fn dedup_partition(path: &Path) -> io::Result<Vec<Group>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut groups: HashMap<Vec<u8>, Group> = HashMap::new();
+
+    while let Some(group) = read_group(&mut reader)? {
+        match groups.get_mut(&group.key) {
+            None => {
+                groups.insert(group.key.clone(), group);
+            }
+            Some(known) => {
+                known.count += group.count;
+                known.printed |= group.printed;
+                if group.index < known.index {
+                    known.index = group.index;
+                    known.line = group.line;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Group> = groups.into_values().collect();
+    groups.sort_unstable_by_key(|group| group.index);
+    Ok(groups)
+}
+
+/// K-way merge of the sorted runs of the partitions
+pub struct Merge {
+    _folder: TempDir, // removed with the runs once the merge is dropped
+    runs: Vec<BufReader<File>>,
+    heads: Vec<Option<Group>>,
+    queue: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+impl Merge {
+    fn new(folder: TempDir, mut runs: Vec<BufReader<File>>) -> io::Result<Self> {
+        let mut heads = Vec::with_capacity(runs.len());
+        let mut queue = BinaryHeap::new();
+
+        for (run_index, run) in runs.iter_mut().enumerate() {
+            let head = read_group(run)?;
+            if let Some(group) = &head {
+                queue.push(Reverse((group.index, run_index)));
+            }
+            heads.push(head);
+        }
+
+        Ok(Self { _folder: folder, runs, heads, queue })
+    }
+
+    fn next_group(&mut self) -> io::Result<Option<Group>> {
+        let Some(Reverse((_, run_index))) = self.queue.pop() else {
+            return Ok(None);
+        };
+
+        let next = read_group(&mut self.runs[run_index])?;
+        if let Some(group) = &next {
+            self.queue.push(Reverse((group.index, run_index)));
+        }
+
+        Ok(std::mem::replace(&mut self.heads[run_index], next))
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<Group>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_group().transpose()
+    }
+}
+
+// Little endian header of index, count, printed flag, key and line lengths, then the bytes
+fn write_group(writer: &mut impl Write, group: &Group) -> io::Result<()> {
+    writer.write_all(&group.index.to_le_bytes())?;
+    writer.write_all(&group.count.to_le_bytes())?;
+    writer.write_all(&[group.printed as u8])?;
+    writer.write_all(&(group.key.len() as u64).to_le_bytes())?;
+    writer.write_all(&(group.line.len() as u64).to_le_bytes())?;
+    writer.write_all(&group.key)?;
+    writer.write_all(&group.line)
+}
+
+fn read_group(reader: &mut impl Read) -> io::Result<Option<Group>> {
+    let mut index = [0; 8];
+    match reader.read_exact(&mut index) {
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+
+    let mut header = [0; 25];
+    reader.read_exact(&mut header)?;
+    let number = |at: usize| u64::from_le_bytes(header[at..at + 8].try_into().expect("8 bytes"));
+
+    let mut key = vec![0; number(9) as usize];
+    reader.read_exact(&mut key)?;
+    let mut line = vec![0; number(17) as usize];
+    reader.read_exact(&mut line)?;
+
+    Ok(Some(Group {
+        index: u64::from_le_bytes(index),
+        count: number(0),
+        printed: header[8] != 0,
+        key,
+        line,
+    }))
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{Group, Spill};
+
+    fn group(index: u64, printed: bool, key: &str) -> Group {
+        let line = if printed { Vec::new() } else { format!("{key}\n").into_bytes() };
+        Group { index, count: 1, printed, key: key.into(), line }
+    }
+
+    #[test]
+    fn test_spill() {
+        let mut spill = Spill::new(None).unwrap();
+        for (index, key) in ["b", "a", "b", "c", "a", "b"].into_iter().enumerate() {
+            spill.add(&group(index as u64, index == 0, key)).unwrap();
+        }
+
+        let merged: Vec<Group> = spill.finish().unwrap().map(Result::unwrap).collect();
+        assert_eq!(
+            merged,
+            [
+                Group { count: 3, ..group(0, true, "b") },
+                Group { count: 2, ..group(1, false, "a") },
+                group(3, false, "c"),
+            ]
+        );
+    }
+}
@@ -120,6 +120,17 @@ fn run(test: &Test) -> TestResult {
     Ok(())
 }
 
+// --------------------------------------------------
+fn run_args(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
 // --------------------------------------------------
 fn run_count(test: &Test) -> TestResult {
     let expected = fs::read_to_string(test.out_count)?;
@@ -605,3 +616,185 @@ fn t6_outfile_count() -> TestResult {
 fn t6_stdin_outfile_count() -> TestResult {
     run_stdin_outfile_count(&T6)
 }
+
+// --------------------------------------------------
+#[test]
+fn three_repeated() -> TestResult {
+    run_args(&["-d", THREE.input], "tests/expected/three.txt.d.out")
+}
+
+#[test]
+fn three_repeated_count() -> TestResult {
+    run_args(&["--repeated", "--count", THREE.input], "tests/expected/three.txt.dc.out")
+}
+
+#[test]
+fn three_unique() -> TestResult {
+    run_args(&["-u", THREE.input], "tests/expected/three.txt.u.out")
+}
+
+#[test]
+fn three_repeated_unique() -> TestResult {
+    run_args(&["-d", "-u", THREE.input], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn prefix_check_chars() -> TestResult {
+    run_args(&["-w", "3", "tests/inputs/prefix.txt"], "tests/expected/prefix.txt.w3.out")
+}
+
+#[test]
+fn prefix_check_chars_count() -> TestResult {
+    run_args(
+        &["--check_chars=2", "-c", "tests/inputs/prefix.txt"],
+        "tests/expected/prefix.txt.w2c.out",
+    )
+}
+
+// Empty keys are compared the same as the others, the first line is never skipped
+#[test]
+fn check_chars_zero() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "0"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("a\n");
+    Ok(())
+}
+
+#[test]
+fn check_chars_zero_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "0", "-c"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("   2 a\n");
+    Ok(())
+}
+
+#[test]
+fn leading_empty_lines_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("-c")
+        .write_stdin("\n\na\n")
+        .assert()
+        .success()
+        .stdout("   2 \n   1 a\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_terminated_count() -> TestResult {
+    let expected = fs::read("tests/expected/zero.txt.zc.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-c", "tests/inputs/zero.txt"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn zero_terminated_leading_empty_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-c"])
+        .write_stdin("\0a\0")
+        .assert()
+        .success()
+        .stdout("   1 \0   1 a\0");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn three_global() -> TestResult {
+    run_args(&["--global", THREE.input], "tests/expected/three.txt.global.out")
+}
+
+#[test]
+fn three_global_count() -> TestResult {
+    run_args(&["--global", "-c", THREE.input], "tests/expected/three.txt.global.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn three_count_percent() -> TestResult {
+    run_args(&["-c", "--percent", THREE.input], "tests/expected/three.txt.c.percent.out")
+}
+
+#[test]
+fn three_global_count_format() -> TestResult {
+    run_args(
+        &["--global", "--count_format", "{line}\t{count}", THREE.input],
+        "tests/expected/three.txt.global.format.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn three_in_place() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    let path = folder.path().join("three.txt");
+    fs::copy(THREE.input, &path)?;
+
+    Command::cargo_bin(PRG)?
+        .args([path.to_str().unwrap(), "--in_place"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(fs::read_to_string(&path)?, "a\nb\na\nc\na\nd\n");
+    assert_eq!(fs::read_dir(folder.path())?.count(), 1);
+    Ok(())
+}
+
+#[test]
+fn three_in_place_backup() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    let path = folder.path().join("three.txt");
+    fs::copy(THREE.input, &path)?;
+
+    Command::cargo_bin(PRG)?
+        .args([path.to_str().unwrap(), "-d", "--in_place=.bak"])
+        .assert()
+        .success()
+        .stdout("");
+
+    let backup = folder.path().join("three.txt.bak");
+    assert_eq!(fs::read_to_string(&path)?, fs::read_to_string("tests/expected/three.txt.d.out")?);
+    assert_eq!(fs::read_to_string(backup)?, fs::read_to_string(THREE.input)?);
+    Ok(())
+}
+
+#[test]
+fn dies_in_place_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("--in_place")
+        .write_stdin("a\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't edit stdin in place"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn three_global_spill() -> TestResult {
+    run_args(&["--global", "-S", "1", THREE.input], "tests/expected/three.txt.global.out")
+}
+
+#[test]
+fn three_global_count_spill_temp_dir() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    run_args(
+        &["--global", "-c", "--buffer_size=1", "-T", folder.path().to_str().unwrap(), THREE.input],
+        "tests/expected/three.txt.global.c.out",
+    )?;
+
+    assert_eq!(fs::read_dir(folder.path())?.count(), 0);
+    Ok(())
+}
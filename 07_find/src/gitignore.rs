@@ -0,0 +1,64 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use walkdir::DirEntry;
+
+// Later files win, the same way as .ignore takes precedence over .gitignore in ripgrep
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Rules of the .gitignore and .ignore files found in the walked folders
+///
+/// Files are read once per folder when the walk first looks into it. Rules of a deeper
+/// folder take precedence, so its !whitelist lines can bring back what a parent ignores.
+#[derive(Default)]
+pub struct IgnoreRules {
+    folders: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    pub fn is_ignored(&mut self, entry: &DirEntry) -> bool {
+        let is_dir = entry.file_type().is_dir();
+
+        // Folders above the start path are not part of the walked tree
+        for folder in entry.path().ancestors().skip(1).take(entry.depth()) {
+            let rules = self.folders.entry(folder.to_path_buf()).or_insert_with(|| load(folder));
+            let Some(rules) = rules else {
+                continue;
+            };
+
+            let matched = rules.matched(entry.path(), is_dir);
+            if !matched.is_none() {
+                return matched.is_ignore();
+            }
+        }
+
+        false
+    }
+}
+
+fn load(folder: &Path) -> Option<Gitignore> {
+    let mut builder = GitignoreBuilder::new(folder);
+
+    for name in IGNORE_FILES {
+        let path = folder.join(name);
+        if !path.is_file() {
+            continue;
+        }
+
+        // Lines with bad globs are reported and skipped, the rest of the file still applies
+        if let Some(error) = builder.add(&path) {
+            eprintln!("Error: {error}");
+        }
+    }
+
+    match builder.build() {
+        Ok(rules) if !rules.is_empty() => Some(rules),
+        Ok(_) => None,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            None
+        }
+    }
+}
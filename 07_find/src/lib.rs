@@ -1,52 +1,220 @@
+mod gitignore;
+
 use clap::{arg, builder::PossibleValuesParser, Command};
+use common::{json, listing, locale::Locale};
 use crate::FileEntityType::*;
+use gitignore::IgnoreRules;
+use globset::{GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use regex::Regex;
-use std::error::Error;
+use std::{
+    error::Error,
+    fs::Metadata,
+    io::Write,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 use walkdir::WalkDir;
 
 type DynErrorResult<T> = Result<T, Box<dyn Error>>;
 
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+// Users that prefer find -name globs can make them the --name default
+const NAME_SYNTAX_VARIABLE: &str = "FIND_NAME_SYNTAX";
+
 #[derive(Debug)]
 enum FileEntityType {
     File,
     Dir,
     Link,
+    Socket,      // sockets, FIFOs and devices exist only on unix
+    Fifo,
+    BlockDevice,
+    CharDevice,
+    Executable,  // file with any execute bit, or an executable extension on Windows
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Follow {
+    Never,     // -P, symlinks are reported as links
+    Arguments, // -H, only the start paths are followed
+    Always,    // -L, links are replaced with their targets
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortKey {
+    Name,  // path, so folder content goes right after the folder
+    Size,  // smallest first
+    Mtime, // oldest first
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Paths, // one path per line, or NUL terminated with --print0
+    Long,  // ls -l rows, aligned once all matches are known
+    Json,  // one object per line with the metadata that was read anyway
+}
+
+/// Matched entry that waits for the walk to end with --sort or --long
+struct Found {
+    path: PathBuf,
+    metadata: Option<Metadata>,
+}
+
+#[derive(Debug)]
+enum NamePattern {
+    Regex(Regex),      // part of the file name matches
+    Glob(GlobMatcher), // whole file name matches
+}
+
+impl NamePattern {
+    fn is_match(&self, file_name: &str) -> bool {
+        match self {
+            NamePattern::Regex(regex) => regex.is_match(file_name),
+            NamePattern::Glob(glob) => glob.is_match(file_name),
+        }
+    }
+}
+
+/// Number compared the GNU find way: +N is more than N, -N is less than N, N is exactly N
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumericTest {
+    More(u64),
+    Less(u64),
+    Exactly(u64),
+}
+
+impl NumericTest {
+    fn matches(self, value: u64) -> bool {
+        match self {
+            NumericTest::More(limit) => value > limit,
+            NumericTest::Less(limit) => value < limit,
+            NumericTest::Exactly(limit) => value == limit,
+        }
+    }
+}
+
+/// File length in units, partial units are rounded up the same way as in GNU find
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SizeTest {
+    units: NumericTest,
+    unit: u64,
+}
+
+/// Age of the last modification, counted from the start of the search
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimeTest {
+    Days(NumericTest),    // --mtime, whole days, +1 is at least two days ago as in GNU find
+    Minutes(NumericTest), // --mmin, +N and -N compare the exact age, N is a partial minute
 }
 
 #[derive(Debug)]
 pub struct Config {
     paths: Vec<String>,
-    names: Vec<Regex>,
+    names: Vec<NamePattern>,
     types: Vec<FileEntityType>,
+    sizes: Vec<SizeTest>,
+    times: Vec<TimeTest>,
+    newer: Vec<SystemTime>,
+    now: SystemTime,
+    min_depth: usize,
+    max_depth: usize,
+    empty: bool,
+    print0: bool,
+    output: Output,
+    locale: &'static Locale,
+    follow: Follow,
+    exclude: GlobSet,
+    prune: GlobSet,
+    gitignore: bool,
+    sort: Option<SortKey>,
 }
 
 pub fn get_args() -> DynErrorResult<Config> {
+    let name_syntax = match std::env::var(NAME_SYNTAX_VARIABLE).as_deref() {
+        Ok("glob") => "glob",
+        _ => "regex",
+    };
+
     let mut matches = Command::new("uniq")
         .version("1.0")
         .author("FallenGameR")
         .about("Finds files and folders in the file system")
         .args([
             arg!([PATH] ... "Paths that would be used to start the search from").default_value("."),
-            arg!(-n --name [NAME] ... "File names to look for"),
-            arg!(-t --type [TYPE] ... "File types to look for")
-                .value_parser(PossibleValuesParser::new(&["f", "d", "l"])),
+            arg!(-n --name [NAME] ... "File names to look for, regex or glob per --name_syntax"),
+            arg!(--regex <REGEX> ... "File names that match the regular expression"),
+            arg!(--glob <GLOB> ... "Whole file names that match the glob like *.rs"),
+            arg!(--iname <GLOB> ... "Same as --glob but ignores case"),
+            arg!(--name_syntax <SYNTAX> "How --name is read, the default is from FIND_NAME_SYNTAX")
+                .value_parser(PossibleValuesParser::new(["regex", "glob"]))
+                .default_value(name_syntax),
+            arg!(-t --type [TYPE] ... "Types f d l, s socket, p FIFO, b c device, x executable")
+                .value_parser(PossibleValuesParser::new(["f", "d", "l", "s", "p", "b", "c", "x"])),
+            arg!(--size <SIZE> ... "File size like +1M, -512k or 100c, default unit is 512 bytes")
+                .value_parser(parse_size)
+                .allow_hyphen_values(true),
+            arg!(--mtime <DAYS> ... "Modified days ago, +N is more and -N is less than N days")
+                .value_parser(parse_numeric)
+                .allow_hyphen_values(true),
+            arg!(--mmin <MINUTES> ... "Modified minutes ago, +N is more and -N is less than N")
+                .value_parser(parse_numeric)
+                .allow_hyphen_values(true),
+            arg!(--newer <FILE> ... "Modified more recently than the file"),
+            arg!(--empty "Only empty files and folders without entries"),
+            arg!(follow_always: -L "Follow symlinks, loops are reported and skipped")
+                .overrides_with_all(["follow_never", "follow_arguments"]),
+            arg!(follow_never: -P "Never follow symlinks, that is the default")
+                .overrides_with_all(["follow_always", "follow_arguments"]),
+            arg!(follow_arguments: -H "Follow symlinks only for the start paths")
+                .overrides_with_all(["follow_always", "follow_never"]),
+            arg!(--exclude <GLOB> ... "Hide entries with matching names, folders are still walked"),
+            arg!(--prune_dir <GLOB> ... "Don't descend into matching folders like target or .git"),
+            arg!(--gitignore "Skip entries ignored by .gitignore and .ignore files in the tree"),
+            arg!(--sort <KEY> "Print matches sorted, not in the file system order")
+                .value_parser(PossibleValuesParser::new(["name", "size", "mtime"])),
+            arg!(-l --long "Print ls -l style rows, they appear once the search ends")
+                .conflicts_with_all(["json", "print0"]),
+            arg!(--json "Print a JSON object with path, size, type and mtime per match")
+                .conflicts_with("print0"),
+            arg!(-'0' --print0 "End paths with NUL instead of newline, e.g. for xargs -0"),
+            arg!(--mindepth <LEVELS> "Skip entries less deep than this, 1 skips the start paths")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("0"),
+            arg!(--maxdepth <LEVELS> "Don't descend deeper than this, 1 is the start paths content")
+                .value_parser(clap::value_parser!(usize)),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
-    let names: Vec<String> = matches.remove_many("name").unwrap_or_default().collect();
+    let mut remove_names = |id: &str| -> Vec<String> {
+        matches.remove_many(id).unwrap_or_default().collect()
+    };
+    let (names, regexes, globs, inames) =
+        (remove_names("name"), remove_names("regex"), remove_names("glob"), remove_names("iname"));
+    let (exclude, prune) = (remove_names("exclude"), remove_names("prune_dir"));
+    let name_syntax: String = matches.remove_one("name_syntax").expect("Default value is provided");
+    let sort: Option<String> = matches.remove_one("sort");
     let types: Vec<String> = matches.remove_many("type").unwrap_or_default().collect();
 
+    let sizes: Vec<SizeTest> = matches.remove_many("size").unwrap_or_default().collect();
+    let days = matches.remove_many("mtime").unwrap_or_default().map(TimeTest::Days);
+    let minutes = matches.remove_many("mmin").unwrap_or_default().map(TimeTest::Minutes);
+    let newer: Vec<String> = matches.remove_many("newer").unwrap_or_default().collect();
+
     Ok(Config {
         paths: matches
             .remove_many("PATH")
             .expect("Paths were not provided")
             .collect(),
         names: names
-            .into_iter()
-            .map(|regex_text| {
-                Regex::new(&regex_text)
-                    .map_err(|err| format!("Invalid --name '{}', {}", regex_text, err))
+            .iter()
+            .map(|name| match name_syntax.as_str() {
+                "glob" => parse_glob(name, "name", false),
+                _ => parse_regex(name, "name"),
             })
+            .chain(regexes.iter().map(|regex| parse_regex(regex, "regex")))
+            .chain(globs.iter().map(|glob| parse_glob(glob, "glob", false)))
+            .chain(inames.iter().map(|glob| parse_glob(glob, "iname", true)))
             .collect::<Result<_, _>>()?,
         types: types
             .into_iter()
@@ -54,50 +222,400 @@ pub fn get_args() -> DynErrorResult<Config> {
                 "f" => Ok(File),
                 "d" => Ok(Dir),
                 "l" => Ok(Link),
+                "s" => Ok(Socket),
+                "p" => Ok(Fifo),
+                "b" => Ok(BlockDevice),
+                "c" => Ok(CharDevice),
+                "x" => Ok(Executable),
                 unknown => Err(format!("Unsupported file entiry type: {}", unknown)),
                 // unreachable! could be used instead here, then we don't need Ok() annotation
             })
             .collect::<Result<_, _>>()?,
+        sizes,
+        times: days.chain(minutes).collect(),
+        newer: newer
+            .into_iter()
+            .map(|file| {
+                std::fs::metadata(&file)
+                    .and_then(|metadata| metadata.modified())
+                    .map_err(|err| format!("Can't read --newer file '{file}', {err}"))
+            })
+            .collect::<Result<_, _>>()?,
+        now: SystemTime::now(),
+        min_depth: matches.remove_one("mindepth").expect("Default value is provided"),
+        max_depth: matches.remove_one("maxdepth").unwrap_or(usize::MAX),
+        empty: matches.get_flag("empty"),
+        print0: matches.get_flag("print0"),
+        output: if matches.get_flag("long") {
+            Output::Long
+        } else if matches.get_flag("json") {
+            Output::Json
+        } else {
+            Output::Paths
+        },
+        locale: Locale::from_env(),
+        exclude: parse_glob_set(&exclude, "exclude")?,
+        prune: parse_glob_set(&prune, "prune_dir")?,
+        gitignore: matches.get_flag("gitignore"),
+        sort: sort.as_deref().map(parse_sort_key),
+        follow: if matches.get_flag("follow_always") {
+            Follow::Always
+        } else if matches.get_flag("follow_arguments") {
+            Follow::Arguments
+        } else {
+            Follow::Never
+        },
     })
 }
 
+fn parse_regex(text: &str, option: &str) -> Result<NamePattern, String> {
+    Regex::new(text)
+        .map(NamePattern::Regex)
+        .map_err(|err| format!("Invalid --{option} '{text}', {err}"))
+}
+
+fn parse_glob(text: &str, option: &str, ignore_case: bool) -> Result<NamePattern, String> {
+    GlobBuilder::new(text)
+        .case_insensitive(ignore_case)
+        .literal_separator(true)
+        .build()
+        .map(|glob| NamePattern::Glob(glob.compile_matcher()))
+        .map_err(|err| format!("Invalid --{option} '{text}', {err}"))
+}
+
+fn parse_glob_set(globs: &[String], option: &str) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for text in globs {
+        let glob = GlobBuilder::new(text)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| format!("Invalid --{option} '{text}', {err}"))?;
+        builder.add(glob);
+    }
+
+    builder.build().map_err(|err| format!("Invalid --{option}, {err}"))
+}
+
+fn parse_sort_key(name: &str) -> SortKey {
+    match name {
+        "name" => SortKey::Name,
+        "size" => SortKey::Size,
+        "mtime" => SortKey::Mtime,
+        unknown => unreachable!("Unsupported sort key {unknown}"),
+    }
+}
+
+fn parse_numeric(text: &str) -> Result<NumericTest, String> {
+    let (constructor, number): (fn(u64) -> NumericTest, _) = match text.as_bytes().first() {
+        Some(b'+') => (NumericTest::More, &text[1..]),
+        Some(b'-') => (NumericTest::Less, &text[1..]),
+        _ => (NumericTest::Exactly, text),
+    };
+
+    number
+        .parse()
+        .map(constructor)
+        .map_err(|_| format!("Invalid number '{text}'"))
+}
+
+// Units are c for bytes, w for 2 byte words, b for 512 byte blocks, k, M and G for KiB, MiB, GiB
+fn parse_size(text: &str) -> Result<SizeTest, String> {
+    let (number, unit) = match text.char_indices().last() {
+        Some((index, unit)) if unit.is_ascii_alphabetic() => (&text[..index], unit),
+        _ => (text, 'b'),
+    };
+
+    let unit = match unit {
+        'c' => 1,
+        'w' => 2,
+        'b' => 512,
+        'k' => 1 << 10,
+        'M' => 1 << 20,
+        'G' => 1 << 30,
+        unknown => return Err(format!("Invalid size unit '{unknown}' in '{text}'")),
+    };
+
+    Ok(SizeTest { units: parse_numeric(number)?, unit })
+}
+
 pub fn run(config: Config) -> DynErrorResult<()> {
+    let mut found = Vec::new();
+    let mut ignore_rules = config.gitignore.then(IgnoreRules::default);
+
     for path in &config.paths {
-        for entry in WalkDir::new(path) {
+        let walker = WalkDir::new(path)
+            .min_depth(config.min_depth)
+            .max_depth(config.max_depth)
+            .follow_links(config.follow == Follow::Always)
+            .follow_root_links(config.follow != Follow::Never)
+            .into_iter()
+            .filter_entry(|entry| {
+                let pruned = entry.file_type().is_dir() && config.prune.is_match(entry.file_name());
+                !pruned && !ignore_rules.as_mut().is_some_and(|rules| rules.is_ignored(entry))
+            });
+
+        for entry in walker {
             match entry {
-                Ok(entry) => process(entry, &config),
-                Err(error) => eprint!("Error: {}", error),
+                Ok(entry) => process(entry, &config, &mut found),
+                Err(error) => match (error.path(), error.loop_ancestor()) {
+                    (Some(link), Some(ancestor)) => eprintln!(
+                        "File system loop: '{}' points to its ancestor '{}'",
+                        link.display(),
+                        ancestor.display()
+                    ),
+                    _ => eprint!("Error: {}", error),
+                },
             }
         }
     }
 
+    if let Some(key) = config.sort {
+        sort_found(&mut found, key);
+    }
+
+    if config.output == Output::Long {
+        let entries = found
+            .iter()
+            .filter_map(|entry| Some((entry.path.as_path(), entry.metadata.as_ref()?)));
+        print!("{}", listing::format_long(entries, config.locale)?);
+    } else {
+        for entry in found {
+            print_found(&entry, &config);
+        }
+    }
+
     Ok(())
 }
 
-fn process(entry: walkdir::DirEntry, config: &Config) {
+// Path breaks the ties, so the order is the same on every run and platform
+fn sort_found(found: &mut [Found], key: SortKey) {
+    let size = |entry: &Found| entry.metadata.as_ref().map_or(0, Metadata::len);
+    let modified = |entry: &Found| {
+        let modified = entry.metadata.as_ref().and_then(|metadata| metadata.modified().ok());
+        modified.unwrap_or(SystemTime::UNIX_EPOCH)
+    };
+
+    match key {
+        SortKey::Name => found.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::Size => found.sort_by(|a, b| size(a).cmp(&size(b)).then(a.path.cmp(&b.path))),
+        SortKey::Mtime => {
+            found.sort_by(|a, b| modified(a).cmp(&modified(b)).then(a.path.cmp(&b.path)))
+        }
+    }
+}
+
+fn process(entry: walkdir::DirEntry, config: &Config, found: &mut Vec<Found>) {
     let path = entry.path();
+    if config.exclude.is_match(entry.file_name()) {
+        return;
+    }
 
     // The solution uses to_string_lossy here, but it's not needed to pass tests
     let file_name = path.file_name().unwrap().to_str().unwrap();
 
     let name_match =
         config.names.is_empty() ||
-        config.names.iter().any(|regex| regex.is_match(file_name));
+        config.names.iter().any(|pattern| pattern.is_match(file_name));
 
     let type_match =
         config.types.is_empty() ||
-        config.types.iter().any(|entity_type| match entity_type {
-            File => entry.file_type().is_file(),
-            Dir => entry.file_type().is_dir(),
-            Link => entry.file_type().is_symlink(),
+        config.types.iter().any(|entity_type| type_matches(entity_type, &entry));
+
+    // Metadata needs a syscall, so it is read only when some test needs it
+    let needs_metadata = config.empty
+        || !config.sizes.is_empty()
+        || !config.times.is_empty()
+        || !config.newer.is_empty();
+    let metadata = if needs_metadata { entry.metadata().ok() } else { None };
+
+    let size_match =
+        config.sizes.is_empty() ||
+        metadata.as_ref().is_some_and(|metadata| {
+            config.sizes.iter().all(|size| size.units.matches(metadata.len().div_ceil(size.unit)))
         });
 
+    let time_match =
+        (config.times.is_empty() && config.newer.is_empty()) ||
+        metadata.as_ref().is_some_and(|metadata| time_matches(metadata, config));
+
+    let empty_match =
+        !config.empty ||
+        metadata.as_ref().is_some_and(|metadata| is_empty(path, metadata));
+
     // Another way of doing it would be to pass filter closure
     // into walkdir. The closure would reference config and do
     // essentially the same thing we do here, but would call
     // the type filter less often. But then it would join
     // all the result lines together.
-    if name_match && type_match {
+    if !(name_match && type_match && size_match && time_match && empty_match) {
+        return;
+    }
+
+    let needs_metadata = config.output != Output::Paths
+        || config.sort.is_some_and(|key| key != SortKey::Name);
+    let metadata = match metadata {
+        None if needs_metadata => entry.metadata().ok(),
+        metadata => metadata,
+    };
+
+    let matched = Found { path: path.to_path_buf(), metadata };
+    if config.sort.is_some() || config.output == Output::Long {
+        found.push(matched);
+    } else {
+        print_found(&matched, config);
+    }
+}
+
+fn print_found(found: &Found, config: &Config) {
+    match config.output {
+        Output::Json => println!("{}", format_json(found)),
+        _ => print_path(&found.path, config),
+    }
+}
+
+// Fields that couldn't be read are null, so every line has the same keys
+fn format_json(found: &Found) -> String {
+    let metadata = found.metadata.as_ref();
+    let size = metadata.map(|metadata| metadata.len().to_string());
+    let entity_type = metadata.map(|metadata| json::quote(type_name(metadata.file_type())));
+    let mtime = metadata
+        .and_then(|metadata| metadata.modified().ok())
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|age| age.as_secs().to_string());
+    let null = || "null".to_string();
+
+    format!(
+        r#"{{"path":{},"size":{},"type":{},"mtime":{}}}"#,
+        json::quote(&found.path.to_string_lossy()),
+        size.unwrap_or_else(null),
+        entity_type.unwrap_or_else(null),
+        mtime.unwrap_or_else(null),
+    )
+}
+
+// Same letters as --type takes
+fn type_name(file_type: std::fs::FileType) -> &'static str {
+    let names = [
+        (File, "f"),
+        (Dir, "d"),
+        (Link, "l"),
+        (Socket, "s"),
+        (Fifo, "p"),
+        (BlockDevice, "b"),
+        (CharDevice, "c"),
+    ];
+
+    let found = names.into_iter().find(|(entity_type, _)| is_kind(entity_type, file_type));
+    found.map_or("unknown", |(_, name)| name)
+}
+
+// With --print0 the path bytes go as is, so any file name survives the trip to xargs -0
+fn print_path(path: &Path, config: &Config) {
+    if config.print0 {
+        let mut stdout = std::io::stdout().lock();
+        let written = stdout
+            .write_all(path.as_os_str().as_encoded_bytes())
+            .and_then(|_| stdout.write_all(b"\0"));
+        if let Err(error) = written {
+            eprintln!("Can't print path, error {error}");
+        }
+    } else {
         println!("{}", path.display());
     }
 }
+
+// Folder is peeked for the first entry, links are never empty the same as in GNU find
+fn type_matches(entity_type: &FileEntityType, entry: &walkdir::DirEntry) -> bool {
+    match entity_type {
+        Executable => entry.file_type().is_file() && is_executable(entry),
+        kind => is_kind(kind, entry.file_type()),
+    }
+}
+
+fn is_kind(entity_type: &FileEntityType, file_type: std::fs::FileType) -> bool {
+    match entity_type {
+        File => file_type.is_file(),
+        Dir => file_type.is_dir(),
+        Link => file_type.is_symlink(),
+        special => is_special(special, file_type),
+    }
+}
+
+#[cfg(unix)]
+fn is_special(entity_type: &FileEntityType, file_type: std::fs::FileType) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    match entity_type {
+        Socket => file_type.is_socket(),
+        Fifo => file_type.is_fifo(),
+        BlockDevice => file_type.is_block_device(),
+        CharDevice => file_type.is_char_device(),
+        _ => false,
+    }
+}
+
+// Windows has no sockets, FIFOs or devices in the file system, so nothing matches them
+#[cfg(not(unix))]
+fn is_special(_: &FileEntityType, _: std::fs::FileType) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn is_executable(entry: &walkdir::DirEntry) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    entry.metadata().is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+// There are no execute bits on Windows, the extension is what makes a file runnable
+#[cfg(not(unix))]
+fn is_executable(entry: &walkdir::DirEntry) -> bool {
+    const RUNNABLE: [&str; 5] = ["exe", "com", "bat", "cmd", "ps1"];
+
+    let extension = entry.path().extension().and_then(|extension| extension.to_str());
+    extension.is_some_and(|extension| {
+        RUNNABLE.iter().any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+fn is_empty(path: &Path, metadata: &Metadata) -> bool {
+    if metadata.is_dir() {
+        std::fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+    } else {
+        metadata.is_file() && metadata.len() == 0
+    }
+}
+
+// Times are compared as UTC instants, files from the future are treated as just modified
+fn time_matches(metadata: &Metadata, config: &Config) -> bool {
+    let Ok(modified) = metadata.modified() else {
+        return false;
+    };
+    let age = config.now.duration_since(modified).unwrap_or_default().as_secs();
+
+    let times = config.times.iter().all(|time| match *time {
+        TimeTest::Days(days) => days.matches(age / SECONDS_IN_DAY),
+        TimeTest::Minutes(NumericTest::More(minutes)) => age > minutes.saturating_mul(60),
+        TimeTest::Minutes(NumericTest::Less(minutes)) => age < minutes.saturating_mul(60),
+        TimeTest::Minutes(NumericTest::Exactly(minutes)) => age.div_ceil(60) == minutes,
+    });
+
+    times && config.newer.iter().all(|&reference| modified > reference)
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_size, NumericTest, SizeTest};
+
+    #[test]
+    fn test_parse_size() {
+        let size = |units, unit| Ok(SizeTest { units, unit });
+        assert_eq!(parse_size("+1M"), size(NumericTest::More(1), 1 << 20));
+        assert_eq!(parse_size("-512k"), size(NumericTest::Less(512), 1024));
+        assert_eq!(parse_size("100c"), size(NumericTest::Exactly(100), 1));
+        assert_eq!(parse_size("3"), size(NumericTest::Exactly(3), 512));
+        assert!(parse_size("1x").is_err());
+        assert!(parse_size("+k").is_err());
+    }
+}
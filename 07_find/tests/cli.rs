@@ -1,7 +1,7 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::{borrow::Cow, fs};
+use std::{borrow::Cow, fs, path::Path};
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -39,7 +39,7 @@ fn skips_bad_dir() -> TestResult {
 #[test]
 fn dies_bad_name() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["--name", "*.csv"])
+        .args(["--name", "*.csv"])
         .assert()
         .failure()
         .stderr(predicate::str::contains("Invalid --name '*.csv'"));
@@ -49,9 +49,9 @@ fn dies_bad_name() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dies_bad_type() -> TestResult {
-    let expected = "invalid value 'x' for '--type [<TYPE>]'";
+    let expected = "invalid value 'z' for '--type [<TYPE>]'";
     Command::cargo_bin(PRG)?
-        .args(&["--type", "x"])
+        .args(["--type", "z"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -60,14 +60,14 @@ fn dies_bad_type() -> TestResult {
 
 // --------------------------------------------------
 #[cfg(windows)]
-fn format_file_name(expected_file: &str) -> Cow<str> {
+fn format_file_name(expected_file: &str) -> Cow<'_, str> {
     // Equivalent to: Cow::Owned(format!("{}.windows", expected_file))
     format!("{}.windows", expected_file).into()
 }
 
 // --------------------------------------------------
 #[cfg(not(windows))]
-fn format_file_name(expected_file: &str) -> Cow<str> {
+fn format_file_name(expected_file: &str) -> Cow<'_, str> {
     // Equivalent to: Cow::Borrowed(expected_file)
     expected_file.into()
 }
@@ -96,6 +96,17 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
     Ok(())
 }
 
+// --------------------------------------------------
+fn run_ordered(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(format_file_name(expected_file).as_ref())?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn path1() -> TestResult {
@@ -300,7 +311,7 @@ fn unreadable_dir() -> TestResult {
     //permissions.set_mode(0o000);
 
     std::process::Command::new("chmod")
-        .args(&["000", dirname])
+        .args(["000", dirname])
         .status()
         .expect("failed");
 
@@ -320,3 +331,428 @@ fn unreadable_dir() -> TestResult {
     assert!(stderr.contains("cant-touch-this: Permission denied"));
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn type_f_size_more_2c() -> TestResult {
+    run(&["tests/inputs", "-t", "f", "--size", "+2c"], "tests/expected/type_f_size_more_2c.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn type_f_size_less_3c() -> TestResult {
+    run(&["tests/inputs", "-t", "f", "--size", "-3c"], "tests/expected/type_f_size_less_3c.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_size() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--size", "1x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid size unit 'x' in '1x'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+// Files with the modification times set relative to now, old.txt is 3 days old
+fn aged_files() -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let folder = tempfile::tempdir()?;
+    let three_days = std::time::Duration::from_secs(3 * 24 * 60 * 60);
+
+    let old = fs::File::create(folder.path().join("old.txt"))?;
+    old.set_modified(std::time::SystemTime::now() - three_days)?;
+    fs::File::create(folder.path().join("new.txt"))?;
+
+    Ok(folder)
+}
+
+// --------------------------------------------------
+fn run_aged(args: &[&str], expected_name: &str) -> TestResult {
+    let folder = aged_files()?;
+    let root = folder.path().to_str().unwrap();
+    let expected = format!("{}\n", folder.path().join(expected_name).display());
+
+    Command::cargo_bin(PRG)?
+        .args([root, "-t", "f"])
+        .args(args.iter().map(|arg| arg.replace("{root}", root)))
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mtime_more() -> TestResult {
+    run_aged(&["--mtime", "+1"], "old.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn mtime_exactly() -> TestResult {
+    run_aged(&["--mtime", "0"], "new.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn mmin_less() -> TestResult {
+    run_aged(&["--mmin", "-10"], "new.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn mmin_more_huge() -> TestResult {
+    let folder = aged_files()?;
+    Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "--mmin", "+400000000000000000"])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn newer() -> TestResult {
+    run_aged(&["--newer", "{root}/old.txt"], "new.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_newer() -> TestResult {
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .args(["--newer", &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("Can't read --newer file '{bad}'")));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn maxdepth_1() -> TestResult {
+    run(&["tests/inputs", "--maxdepth", "1"], "tests/expected/maxdepth_1.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn mindepth_3() -> TestResult {
+    run(&["tests/inputs", "--mindepth", "3"], "tests/expected/mindepth_3.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn mindepth_2_maxdepth_2() -> TestResult {
+    run(
+        &["tests/inputs", "--mindepth=2", "--maxdepth=2"],
+        "tests/expected/mindepth_2_maxdepth_2.txt",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    fs::create_dir_all(folder.path().join("full/empty_dir"))?;
+    fs::write(folder.path().join("full/text.txt"), "text")?;
+    fs::write(folder.path().join("empty.txt"), "")?;
+
+    let output = Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "--empty"])
+        .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+
+    let expected = [folder.path().join("empty.txt"), folder.path().join("full/empty_dir")];
+    assert_eq!(lines, expected.map(|path| path.display().to_string()));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn glob_csv() -> TestResult {
+    run(&["tests/inputs", "--glob", "*.csv"], "tests/expected/name_csv.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn iname_csv() -> TestResult {
+    run(&["tests/inputs", "--iname", "*.CSV"], "tests/expected/name_csv.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn regex_csv() -> TestResult {
+    run(&["tests/inputs", "--regex", "[.]csv$"], "tests/expected/name_csv.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn name_syntax_glob() -> TestResult {
+    run(&["tests/inputs", "--name_syntax", "glob", "-n", "*.csv"], "tests/expected/name_csv.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn name_syntax_from_variable() -> TestResult {
+    let expected = fs::read_to_string(format_file_name("tests/expected/g_csv.txt").as_ref())?;
+    Command::cargo_bin(PRG)?
+        .env("FIND_NAME_SYNTAX", "glob")
+        .args(["tests/inputs", "--maxdepth", "1", "-n", "*.csv"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_glob() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--glob", "a[b"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --glob 'a[b'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn print0() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/f", "-t", "f", "-0"])
+        .assert()
+        .success()
+        .stdout(format!("{}\0", Path::new("tests/inputs/f").join("f.txt").display()));
+    Ok(())
+}
+
+// --------------------------------------------------
+// Folder with real/file.txt and link that points to real
+#[cfg(unix)]
+fn linked_files() -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let folder = tempfile::tempdir()?;
+    fs::create_dir(folder.path().join("real"))?;
+    fs::write(folder.path().join("real/file.txt"), "text")?;
+    std::os::unix::fs::symlink("real", folder.path().join("link"))?;
+    Ok(folder)
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+fn run_linked(start: &str, args: &[&str], expected: &[&str]) -> TestResult {
+    let folder = linked_files()?;
+    let output = Command::cargo_bin(PRG)?
+        .arg(folder.path().join(start))
+        .args(args)
+        .args(["-t", "f"])
+        .output()?;
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    let expected: Vec<String> = expected
+        .iter()
+        .map(|path| folder.path().join(start).join(path).display().to_string())
+        .collect();
+    assert_eq!(lines, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn follow_never() -> TestResult {
+    run_linked("", &[], &["real/file.txt"])?;
+    run_linked("link", &["-P"], &[])
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn follow_always() -> TestResult {
+    run_linked("", &["-L"], &["link/file.txt", "real/file.txt"])
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn follow_arguments() -> TestResult {
+    run_linked("link", &["-H"], &["file.txt"])?;
+    run_linked("", &["-H"], &["real/file.txt"])
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn follow_always_reports_loop() -> TestResult {
+    let folder = linked_files()?;
+    std::os::unix::fs::symlink("..", folder.path().join("real/loop"))?;
+    Command::cargo_bin(PRG)?
+        .arg(folder.path())
+        .args(["-L", "-t", "f"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("File system loop: '"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn prune_dir_a() -> TestResult {
+    run(&["tests/inputs", "--prune_dir", "a"], "tests/expected/prune_dir_a.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn exclude_csv() -> TestResult {
+    run(&["tests/inputs", "--exclude", "*.csv"], "tests/expected/exclude_csv.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn exclude_mp3_prune_dir_e() -> TestResult {
+    run(
+        &["tests/inputs", "--exclude", "*.mp3", "--prune_dir", "e", "--prune_dir", "x*"],
+        "tests/expected/exclude_mp3_prune_dir_e.txt",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn sort_name() -> TestResult {
+    run_ordered(&["tests/inputs", "--sort", "name"], "tests/expected/sort_name.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn sort_size() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    for (name, size) in [("a.txt", 10), ("b.txt", 1), ("c.txt", 5)] {
+        fs::write(folder.path().join(name), "x".repeat(size))?;
+    }
+    let expected = ["b.txt", "c.txt", "a.txt"]
+        .map(|name| format!("{}\n", folder.path().join(name).display()))
+        .concat();
+
+    Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "-t", "f", "--sort=size"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sort_mtime() -> TestResult {
+    let folder = aged_files()?;
+    let expected = format!(
+        "{}\n{}\n",
+        folder.path().join("old.txt").display(),
+        folder.path().join("new.txt").display()
+    );
+
+    Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "-t", "f", "--sort", "mtime"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn gitignore() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    let root = folder.path();
+    let path = |name: &str| root.join(name.replace('/', std::path::MAIN_SEPARATOR_STR));
+
+    fs::create_dir_all(path("build"))?;
+    fs::create_dir_all(path("sub"))?;
+    fs::write(path(".gitignore"), "*.log\nbuild/\n!keep.log\n")?;
+    fs::write(path("sub/.ignore"), "c.txt\n!e.log\n")?;
+    let files = ["a.txt", "b.log", "keep.log", "build/out.txt"];
+    for name in files.into_iter().chain(["sub/c.txt", "sub/d.txt", "sub/e.log"]) {
+        fs::write(path(name), "")?;
+    }
+
+    let expected = ["a.txt", "keep.log", "sub/d.txt", "sub/e.log"]
+        .map(|name| format!("{}\n", path(name).display()))
+        .concat();
+
+    Command::cargo_bin(PRG)?
+        .args([root.to_str().unwrap(), "-t", "f", "--name", "txt|log"])
+        .args(["--gitignore", "--sort=name"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn type_s_x() -> TestResult {
+    use std::os::unix::fs::PermissionsExt;
+
+    let folder = tempfile::tempdir()?;
+    let script = folder.path().join("run.sh");
+    fs::write(&script, "#!/bin/sh\n")?;
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+    fs::write(folder.path().join("data.txt"), "text")?;
+    let _listener = std::os::unix::net::UnixListener::bind(folder.path().join("app.sock"))?;
+
+    let expected = ["app.sock", "run.sh"]
+        .map(|name| format!("{}\n", folder.path().join(name).display()))
+        .concat();
+
+    Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "-t", "s", "-t", "x", "--sort=name"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json() -> TestResult {
+    let folder = tempfile::tempdir()?;
+    let file = folder.path().join("data.txt");
+    fs::write(&file, "12345")?;
+    let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1000);
+    fs::File::options().write(true).open(&file)?.set_modified(modified)?;
+
+    // Debug formatting escapes the path the same way as JSON does for plain names
+    let path = format!("{:?}", file.display().to_string());
+    let expected = format!("{{\"path\":{path},\"size\":5,\"type\":\"f\",\"mtime\":1000}}\n");
+
+    Command::cargo_bin(PRG)?
+        .args([folder.path().to_str().unwrap(), "-t", "f", "--json"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn long() -> TestResult {
+    let output = Command::cargo_bin(PRG)?
+        .args(["tests/inputs/a", "--long", "--sort", "name", "-t", "f"])
+        .output()?;
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|row| row.split_whitespace().collect()).collect();
+    let names: Vec<&str> = rows.iter().map(|row| *row.last().unwrap()).collect();
+    assert_eq!(names.len(), 3);
+    assert!(names[0].ends_with("a.txt") && names[1].ends_with("b.csv"));
+    assert!(names[2].ends_with("c.mp3"));
+    assert!(rows.iter().all(|row| row[0].starts_with('-') && row[4] == "2"));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Measures field extraction over a large TSV file
+//! and compares it with GNU cut found in PATH
+//!
+//! Input is a file with a million rows in the temp folder, it is generated once and reused.
+//! Row count can be changed with `CUT_BENCH_ROWS=<count> cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const DEFAULT_ROWS: usize = 1_000_000;
+
+fn input_file(rows: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cut-bench-{rows}.tsv"));
+    if path.exists() {
+        return path;
+    }
+
+    let mut text = String::new();
+    for row in 0..rows {
+        let _ = writeln!(text, "{row}\tname {}\t{}\tsome text\t\"quoted\"", row % 977, row * 7);
+    }
+    fs::write(&path, text).expect("Can't write bench input");
+    path
+}
+
+fn cut(program: &str, args: &[&str], path: &PathBuf) {
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdout(Stdio::null())
+        .status()
+        .expect("Can't run cut");
+    assert!(status.success());
+}
+
+fn million_rows(c: &mut Criterion) {
+    let rows = std::env::var("CUT_BENCH_ROWS")
+        .ok()
+        .and_then(|rows| rows.parse().ok())
+        .unwrap_or(DEFAULT_ROWS);
+    let path = input_file(rows);
+
+    let mut group = c.benchmark_group(format!("cut -f over {rows} rows"));
+    group.sample_size(10);
+
+    let this = env!("CARGO_BIN_EXE_cut");
+    group.bench_function("this fields", |b| b.iter(|| cut(this, &["-f", "2,4"], &path)));
+    group.bench_function("this quoted", |b| b.iter(|| cut(this, &["-f", "5,1"], &path)));
+    group.bench_function("this prefixed", |b| {
+        b.iter(|| cut(this, &["-f", "2", "--with_filename"], &path))
+    });
+    group.bench_function("gnu fields", |b| b.iter(|| cut("cut", &["-f", "2,4"], &path)));
+
+    group.finish();
+}
+
+criterion_group!(benches, million_rows);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Measures char extraction with wide selections over long lines
+//! and compares it with GNU cut found in PATH
+//!
+//! Input is a file with long multi-byte lines in the temp folder, it is generated once and reused.
+//! Line length can be changed with `CUT_BENCH_CHARS=<count> cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const DEFAULT_CHARS: usize = 20_000;
+const LINES: usize = 200;
+const WORD: &str = "Überraschung ";
+
+fn input_file(chars: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cut-bench-{chars}.txt"));
+    if path.exists() {
+        return path;
+    }
+
+    let line: String = WORD.chars().cycle().take(chars).collect();
+    fs::write(&path, format!("{line}\n").repeat(LINES)).expect("Can't write bench input");
+    path
+}
+
+fn cut(program: &str, ranges: &str, path: &PathBuf) {
+    let status = Command::new(program)
+        .args(["-c", ranges])
+        .arg(path)
+        .stdout(Stdio::null())
+        .status()
+        .expect("Can't run cut");
+    assert!(status.success());
+}
+
+fn wide_chars(c: &mut Criterion) {
+    let chars = std::env::var("CUT_BENCH_CHARS")
+        .ok()
+        .and_then(|chars| chars.parse().ok())
+        .unwrap_or(DEFAULT_CHARS);
+    let path = input_file(chars);
+    let all = format!("1-{chars}");
+    let reversed = format!("{chars}-1");
+
+    let mut group = c.benchmark_group(format!("cut -c over {LINES} lines of {chars} chars"));
+    group.sample_size(10);
+
+    let this = env!("CARGO_BIN_EXE_cut");
+    group.bench_function("this forward", |b| b.iter(|| cut(this, &all, &path)));
+    group.bench_function("this reversed", |b| b.iter(|| cut(this, &reversed, &path)));
+    group.bench_function("gnu forward", |b| b.iter(|| cut("cut", &all, &path)));
+
+    group.finish();
+}
+
+criterion_group!(benches, wide_chars);
+criterion_main!(benches);
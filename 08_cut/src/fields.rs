@@ -0,0 +1,147 @@
+use crate::ranges::ranges_iter;
+use csv_core::{ReadRecordResult, Reader, ReaderBuilder, WriteResult, Writer, WriterBuilder};
+use std::ops::RangeInclusive;
+
+/// Splits lines into fields with the csv quoting rules and joins the selected ones back
+///
+/// Parser, writer and their buffers are kept between lines,
+/// csv::Reader and csv::Writer would allocate new buffers for every line of the input.
+pub struct FieldCutter {
+    reader: Reader,
+    writer: Writer,
+    fields: Vec<u8>,  // unquoted fields of the last line one after another
+    ends: Vec<usize>, // where each of the fields ends in fields
+    count: usize,     // number of fields in the last line
+    output: Vec<u8>,
+}
+
+impl FieldCutter {
+    pub fn new(delimeter: u8) -> Self {
+        FieldCutter {
+            reader: ReaderBuilder::new().delimiter(delimeter).build(),
+            writer: WriterBuilder::new().delimiter(delimeter).build(),
+            fields: vec![0; 1024],
+            ends: vec![0; 64],
+            count: 0,
+            output: Vec::with_capacity(1024),
+        }
+    }
+
+    /// Selected fields of the line, an empty line has no fields to select
+    pub fn cut(&mut self, line: &str, ranges: &[RangeInclusive<usize>]) -> &[u8] {
+        self.split(line.as_bytes());
+        if self.count == 0 {
+            return &[];
+        }
+
+        let (fields, ends) = (&self.fields, &self.ends[..self.count]);
+        let selected = ranges_iter(ranges, ends.len()).map(|i| {
+            let start = if i == 0 { 0 } else { ends[i - 1] };
+            &fields[start..ends[i]]
+        });
+        join(&mut self.writer, &mut self.output, selected)
+    }
+
+    /// Fields as a single csv record, quoted where the delimeter or quotes require it
+    pub fn write<'a>(&mut self, fields: impl IntoIterator<Item = &'a [u8]>) -> &[u8] {
+        join(&mut self.writer, &mut self.output, fields)
+    }
+
+    // The reader starts over for every line, so an unclosed quote doesn't spill into the next one
+    fn split(&mut self, mut line: &[u8]) {
+        self.reader.reset();
+        let (mut written, mut ended) = (0, 0);
+
+        loop {
+            let (result, read, output, ends) = self.reader.read_record(
+                line,
+                &mut self.fields[written..],
+                &mut self.ends[ended..],
+            );
+            line = &line[read..];
+            written += output;
+            ended += ends;
+
+            match result {
+                // Next call gets empty input, that finishes the last field
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => self.fields.resize(self.fields.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len() * 2, 0),
+                ReadRecordResult::Record | ReadRecordResult::End => break,
+            }
+        }
+
+        self.count = ended;
+    }
+}
+
+// Output is the same as of csv::Writer::write_record without the line break,
+// e.g. a record with a single empty field or without fields becomes ""
+fn join<'o, 'a>(
+    writer: &mut Writer,
+    output: &'o mut Vec<u8>,
+    fields: impl IntoIterator<Item = &'a [u8]>,
+) -> &'o [u8] {
+    output.clear();
+
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            append(output, 2, |buffer| writer.delimiter(buffer));
+        }
+        append(output, 2 + 2 * field.len(), |buffer| {
+            let (result, _, written) = writer.field(field, buffer);
+            (result, written)
+        });
+    }
+
+    // Terminator closes the quotes and resets the writer for the next record
+    append(output, 3, |buffer| writer.terminator(buffer));
+    output.pop();
+    output
+}
+
+// Writer gets the space of the worst case, so it never runs out of it midway
+fn append(
+    output: &mut Vec<u8>,
+    reserved: usize,
+    write: impl FnOnce(&mut [u8]) -> (WriteResult, usize),
+) {
+    let start = output.len();
+    output.resize(start + reserved, 0);
+    let (result, written) = write(&mut output[start..]);
+    debug_assert_eq!(result, WriteResult::InputEmpty);
+    output.truncate(start + written);
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::FieldCutter;
+
+    #[test]
+    fn test_cut() {
+        let mut cutter = FieldCutter::new(b',');
+        assert_eq!(cutter.cut("a,b,c", &[2..=2, 0..=0]), b"c,a");
+        assert_eq!(cutter.cut(r#""x,y",z"#, &[0..=0]), br#""x,y""#);
+        assert_eq!(cutter.cut(r#""q ""w"" e",r"#, &[0..=1]), br#""q ""w"" e",r"#);
+        assert_eq!(cutter.cut("a,,c", &[1..=1]), br#""""#);
+        assert_eq!(cutter.cut("a,b", &[4..=4]), br#""""#);
+        assert_eq!(cutter.cut("", &[0..=0]), b"");
+
+        // Unclosed quote ends with the line
+        assert_eq!(cutter.cut(r#""open,x"#, &[0..=0]), br#""open,x""#);
+        assert_eq!(cutter.cut("d,e", &[1..=1]), b"e");
+
+        // Buffers grow to fit long lines with many fields
+        let line = vec!["field"; 300].join(",");
+        assert_eq!(cutter.cut(&line, &[299..=299]), b"field");
+        assert_eq!(cutter.cut(&line, &[0..=299]), line.as_bytes());
+    }
+
+    #[test]
+    fn test_write() {
+        let mut cutter = FieldCutter::new(b'\t');
+        assert_eq!(cutter.write([&b"a"[..], b"b c", b"d\te"]), b"a\tb c\t\"d\te\"");
+        assert_eq!(cutter.write([]), br#""""#);
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// One step of a --json selector like `user.tags[0]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Key(String),  // object member
+    Index(usize), // array item, zero-based the same way as in JSON tools
+}
+
+pub type Selector = Vec<Step>;
+
+/// Parses comma separated selectors, keys are split by dots and followed by [N] indexes
+pub fn parse_selectors(text: &str) -> Result<Vec<Selector>> {
+    text.split(',').map(|selector| parse_selector(selector.trim())).collect()
+}
+
+fn parse_selector(selector: &str) -> Result<Selector> {
+    let mut steps = Vec::new();
+
+    for (position, part) in selector.split('.').enumerate() {
+        let (key, indexes) = part.split_at(part.find('[').unwrap_or(part.len()));
+
+        // Only the top level array can be indexed without a key, like [0].name
+        match key.is_empty() {
+            true if position > 0 || indexes.is_empty() => {
+                bail!("Invalid selector '{selector}' - empty key")
+            }
+            true => {}
+            false => steps.push(Step::Key(key.to_string())),
+        }
+        steps.extend(parse_indexes(selector, indexes)?);
+    }
+
+    Ok(steps)
+}
+
+fn parse_indexes(selector: &str, mut indexes: &str) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+
+    while !indexes.is_empty() {
+        let Some((index, rest)) = indexes.strip_prefix('[').and_then(|rest| rest.split_once(']'))
+        else {
+            bail!("Invalid selector '{selector}' - expected [N] after the key");
+        };
+        match index.parse() {
+            Ok(index) => steps.push(Step::Index(index)),
+            Err(error) => bail!("Invalid selector '{selector}' - index '{index}' {error}"),
+        }
+        indexes = rest;
+    }
+
+    Ok(steps)
+}
+
+fn select<'a>(value: &'a Value, selector: &Selector) -> Option<&'a Value> {
+    selector.iter().try_fold(value, |value, step| match step {
+        Step::Key(key) => value.get(key),
+        Step::Index(index) => value.get(index),
+    })
+}
+
+/// Selected values of the JSON line, either joined by the delimeter or as a JSON array
+///
+/// Joined strings are printed without quotes and missing values are empty,
+/// in the array they stay JSON and missing values are null.
+pub fn extract(line: &str, selectors: &[Selector], delimeter: &str, array: bool) -> Result<String> {
+    let value: Value = serde_json::from_str(line)?;
+    let selected = selectors.iter().map(|selector| select(&value, selector));
+
+    if array {
+        let values = selected.map(|value| value.cloned().unwrap_or(Value::Null));
+        return Ok(Value::Array(values.collect()).to_string());
+    }
+
+    let texts: Vec<String> = selected
+        .map(|value| match value {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(text)) => text.clone(),
+            Some(other) => other.to_string(),
+        })
+        .collect();
+    Ok(texts.join(delimeter))
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{extract, parse_selectors, Step};
+
+    #[test]
+    fn test_parse_selectors() {
+        let key = |key: &str| Step::Key(key.to_string());
+        assert_eq!(parse_selectors("level").unwrap(), [vec![key("level")]]);
+        assert_eq!(
+            parse_selectors("user.name, tags[1]").unwrap(),
+            [vec![key("user"), key("name")], vec![key("tags"), Step::Index(1)]]
+        );
+        assert_eq!(
+            parse_selectors("[0].spans[2][3]").unwrap(),
+            [vec![Step::Index(0), key("spans"), Step::Index(2), Step::Index(3)]]
+        );
+        assert!(parse_selectors("user..name").is_err());
+        assert!(parse_selectors("tags[x]").is_err());
+        assert!(parse_selectors("tags[1").is_err());
+        assert!(parse_selectors("").is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let line = r#"{"level":"warn","user":{"id":7,"name":"Ann"},"tags":["a","b"],"gone":null}"#;
+        let selectors = parse_selectors("level,user.id,tags[1],user,missing,gone").unwrap();
+
+        assert_eq!(
+            extract(line, &selectors, "\t", false).unwrap(),
+            "warn\t7\tb\t{\"id\":7,\"name\":\"Ann\"}\t\t"
+        );
+        assert_eq!(
+            extract(line, &selectors, "\t", true).unwrap(),
+            r#"["warn",7,"b",{"id":7,"name":"Ann"},null,null]"#
+        );
+        assert!(extract("not json", &selectors, "\t", false).is_err());
+    }
+}
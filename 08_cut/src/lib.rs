@@ -1,12 +1,17 @@
+mod fields;
+mod json;
 mod ranges;
 
 use anyhow::{bail, Result};
-use clap::{arg, Command};
+use clap::{arg, builder::PossibleValuesParser, Command};
+use common::ranges::parse as parse_ranges;
 use csv::StringRecord;
-use ranges::ranges_iter;
+use fields::FieldCutter;
+use ranges::{input_order, ranges_iter};
+use regex::Regex;
 use std::{
-    io::{BufRead, BufReader},
-    num::NonZeroUsize,
+    borrow::Cow,
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Write},
     ops::RangeInclusive,
 };
 
@@ -17,26 +22,74 @@ pub enum ExtractedRanges {
     Bytes(Positions),
     Chars(Positions),
     Fields(Positions),
+    Json(Vec<json::Selector>),
+}
+
+/// Where --with_filename puts the name of the file the lines came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilenameStyle {
+    Prefix, // file:line the same way as grep does
+    Header, // ==> file <== above the lines the same way as head does
+}
+
+/// How a line is split into fields
+#[derive(Debug)]
+pub enum Splitter {
+    Csv(u8),      // single byte delimeter, fields can be quoted
+    Text,         // multi-byte delimeter is matched literally
+    Regex(Regex), // --delimeter_regex, -d joins the output fields
+    Whitespace,   // -w, -d joins the output fields
 }
 
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
     extracted: ExtractedRanges,
-    delimeter: char,
+    delimeter: String,
+    splitter: Splitter,
+    only_delimited: bool,
+    csv: bool,
+    json_array: bool,
+    with_filename: Option<FilenameStyle>,
 }
 
 pub fn run(config: Config) -> Result<()> {
-    for path in &config.files {
-        match open(path) {
-            Ok(reader) => process_file(path, reader, &config)?,
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let mut shown = 0;
+
+    for (index, path) in config.files.iter().enumerate() {
+        // Files that can't be opened get no header, the same as in head
+        let reader = open(path);
+        if reader.is_ok() && config.with_filename == Some(FilenameStyle::Header) {
+            if shown > 0 {
+                writeln!(out)?;
+            }
+            let header = common::header::format(common::header::DEFAULT_FORMAT, path, index + 1);
+            writeln!(out, "{header}")?;
+            shown += 1;
+        }
+
+        match reader {
+            Ok(reader) if config.csv => process_csv(path, reader, &config, &mut out)?,
+            Ok(reader) if matches!(config.extracted, ExtractedRanges::Bytes(_)) => {
+                process_bytes(path, reader, &config, &mut out)?
+            }
+            Ok(reader) => process_file(path, reader, &config, &mut out)?,
             Err(error) => eprintln!("Can't open file '{}', error {}", &path, error),
         }
     }
 
+    out.flush()?;
     Ok(())
 }
 
+fn filename_prefix(path: &str, config: &Config) -> String {
+    match config.with_filename {
+        Some(FilenameStyle::Prefix) => format!("{path}:"),
+        _ => String::new(),
+    }
+}
+
 fn open(path: &str) -> Result<Box<dyn BufRead>> {
     match path {
         "-" => Ok(Box::new(BufReader::new(std::io::stdin()))),
@@ -61,11 +114,29 @@ pub fn get_args() -> Result<Config> {
             arg!(-f --fields <FIELDS> "What field ranges to extract, e.g. 1, 3")
                 .value_parser(parse_ranges)
                 .conflicts_with_all(["bytes", "chars"]),
-            arg!(-d --delimeter <DELIMETER> "Fields delimeter, tab is default")
-                .value_parser(clap::value_parser!(char))
+            arg!(--json <SELECTORS> "Keys to extract from JSON lines, e.g. level,user.name,tags[0]")
+                .value_parser(json::parse_selectors)
+                .conflicts_with_all(["bytes", "chars", "fields"]),
+            arg!(--json_array "Print --json values as a JSON array instead of joining them")
+                .requires("json"),
+            arg!(--input_order "Print every position once in the input order, like GNU cut does")
+                .conflicts_with("json"),
+            arg!(--with_filename [STYLE] "Show file names as prefix of lines or as =header")
+                .value_parser(PossibleValuesParser::new(["prefix", "header"]))
+                .require_equals(true)
+                .default_missing_value("prefix"),
+            arg!(-d --delimeter <DELIMETER> "Fields delimeter, tab is default, can be a string")
+                .value_parser(clap::builder::NonEmptyStringValueParser::new())
                 .default_value("\t"),
+            arg!(--delimeter_regex <REGEX> "Fields are split by regex matches, -d joins them")
+                .value_parser(parse_regex)
+                .conflicts_with("whitespace"),
+            arg!(--csv "Parse the whole input as CSV, quoted fields can span lines")
+                .conflicts_with_all(["whitespace", "delimeter_regex"]),
+            arg!(-s --only_delimited "Skip lines without the delimeter when extracting fields"),
+            arg!(-w --whitespace "Fields are split by runs of spaces and tabs, -d joins them"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     // Make sure that only one range type is provided
     let mut selected_count = 0;
@@ -86,8 +157,38 @@ pub fn get_args() -> Result<Config> {
         selected = "fields";
     }
 
+    if matches.contains_id("json") {
+        selected_count += 1;
+        selected = "json";
+    }
+
     if selected_count != 1 {
-        bail!("Please provide either --bytes --chars or --fields once");
+        bail!("Please provide either --bytes --chars --fields or --json once");
+    }
+
+    // clap doesn't check requires when the required arg conflicts with a present one
+    if matches.get_flag("only_delimited") && selected != "fields" {
+        bail!("Lines can be skipped with --only_delimited only when extracting --fields");
+    }
+
+    if matches.get_flag("whitespace") && selected != "fields" {
+        bail!("Lines can be split by --whitespace only when extracting --fields");
+    }
+
+    if matches.contains_id("delimeter_regex") && selected != "fields" {
+        bail!("Lines can be split by --delimeter_regex only when extracting --fields");
+    }
+
+    let delimeter: String = matches
+        .remove_one("delimeter")
+        .expect("No delimeter was provided");
+
+    if matches.get_flag("csv") && selected != "fields" {
+        bail!("Input can be parsed as --csv only when extracting --fields");
+    }
+
+    if matches.get_flag("csv") && delimeter.len() != 1 {
+        bail!("Delimeter '{delimeter}' is not a single byte, --csv can't use it");
     }
 
     // Composing the config
@@ -96,63 +197,192 @@ pub fn get_args() -> Result<Config> {
             .remove_many("FILES")
             .expect("No file paths provided")
             .collect(),
-        extracted: {
-            let ranges = matches
-                .remove_one(selected)
-                .expect("No ranges were provided");
-
-            match selected {
-                "bytes" => ExtractedRanges::Bytes(ranges),
-                "chars" => ExtractedRanges::Chars(ranges),
-                "fields" => ExtractedRanges::Fields(ranges),
-                _ => unreachable!("Unknown range type"),
+        extracted: match selected {
+            "json" => ExtractedRanges::Json(
+                matches
+                    .remove_one("json")
+                    .expect("No selectors were provided"),
+            ),
+            _ => {
+                let ranges: Positions = matches
+                    .remove_one(selected)
+                    .expect("No ranges were provided");
+
+                // Ranges go in the given order by default, e.g. -f 3,1 swaps the fields
+                let ranges = match matches.get_flag("input_order") {
+                    true => input_order(&ranges),
+                    false => ranges,
+                };
+
+                match selected {
+                    "bytes" => ExtractedRanges::Bytes(ranges),
+                    "chars" => ExtractedRanges::Chars(ranges),
+                    "fields" => ExtractedRanges::Fields(ranges),
+                    _ => unreachable!("Unknown range type"),
+                }
             }
         },
-        delimeter: matches
-            .remove_one("delimeter")
-            .expect("No delimeter was provided"),
+        splitter: match matches.remove_one("delimeter_regex") {
+            Some(regex) => Splitter::Regex(regex),
+            None if matches.get_flag("whitespace") => Splitter::Whitespace,
+            None if delimeter.len() == 1 => Splitter::Csv(delimeter.as_bytes()[0]),
+            None => Splitter::Text,
+        },
+        delimeter,
+        only_delimited: matches.get_flag("only_delimited"),
+        csv: matches.get_flag("csv"),
+        json_array: matches.get_flag("json_array"),
+        with_filename: matches
+            .remove_one::<String>("with_filename")
+            .map(|style| parse_filename_style(&style)),
     })
 }
 
-fn parse_ranges(ranges: &str) -> Result<Vec<RangeInclusive<usize>>> {
-    ranges.split(',').map(|x| parse_range(x.trim())).collect()
+fn parse_filename_style(style: &str) -> FilenameStyle {
+    match style {
+        "prefix" => FilenameStyle::Prefix,
+        "header" => FilenameStyle::Header,
+        unknown => unreachable!("Unsupported file name style {unknown}"),
+    }
 }
 
-fn parse_range(range: &str) -> Result<RangeInclusive<usize>> {
-    let result: Result<Vec<NonZeroUsize>, _> = range.split('-').map(str::parse).collect();
+fn parse_regex(regex: &str) -> Result<Regex> {
+    match Regex::new(regex) {
+        Ok(regex) if regex.is_match("") => bail!("Invalid regex '{regex}' - it matches empty text"),
+        Ok(regex) => Ok(regex),
+        Err(error) => bail!("Invalid regex '{regex}' - {error}"),
+    }
+}
 
-    // Input: inclusive range as indexes, positive indexes
-    // Output: inclusive range as range, zero-based indexes
-    let construct = |start, end| -> Result<RangeInclusive<usize>> {
-        Ok(usize::from(start) - 1..=usize::from(end) - 1)
+// Line, cutter and output buffers are reused, so long inputs are cut without an allocation
+// per line. Errors are reported and the line is skipped, the rest of the file is still cut.
+fn process_file(
+    path: &str,
+    mut reader: Box<dyn BufRead>,
+    config: &Config,
+    out: &mut impl Write,
+) -> Result<()> {
+    let prefix = filename_prefix(path, config);
+    let mut cutter = match config.splitter {
+        Splitter::Csv(delimeter) => Some(FieldCutter::new(delimeter)),
+        _ => None,
+    };
+    let mut buffer = String::new();
+
+    // GNU cut prints lines without the delimeter as is, or skips them with -s,
+    // instead of treating the whole line as the first field
+    let delimited = |line: &str| match &config.splitter {
+        Splitter::Csv(_) | Splitter::Text => line.contains(&config.delimeter),
+        Splitter::Regex(regex) => regex.is_match(line),
+        Splitter::Whitespace => line.split_whitespace().nth(1).is_some(),
     };
 
-    match result {
-        Err(error) => bail!("Invalid range '{}' - {}", range, error),
-        Ok(parts) => match parts.len() {
-            1 => construct(parts[0], parts[0]),
-            2 => construct(parts[0], parts[1]),
-            _ => bail!(
-                "Invalid range '{}' - wrong number of range parts {}",
-                range,
-                parts.len()
-            ),
-        },
+    loop {
+        buffer.clear();
+        match reader.read_line(&mut buffer) {
+            // Line that is not UTF-8 is consumed, other errors would repeat on every read
+            Err(error) if error.kind() == ErrorKind::InvalidData => {
+                eprintln!("Can't read line from file '{path}', error {error}");
+                continue;
+            }
+            Err(error) => {
+                eprintln!("Can't read file '{path}', error {error}");
+                break;
+            }
+            Ok(0) => break,
+            Ok(_) => {}
+        }
+
+        // Same line endings are removed as with BufRead::lines
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let extracted: Cow<[u8]> = match &config.extracted {
+            ExtractedRanges::Bytes(_) => unreachable!("Bytes are processed as raw lines"),
+            ExtractedRanges::Json(_) if line.trim().is_empty() => continue,
+            ExtractedRanges::Json(selectors) => {
+                match json::extract(line, selectors, &config.delimeter, config.json_array) {
+                    Ok(values) => values.into_bytes().into(),
+                    Err(error) => {
+                        eprintln!("Can't parse JSON in '{path}', error {error}");
+                        continue;
+                    }
+                }
+            }
+            ExtractedRanges::Chars(ranges) => extract_chars(line, ranges).into_bytes().into(),
+            ExtractedRanges::Fields(_) if !delimited(line) => {
+                if config.only_delimited {
+                    continue;
+                }
+                line.as_bytes().into()
+            }
+            ExtractedRanges::Fields(ranges) => match (&config.splitter, &mut cutter) {
+                (Splitter::Csv(_), Some(cutter)) => cutter.cut(line, ranges).into(),
+                (Splitter::Csv(_), None) => unreachable!("Cutter is created for csv splitter"),
+                (Splitter::Text, _) => {
+                    let fields = line.split(config.delimeter.as_str());
+                    extract_split(fields, &config.delimeter, ranges).into_bytes().into()
+                }
+                (Splitter::Regex(regex), _) => {
+                    let fields = regex.split(line);
+                    extract_split(fields, &config.delimeter, ranges).into_bytes().into()
+                }
+                (Splitter::Whitespace, _) => {
+                    let fields = line.split_whitespace();
+                    extract_split(fields, &config.delimeter, ranges).into_bytes().into()
+                }
+            },
+        };
+
+        out.write_all(prefix.as_bytes())?;
+        out.write_all(&extracted)?;
+        out.write_all(b"\n")?;
     }
+
+    Ok(())
 }
 
-fn process_file(path: &str, reader: Box<dyn BufRead>, config: &Config) -> Result<()> {
-    for line in reader.lines() {
-        match line {
-            Err(error) => eprintln!("Can't read line from file '{path}', error {error}"),
-            Ok(line) => {
-                match &config.extracted {
-                    ExtractedRanges::Bytes(ranges) => println!("{}", extract_bytes(&line, ranges)),
-                    ExtractedRanges::Chars(ranges) => println!("{}", extract_chars(&line, ranges)),
-                    ExtractedRanges::Fields(ranges) => {
-                        print!("{}", extract_fields(&line, config.delimeter, ranges)?)
-                    }
-                };
+// Records are read from the whole input instead of line by line, so a quoted field
+// with the delimeter or a line break stays one field. There are no headers here,
+// the first record is cut the same way as the rest of them.
+// The file name prefix goes only before the first line of a record that has a quoted line break.
+fn process_csv(
+    path: &str,
+    reader: Box<dyn BufRead>,
+    config: &Config,
+    out: &mut impl Write,
+) -> Result<()> {
+    let (ExtractedRanges::Fields(ranges), Splitter::Csv(delimeter)) =
+        (&config.extracted, &config.splitter)
+    else {
+        unreachable!("--csv is checked to go with --fields and a single byte delimeter");
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(*delimeter)
+        .from_reader(reader);
+    let mut cutter = FieldCutter::new(*delimeter);
+    let mut record = StringRecord::new();
+    let prefix = filename_prefix(path, config);
+
+    loop {
+        match reader.read_record(&mut record) {
+            Err(error) => eprintln!("Can't read record from file '{path}', error {error}"),
+            Ok(false) => break,
+            Ok(true) if config.only_delimited && record.len() < 2 => {}
+            Ok(true) if record.len() < 2 => {
+                // Undelimited record is printed as is, the same as in GNU cut
+                out.write_all(prefix.as_bytes())?;
+                out.write_all(cutter.write(record.iter().map(str::as_bytes)))?;
+                out.write_all(b"\n")?;
+            }
+            Ok(true) => {
+                let fields = extract_fields_internal(&record, ranges);
+                out.write_all(prefix.as_bytes())?;
+                out.write_all(cutter.write(fields.into_iter().map(str::as_bytes)))?;
+                out.write_all(b"\n")?;
             }
         }
     }
@@ -160,34 +390,64 @@ fn process_file(path: &str, reader: Box<dyn BufRead>, config: &Config) -> Result
     Ok(())
 }
 
-fn extract_chars(line: &str, ranges: &[RangeInclusive<usize>]) -> String {
-    ranges_iter(ranges)
-        .filter_map(|i| line.chars().nth(i))
-        .collect()
+// Lines are not decoded, so the selected bytes of binary or non UTF-8 records
+// go to the output exactly as they were in the input
+fn process_bytes(
+    path: &str,
+    mut reader: Box<dyn BufRead>,
+    config: &Config,
+    out: &mut impl Write,
+) -> Result<()> {
+    let ExtractedRanges::Bytes(ranges) = &config.extracted else {
+        unreachable!("Only byte ranges are processed as raw lines");
+    };
+
+    let mut line = Vec::new();
+    let prefix = filename_prefix(path, config);
+
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            // The error would repeat on every read, so the rest of the file is skipped
+            Err(error) => {
+                eprintln!("Can't read file '{path}', error {error}");
+                break;
+            }
+            Ok(0) => break,
+            Ok(_) => {
+                // Same line endings are removed as with BufRead::lines
+                let content = line.strip_suffix(b"\n").unwrap_or(&line);
+                let content = content.strip_suffix(b"\r").unwrap_or(content);
+                out.write_all(prefix.as_bytes())?;
+                out.write_all(&extract_bytes(content, ranges))?;
+                out.write_all(b"\n")?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
-fn extract_bytes(line: &str, ranges: &[RangeInclusive<usize>]) -> String {
-    let bytes: Vec<u8> = ranges_iter(ranges)
-        .filter_map(|i| line.as_bytes().get(i).copied())
-        .collect();
-    String::from_utf8_lossy(&bytes).into()
+// Chars are decoded once, so wide selections over long lines stay linear.
+// Ranges can go in any order, hence the lookup table instead of a single pass.
+fn extract_chars(line: &str, ranges: &[RangeInclusive<usize>]) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    ranges_iter(ranges, chars.len()).map(|i| chars[i]).collect()
 }
 
-// https://docs.rs/csv/latest/csv/tutorial/index.html
-fn extract_fields(line: &str, delimeter: char, ranges: &[RangeInclusive<usize>]) -> Result<String> {
-    let reader = csv::ReaderBuilder::new()
-        .has_headers(false)
-        .delimiter(delimeter as u8)
-        .from_reader(line.as_bytes());
-    let record = reader.into_records().next().expect("No fields found")?;
-    let fields = extract_fields_internal(&record, ranges);
+fn extract_bytes(line: &[u8], ranges: &[RangeInclusive<usize>]) -> Vec<u8> {
+    ranges_iter(ranges, line.len()).map(|i| line[i]).collect()
+}
 
-    let mut writer = csv::WriterBuilder::new()
-        .has_headers(false)
-        .delimiter(delimeter as u8)
-        .from_writer(vec![]);
-    writer.write_record(fields)?;
-    String::from_utf8(writer.into_inner()?).map_err(Into::into)
+// Fields are split without the csv quoting rules and joined back with the delimeter
+fn extract_split<'a>(
+    fields: impl Iterator<Item = &'a str>,
+    delimeter: &str,
+    ranges: &[RangeInclusive<usize>],
+) -> String {
+    let fields: Vec<&str> = fields.collect();
+    let selected: Vec<&str> = ranges_iter(ranges, fields.len()).map(|i| fields[i]).collect();
+    selected.join(delimeter)
 }
 
 // Could be inlined, but tests depend on extract_fields_internal
@@ -196,7 +456,7 @@ fn extract_fields_internal<'rec>(
     record: &'rec StringRecord,
     ranges: &[RangeInclusive<usize>],
 ) -> Vec<&'rec str> {
-    ranges_iter(ranges).filter_map(|i| record.get(i)).collect()
+    ranges_iter(ranges, record.len()).map(|i| &record[i]).collect()
 }
 
 #[cfg(test)]
@@ -204,10 +464,11 @@ mod unit_tests {
     use csv::StringRecord;
 
     use super::parse_ranges;
-    use crate::{extract_bytes, extract_chars, extract_fields_internal};
+    use crate::ranges::input_order;
+    use crate::{extract_bytes, extract_chars, extract_fields_internal, extract_split};
 
     #[test]
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::reversed_empty_ranges)]
     fn test_parse_ranges() {
         // The empty string is an error
         assert!(parse_ranges("").is_err());
@@ -372,16 +633,25 @@ mod unit_tests {
             extract_chars("ábc", &[0..=0, 1..=1, 4..=4]),
             "áb".to_string()
         );
+
+        // Ranges past the end of the line are clamped to it instead of walked
+        assert_eq!(extract_chars("ábc", &[1..=usize::MAX]), "bc".to_string());
+        assert_eq!(extract_chars("ábc", &[3..=usize::MAX]), String::new());
     }
 
     #[test]
+    #[allow(clippy::reversed_empty_ranges)]
     fn test_extract_bytes() {
-        assert_eq!(extract_bytes("ábc", &[0..=0]), "�".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..=1]), "á".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..=2]), "áb".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..=3]), "ábc".to_string());
-        assert_eq!(extract_bytes("ábc", &[3..=3, 2..=2]), "cb".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..=1, 5..=5]), "á".to_string());
+        let line = "ábc".as_bytes();
+        assert_eq!(extract_bytes(line, &[0..=0]), b"\xC3");
+        assert_eq!(extract_bytes(line, &[0..=1]), "á".as_bytes());
+        assert_eq!(extract_bytes(line, &[0..=2]), "áb".as_bytes());
+        assert_eq!(extract_bytes(line, &[0..=3]), "ábc".as_bytes());
+        assert_eq!(extract_bytes(line, &[3..=3, 2..=2]), b"cb");
+        assert_eq!(extract_bytes(line, &[0..=1, 5..=5]), "á".as_bytes());
+        assert_eq!(extract_bytes(b"\xFF\x00\xFE", &[2..=0]), b"\xFE\x00\xFF");
+        assert_eq!(extract_bytes(line, &[2..=usize::MAX]), b"bc");
+        assert_eq!(extract_bytes(line, &[usize::MAX..=3]), b"c");
     }
 
     #[test]
@@ -399,4 +669,30 @@ mod unit_tests {
             &["Sham", "Captain"]
         );
     }
+
+    #[test]
+    fn test_extract_split() {
+        // Leading and trailing whitespace doesn't start a field, the same way as in awk
+        let words = || "  root     1  0.0 /sbin/init splash ".split_whitespace();
+        assert_eq!(extract_split(words(), "\t", &[0..=0]), "root");
+        assert_eq!(extract_split(words(), "\t", &[3..=4]), "/sbin/init\tsplash");
+        assert_eq!(extract_split(words(), " ", &[1..=1, 0..=0]), "1 root");
+        assert_eq!(extract_split(words(), ",", &[4..=6]), "splash");
+        assert_eq!(extract_split("".split_whitespace(), ",", &[0..=0]), "");
+
+        let text = || "a::b::::c".split("::");
+        assert_eq!(extract_split(text(), "::", &[0..=1]), "a::b");
+        assert_eq!(extract_split(text(), "::", &[2..=3]), "::c");
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_input_order() {
+        assert_eq!(input_order(&[2..=2, 0..=0]), vec![0..=0, 2..=2]);
+        assert_eq!(input_order(&[0..=0, 0..=0]), vec![0..=0]);
+        assert_eq!(input_order(&[4..=1, 0..=2]), vec![0..=4]);
+        assert_eq!(input_order(&[5..=6, 0..=1, 2..=3]), vec![0..=3, 5..=6]);
+        assert_eq!(input_order(&[0..=usize::MAX, 3..=3]), vec![0..=usize::MAX]);
+        assert!(input_order(&[]).is_empty());
+    }
 }
@@ -7,16 +7,27 @@ pub struct RangeIndex {
 
 pub struct RangeIter<'a> {
     ranges: &'a [RangeInclusive<usize>],
+    len: usize,
     index: RangeIndex,
 }
 
 impl<'a> RangeIter<'a> {
-    fn new(ranges: &'a [RangeInclusive<usize>]) -> Self {
+    fn new(ranges: &'a [RangeInclusive<usize>], len: usize) -> Self {
         Self {
             ranges,
+            len,
             index: RangeIndex { ext: 0, int: None },
         }
     }
+
+    // Range cut to the positions that exist, None when it starts past the end
+    fn clamp(&self, range: &RangeInclusive<usize>) -> Option<(usize, usize)> {
+        let (start, end) = (*range.start(), *range.end());
+        if start.min(end) >= self.len {
+            return None;
+        }
+        Some((start.min(self.len - 1), end.min(self.len - 1)))
+    }
 }
 
 impl<'a> Iterator for RangeIter<'a> {
@@ -24,23 +35,28 @@ impl<'a> Iterator for RangeIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let range = self.ranges.get(self.index.ext)?;
+        let Some((start, end)) = self.clamp(range) else {
+            // Nothing of the range is in the line, skip it without walking its positions
+            self.index = RangeIndex { ext: self.index.ext + 1, int: None };
+            return self.next();
+        };
 
         match self.index.int {
             None => {
                 // Init the internal index
-                self.index.int = Some(*range.start());
+                self.index.int = Some(start);
                 self.next()
             }
             Some(value) => {
                 // What is the next internal index?
-                let next = if range.start() <= range.end() {
+                let next = if start <= end {
                     value.checked_add(1)
                 } else {
                     value.checked_sub(1)
                 };
 
                 // Check if we need to move to another range
-                if value == *range.end() || next.is_none() {
+                if value == end || next.is_none() {
                     self.index = RangeIndex { ext: self.index.ext + 1, int: None };
                 } else {
                     self.index.int = next;
@@ -52,8 +68,35 @@ impl<'a> Iterator for RangeIter<'a> {
     }
 }
 
-pub fn ranges_iter(ranges: &[RangeInclusive<usize>]) -> RangeIter {
-    RangeIter::new(ranges)
+/// Positions the ranges select among the `len` ones of a line
+///
+/// Ranges are clamped to the line first, so -c 1-1000000 costs only the line length.
+pub fn ranges_iter(ranges: &[RangeInclusive<usize>], len: usize) -> RangeIter<'_> {
+    RangeIter::new(ranges, len)
+}
+
+/// Ranges that select every position once and in the input order, the way GNU cut does
+///
+/// Reversed ranges are turned around and then overlapping or adjacent ones are merged,
+/// so wide ranges like 1-1000000 are not expanded into positions.
+pub fn input_order(ranges: &[RangeInclusive<usize>]) -> Vec<RangeInclusive<usize>> {
+    let mut sorted: Vec<(usize, usize)> = ranges
+        .iter()
+        .map(|range| (*range.start().min(range.end()), *range.start().max(range.end())))
+        .collect();
+    sorted.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = end.max(*last_end)
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged.into_iter().map(|(start, end)| start..=end).collect()
 }
 
 /*
@@ -9,6 +9,10 @@ const PRG: &str = "cut";
 const CSV: &str = "tests/inputs/movies1.csv";
 const TSV: &str = "tests/inputs/movies1.tsv";
 const BOOKS: &str = "tests/inputs/books.tsv";
+const MIXED: &str = "tests/inputs/mixed.tsv";
+const SPACED: &str = "tests/inputs/books.txt";
+const QUOTED: &str = "tests/inputs/quoted.csv";
+const LOG: &str = "tests/inputs/log.jsonl";
 
 // --------------------------------------------------
 fn random_string() -> String {
@@ -42,6 +46,26 @@ fn skips_bad_file() -> TestResult {
     Ok(())
 }
 
+// --------------------------------------------------
+#[test]
+fn skips_directory() -> TestResult {
+    for arg in ["-f1", "-b1"] {
+        let output = Command::cargo_bin(PRG)?
+            .args([arg, "tests", TSV])
+            .timeout(std::time::Duration::from_secs(10))
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+
+        let stderr = String::from_utf8(output.stderr)?;
+        assert_eq!(stderr.lines().count(), 1, "{stderr}");
+        assert!(stderr.contains("Can't read file 'tests'"));
+        assert!(!output.stdout.is_empty());
+    }
+    Ok(())
+}
+
 // --------------------------------------------------
 fn dies(args: &[&str], expected: &str) -> TestResult {
     Command::cargo_bin(PRG)?
@@ -55,7 +79,7 @@ fn dies(args: &[&str], expected: &str) -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dies_not_enough_args() -> TestResult {
-    dies(&[CSV], "Please provide either --bytes --chars or --fields once")
+    dies(&[CSV], "Please provide either --bytes --chars --fields or --json once")
 }
 
 // --------------------------------------------------
@@ -93,16 +117,25 @@ fn dies_bad_digit_chars() -> TestResult {
 fn dies_empty_delimiter() -> TestResult {
     dies(
         &[CSV, "-f", "1", "-d", ""],
-        "cannot parse char from empty string",
+        "a value is required for '--delimeter <DELIMETER>'",
     )
 }
 
 // --------------------------------------------------
 #[test]
-fn dies_bad_delimiter() -> TestResult {
+fn dies_bad_delimiter_regex() -> TestResult {
     dies(
-        &[CSV, "-f", "1", "-d", ",,"],
-        "too many characters in string",
+        &[CSV, "-f", "1", "--delimeter_regex", "[,"],
+        "Invalid regex '[,'",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_empty_delimiter_regex() -> TestResult {
+    dies(
+        &[CSV, "-f", "1", "--delimeter_regex", ",*"],
+        "it matches empty text",
     )
 }
 
@@ -159,14 +192,13 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
 }
 
 // --------------------------------------------------
-fn run_lossy(args: &[&str], expected_file: &str) -> TestResult {
-    let contents = fs::read(expected_file)?;
-    let expected = String::from_utf8_lossy(&contents);
+fn run_bytes(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read(expected_file)?;
     Command::cargo_bin(PRG)?
         .args(args)
         .assert()
         .success()
-        .stdout(predicate::str::contains(expected));
+        .stdout(expected);
     Ok(())
 }
 
@@ -275,7 +307,7 @@ fn tsv_b2() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn tsv_b8() -> TestResult {
-    run_lossy(&[TSV, "-b", "8"], "tests/expected/movies1.tsv.b8.out")
+    run_bytes(&[TSV, "-b", "8"], "tests/expected/movies1.tsv.b8.out")
 }
 
 // --------------------------------------------------
@@ -293,7 +325,7 @@ fn tsv_b2_3() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn tsv_b1_8() -> TestResult {
-    run_lossy(&[TSV, "-b", "1-8"], "tests/expected/movies1.tsv.b1-8.out")
+    run_bytes(&[TSV, "-b", "1-8"], "tests/expected/movies1.tsv.b1-8.out")
 }
 
 // --------------------------------------------------
@@ -337,3 +369,144 @@ fn tsv_c1_8() -> TestResult {
 fn repeated_value() -> TestResult {
     run(&[BOOKS, "-c", "1,1"], "tests/expected/books.c1,1.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn only_delimited() -> TestResult {
+    run(
+        &[MIXED, "-f", "1-2", "-s"],
+        "tests/expected/mixed.tsv.f1-2.s.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_only_delimited_bytes() -> TestResult {
+    dies(
+        &[MIXED, "-b", "1", "--only_delimited"],
+        "only when extracting --fields",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn whitespace_f2_1() -> TestResult {
+    run(
+        &[SPACED, "-w", "-f", "2,1", "-d", ","],
+        "tests/expected/books.txt.w.f2,1.dcomma.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn multichar_delimiter() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "3,1", "-d", "::"])
+        .write_stdin("a::b::c\nd:e::f\n")
+        .assert()
+        .success()
+        .stdout("c::a\nd:e\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn delimiter_regex() -> TestResult {
+    run(
+        &[SPACED, "--delimeter_regex", r"\s+\d{4}\s+", "-f", "2,1", "-d", "|"],
+        "tests/expected/books.txt.regex_year.f2,1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_quoted_f3_2() -> TestResult {
+    run(
+        &[QUOTED, "--csv", "-f", "3,2", "-d", ","],
+        "tests/expected/quoted.csv.csv.f3,2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_csv_multichar_delimiter() -> TestResult {
+    dies(
+        &[QUOTED, "--csv", "-f", "1", "-d", ",,"],
+        "--csv can't use it",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_bytes() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-b", "3,1-2"])
+        .write_stdin(&b"\xFF\x00\xFEtail\n\x80\n"[..])
+        .assert()
+        .success()
+        .stdout(&b"\xFE\xFF\x00\n\x80\n"[..]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json_keys() -> TestResult {
+    run(
+        &[LOG, "--json", "level,ctx.port,tags[0]", "-d", ","],
+        "tests/expected/log.jsonl.json.dcomma.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn json_array() -> TestResult {
+    run(
+        &[LOG, "--json", "level,msg", "--json_array"],
+        "tests/expected/log.jsonl.json_array.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_json_selector() -> TestResult {
+    dies(&[LOG, "--json", "user..name"], "Invalid selector 'user..name'")
+}
+
+// --------------------------------------------------
+#[test]
+fn input_order_f3_1_1() -> TestResult {
+    run(
+        &[TSV, "-f", "3,1,1", "--input_order"],
+        "tests/expected/movies1.tsv.f3,1,1.input_order.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn with_filename_prefix() -> TestResult {
+    run(
+        &[TSV, BOOKS, "-f", "1", "--with_filename"],
+        "tests/expected/movies1.books.tsv.f1.with_filename.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn with_filename_header() -> TestResult {
+    run(
+        &[TSV, BOOKS, "-f", "1", "--with_filename=header"],
+        "tests/expected/movies1.books.tsv.f1.with_filename_header.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_line_f1() -> TestResult {
+    run(&[MIXED, "-f", "1"], "tests/expected/mixed.tsv.f1.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn undelimited_f2() -> TestResult {
+    run(&[MIXED, "-f", "2"], "tests/expected/mixed.tsv.f2.out")
+}
@@ -0,0 +1,62 @@
+//! Measures the search over a large file line by line and through memory mapping
+//! and compares it with GNU grep found in PATH
+//!
+//! Input is a log-like file in the temp folder, it is generated once and reused.
+//! Size can be changed with `GREP_BENCH_MB=<megabytes> cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const DEFAULT_MB: usize = 200;
+const RARE: &str = "needle";
+
+fn input_file(megabytes: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("grep-bench-{megabytes}mb.log"));
+    if path.exists() {
+        return path;
+    }
+
+    let mut text = String::new();
+    let mut row = 0;
+    while text.len() < megabytes << 20 {
+        let word = if row % 10_000 == 0 { RARE } else { "haystack" };
+        let _ = writeln!(text, "2023-10-{:02} INFO request {row} served {word}", row % 28 + 1);
+        row += 1;
+    }
+    fs::write(&path, text).expect("Can't write bench input");
+    path
+}
+
+fn grep(program: &str, args: &[&str], path: &PathBuf) {
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdout(Stdio::null())
+        .status()
+        .expect("Can't run grep");
+    assert!(status.success());
+}
+
+fn large_file(c: &mut Criterion) {
+    let megabytes = std::env::var("GREP_BENCH_MB")
+        .ok()
+        .and_then(|megabytes| megabytes.parse().ok())
+        .unwrap_or(DEFAULT_MB);
+    let path = input_file(megabytes);
+
+    let mut group = c.benchmark_group(format!("grep over {megabytes} MB"));
+    group.sample_size(10);
+
+    let this = env!("CARGO_BIN_EXE_grep");
+    group.bench_function("this lines", |b| b.iter(|| grep(this, &[RARE], &path)));
+    group.bench_function("this mmap", |b| b.iter(|| grep(this, &["--mmap", RARE], &path)));
+    group.bench_function("gnu", |b| b.iter(|| grep("grep", &[RARE], &path)));
+
+    group.finish();
+}
+
+criterion_group!(benches, large_file);
+criterion_main!(benches);
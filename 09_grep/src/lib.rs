@@ -1,57 +1,187 @@
+mod mapped;
+
+use ansi_term::Colour;
 use anyhow::{anyhow, Result};
-use clap::{arg, Command};
-use regex::{Regex, RegexBuilder};
-use std::io::{BufRead, BufReader};
-use walkdir::WalkDir;
+use clap::{arg, builder::PossibleValuesParser, Command};
+use common::errors::ErrorWriter;
+use regex::{Captures, Regex, RegexBuilder};
+use ignore::WalkBuilder;
+use std::{
+    cell::Cell,
+    io::{self, BufRead, BufReader, IsTerminal, Read},
+    time::Instant,
+};
+
+/// What is done with files that have NUL bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryFiles {
+    Binary,       // only tell that the file matches
+    Text,         // -a, search them the same way as text files
+    WithoutMatch, // skip them as if they had no match
+}
 
 #[derive(Debug)]
 pub struct Config {
     pattern: Regex,
     files: Vec<String>,
     recurse: bool,
+    ignore: bool,
     count: bool,
     invert_match: bool,
+    files_with_matches: bool,
+    files_without_match: bool,
+    color: bool,
+    binary_files: BinaryFiles,
+    max_count: Option<usize>,
+    quiet: bool,
+    mapped_pattern: Option<Regex>,
+    errors: ErrorWriter,
+    json: bool,
+    stats: bool,
+    with_filename: Option<bool>,
+    count_matches: bool,
+}
+
+/// Counters of --stats gathered over the whole run
+#[derive(Debug, Default)]
+struct Stats {
+    searched: usize, // files that were opened and read
+    matched: usize,  // files with at least one shown line
+    lines: usize,    // shown lines, for --json lines with matches
+    bytes: Cell<u64>,
 }
 
-pub fn run(config: Config) -> Result<()> {
+impl Config {
+    /// Sends the errors about files that can't be read somewhere else than stderr
+    pub fn with_errors(self, errors: ErrorWriter) -> Self {
+        Config { errors, ..self }
+    }
+}
+
+pub fn run(mut config: Config) -> Result<()> {
+    let started = Instant::now();
+    let mut stats = Stats::default();
+
+    #[cfg(windows)]
+    if config.color {
+        let _ = ansi_term::enable_ansi_support();
+    }
+
     // Files to process
-    let files = find_files(&config.files, config.recurse);
+    let files = find_files(&config.files, config.recurse, config.ignore);
 
     // Output should be prepended with file name in case we have many files
+    let with_filename = config.with_filename.unwrap_or(files.len() > 1);
     let output = |path: &str, value: &str| {
-        if files.len() > 1 {
-            print!("{path}:{value}");
+        if with_filename {
+            print!("{}{value}", filename_prefix(path, config.color));
         } else {
             print!("{value}");
         }
     };
 
+    // Exit code depends on them
+    let mut found = false;
+    let mut failed = false;
+
     // Process each file
     for path in &files {
         // Print per-file error without terminating the program
         let Ok(path) = path else {
-            eprintln!("{}", path.as_ref().unwrap_err());
+            config.errors.report(path.as_ref().unwrap_err());
+            failed = true;
             continue;
         };
 
         // Open reader to the file
-        let reader = match open(path) {
-            Ok(reader) => reader,
+        let mut reader = match open(path) {
+            Ok(reader) => Counting { inner: reader, bytes: &stats.bytes },
             Err(error) => {
-                eprintln!("Can't open file '{}', error {}", &path, error);
+                config.errors.report(format_args!("Can't open file '{path}', error {error}"));
+                failed = true;
                 continue;
             }
         };
 
+        // Binary files are recognized by a NUL byte in the first buffered chunk, like in GNU grep
+        let binary = config.binary_files != BinaryFiles::Text && is_binary(&mut reader)?;
+        if binary && config.binary_files == BinaryFiles::WithoutMatch {
+            continue;
+        }
+        stats.searched += 1;
+
+        // Nothing is printed and the rest of the files are not read after the first match
+        if config.quiet {
+            if has_match(reader, &config.pattern, config.invert_match)? {
+                std::process::exit(0);
+            }
+            continue;
+        }
+
+        // Only file names are printed, reading stops at the first matching line
+        if config.files_with_matches || config.files_without_match {
+            let matched = has_match(reader, &config.pattern, config.invert_match)?;
+            if matched == config.files_with_matches {
+                println!("{}", paint(Colour::Purple, label(path), config.color));
+                found = true;
+            }
+            continue;
+        }
+
+        // Every match is a separate object, so binary files are shown as text here
+        if config.json {
+            let matched = print_json(reader, path, &config)?;
+            stats.lines += matched;
+            stats.matched += usize::from(matched > 0);
+            found |= matched > 0;
+            continue;
+        }
+
+        // Binary lines would garble the terminal, so only the fact of a match is shown
+        if binary && !config.count && !config.count_matches {
+            if has_match(reader, &config.pattern, config.invert_match)? {
+                println!("Binary file {} matches", label(path));
+                stats.matched += 1;
+                found = true;
+            }
+            continue;
+        }
+
         // Process matches
-        let lines = find_lines(reader, &config.pattern, config.invert_match)?;
-        if config.count {
+        let lines = match find_lines_mapped(path, &config, &stats.bytes) {
+            Some(lines) => lines,
+            None => find_lines(reader, &config.pattern, config.invert_match, config.max_count)?,
+        };
+        stats.lines += lines.len();
+        stats.matched += usize::from(!lines.is_empty());
+        found |= !lines.is_empty();
+        if config.count_matches {
+            output(path, &format!("{}\n", count_matches(&lines, &config.pattern)));
+        } else if config.count {
             output(path, &format!("{}\n", lines.len()));
         } else {
-            lines.into_iter().for_each(|line| output(path, &line));
+            for line in lines {
+                match config.color && !config.invert_match {
+                    true => output(path, &highlight(&line, &config.pattern)),
+                    false => output(path, &line),
+                }
+            }
         };
     }
 
+    if config.stats {
+        print_stats(&stats, started);
+    }
+
+    // Scripts depend on the same exit codes as in GNU grep:
+    // 0 - there was a match, 1 - there were no matches, 2 - there was an error
+    if failed {
+        std::process::exit(2);
+    }
+    if !found {
+        std::process::exit(1);
+    }
+
     // Made it to the end without terminating errors
     Ok(())
 }
@@ -69,15 +199,53 @@ pub fn get_args() -> Result<Config> {
         .version("1.0")
         .author("FallenGameR")
         .about("Finds text specified by regular expression in files")
+        // -h is taken by --no_filename the same way as in GNU grep
+        .disable_help_flag(true)
         .args([
             arg!(<REGULAR_EXPRESSION> "Regular expression to use"),
             arg!([FILES] ... "Files or folders to process, stdin is -").default_value("-"),
             arg!(-i --insensitive "Use case insensitive regex matching"),
             arg!(-r --recursive "Recuresivelly descend into folders looking for files"),
+            arg!(--no_ignore "Don't skip .git folders and files ignored by .gitignore and .ignore"),
             arg!(-c --count "Just count the matches, don't show them"),
+            arg!(--count_matches "Just count every match, a line can have several of them")
+                .conflicts_with("invert_match"),
             arg!(-v --invert_match "Find lines that don't match the regular expression"),
+            arg!(-l --files_with_matches "Just show names of files that have a match")
+                .conflicts_with_all(["count", "count_matches", "files_without_match"]),
+            arg!(-L --files_without_match "Just show names of files that have no match")
+                .conflicts_with_all(["count", "count_matches"]),
+            arg!(--color [WHEN] "Highlight matches and file names, auto is only for terminal")
+                .value_parser(PossibleValuesParser::new(["auto", "always", "never"]))
+                .require_equals(true)
+                .default_value("auto")
+                .default_missing_value("auto"),
+            arg!(-a --text "Search binary files the same way as text files"),
+            arg!(--binary_files <TYPE> "What to do with files that have NUL bytes")
+                .value_parser(PossibleValuesParser::new(["binary", "text", "without-match"]))
+                .default_value("binary"),
+            arg!(-m --max_count <NUM> "Stop reading a file after NUM matching lines")
+                .value_parser(clap::value_parser!(usize)),
+            arg!(-q --quiet "Print nothing, exit with zero code at the first match"),
+            arg!(-s --no_messages "Don't show errors about files that can't be read"),
+            arg!(--json "Show every match as a JSON object with its line, byte offset and groups")
+                .conflicts_with_all([
+                    "count",
+                    "count_matches",
+                    "invert_match",
+                    "files_with_matches",
+                    "files_without_match",
+                    "quiet",
+                ]),
+            arg!(-H --with_filename "Show the file name for every line, even for a single file")
+                .conflicts_with("no_filename"),
+            arg!(-h --no_filename "Don't show file names, even for several files"),
+            arg!(--stats "Show how many files, lines and bytes were searched at the end")
+                .conflicts_with_all(["files_with_matches", "files_without_match", "quiet"]),
+            arg!(--mmap "Search regular files as a whole via memory mapping, faster when huge"),
+            arg!(--help "Print help").action(clap::ArgAction::Help),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     // Construct regex
     let pattern_text: String = matches
@@ -86,6 +254,10 @@ pub fn get_args() -> Result<Config> {
     let pattern = RegexBuilder::new(&pattern_text)
         .case_insensitive(matches.get_flag("insensitive"))
         .build()?;
+    let mapped_pattern = match matches.get_flag("mmap") {
+        true => mapped::candidates_pattern(&pattern_text, matches.get_flag("insensitive")),
+        false => None,
+    };
 
     // Construct config
     Ok(Config {
@@ -95,12 +267,52 @@ pub fn get_args() -> Result<Config> {
             .expect("No file paths provided")
             .collect(),
         recurse: matches.get_flag("recursive"),
+        ignore: !matches.get_flag("no_ignore"),
         count: matches.get_flag("count"),
+        count_matches: matches.get_flag("count_matches"),
         invert_match: matches.get_flag("invert_match"),
+        files_with_matches: matches.get_flag("files_with_matches"),
+        files_without_match: matches.get_flag("files_without_match"),
+        color: match matches.get_one::<String>("color").map(String::as_str) {
+            Some("always") => true,
+            Some("never") => false,
+            _ => std::io::stdout().is_terminal(),
+        },
+        binary_files: match matches.get_flag("text") {
+            true => BinaryFiles::Text,
+            false => parse_binary_files(
+                &matches
+                    .remove_one::<String>("binary_files")
+                    .expect("Default value is provided"),
+            ),
+        },
+        max_count: matches.remove_one("max_count"),
+        quiet: matches.get_flag("quiet"),
+        mapped_pattern,
+        errors: match matches.get_flag("no_messages") {
+            true => ErrorWriter::silent(),
+            false => ErrorWriter::stderr(),
+        },
+        json: matches.get_flag("json"),
+        stats: matches.get_flag("stats"),
+        with_filename: match (matches.get_flag("with_filename"), matches.get_flag("no_filename")) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
     })
 }
 
-fn find_files(paths: &[String], recurse: bool) -> Vec<Result<String>> {
+fn parse_binary_files(binary_files: &str) -> BinaryFiles {
+    match binary_files {
+        "binary" => BinaryFiles::Binary,
+        "text" => BinaryFiles::Text,
+        "without-match" => BinaryFiles::WithoutMatch,
+        unknown => unreachable!("Unsupported binary files type {unknown}"),
+    }
+}
+
+fn find_files(paths: &[String], recurse: bool, ignore: bool) -> Vec<Result<String>> {
     let mut files = Vec::new();
 
     for path in paths {
@@ -117,15 +329,23 @@ fn find_files(paths: &[String], recurse: bool) -> Vec<Result<String>> {
         }
 
         // Walk the file path - if it is file, just return it,
-        // if it is a folder we can safelly recurse into it
-        for root in WalkDir::new(path) {
+        // if it is a folder we can safelly recurse into it.
+        // Ignore files are respected even outside of git repositories, hidden files are searched.
+        let walker = WalkBuilder::new(path)
+            .standard_filters(ignore)
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(move |entry| !ignore || entry.file_name() != ".git")
+            .build();
+
+        for root in walker {
             match root {
                 // Store the errors to handle them upstream without program termination
                 Err(error) => {
                     files.push(Err(error.into()));
                 }
                 // Found a file path to process
-                Ok(entry) if entry.file_type().is_file() => {
+                Ok(entry) if entry.file_type().is_some_and(|kind| kind.is_file()) => {
                     files.push(Ok(entry.path().to_string_lossy().into()));
                 }
                 // Don't modify the output files vector if the entry is anything else
@@ -137,8 +357,8 @@ fn find_files(paths: &[String], recurse: bool) -> Vec<Result<String>> {
 
     /*
     // In case we didn't care to propogate errors
-    let files_in_all_folders: Vec<Result<String>> = WalkDir::new(path)
-        .into_iter()
+    let files_in_all_folders: Vec<Result<String>> = WalkBuilder::new(path)
+        .build()
         .flatten()
         .filter(|e| e.file_type().is_file())
         .map(|e| Ok(e.path().to_string_lossy().into()))
@@ -152,34 +372,206 @@ fn find_lines(
     mut reader: impl BufRead,
     pattern: &Regex,
     invert_match: bool,
+    max_count: Option<usize>,
 ) -> Result<Vec<String>> {
     let mut results = Vec::new();
-    let mut line = String::new();
+    let mut buffer = Vec::new();
 
-    loop {
+    // The rest of the file is not read once there are enough matches
+    while max_count.is_none_or(|max| results.len() < max) {
         // Read line together with line endings
-        if reader.read_line(&mut line)? == 0 {
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
             break;
         }
 
+        // Invalid UTF-8 is replaced, so binary files searched as text don't stop the search
+        let line = match String::from_utf8(std::mem::take(&mut buffer)) {
+            Ok(line) => line,
+            Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+        };
+
         // It should either be a match or it is not a match and we are looking for not-matching lines
-        if pattern.is_match(&line) ^ invert_match {
+        if pattern.is_match(content(&line)) ^ invert_match {
             // Avoiding clone by taking ownership of the line
-            // The old line content would be swapped with an empty string here
-            results.push(std::mem::take(&mut line));
+            // The buffer was swapped with an empty one above
+            results.push(line);
         } else {
-            // If we didn't use the line we need to clean it up for the next iteration
-            line.clear();
+            // If we didn't use the line its memory is reused for the next iteration
+            buffer = line.into_bytes();
+            buffer.clear();
         }
     }
 
     Ok(results)
 }
 
+// Inverted search needs every line anyway, so it goes line by line
+// The whole mapping is counted as scanned, even if max count stopped the search earlier
+fn find_lines_mapped(path: &str, config: &Config, bytes: &Cell<u64>) -> Option<Vec<String>> {
+    let candidates = config.mapped_pattern.as_ref().filter(|_| !config.invert_match)?;
+    let map = mapped::map(path)?;
+
+    // Line by line search replaces invalid UTF-8, the mapping is left to it in that case
+    let text = std::str::from_utf8(&map).ok()?;
+    bytes.set(bytes.get() + text.len() as u64);
+    Some(mapped::find_lines(text, candidates, &config.pattern, config.max_count))
+}
+
+// Offsets are in bytes from the start of the file, lines are counted from 1.
+// Max count limits the lines with matches, all matches of the last line are still shown.
+fn print_json(mut reader: impl BufRead, path: &str, config: &Config) -> Result<usize> {
+    let mut buffer = Vec::new();
+    let (mut number, mut offset, mut matched) = (0, 0, 0);
+
+    while config.max_count.is_none_or(|max| matched < max) {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        number += 1;
+
+        let line = String::from_utf8_lossy(&buffer);
+        let mut found = false;
+        for captures in config.pattern.captures_iter(content(&line)) {
+            println!("{}", format_json(path, number, offset, &captures));
+            found = true;
+        }
+
+        matched += usize::from(found);
+        offset += read;
+    }
+
+    Ok(matched)
+}
+
+// Groups that didn't take part in the match are null
+fn format_json(path: &str, number: usize, offset: usize, captures: &Captures) -> String {
+    let whole = captures.get(0).expect("Group 0 is the whole match");
+    let groups: Vec<String> = captures
+        .iter()
+        .skip(1)
+        .map(|group| group.map_or("null".to_string(), |group| common::json::quote(group.as_str())))
+        .collect();
+
+    format!(
+        r#"{{"file":{},"line":{number},"offset":{},"text":{},"groups":[{}]}}"#,
+        common::json::quote(label(path)),
+        offset + whole.start(),
+        common::json::quote(whole.as_str()),
+        groups.join(",")
+    )
+}
+
+fn print_stats(stats: &Stats, started: Instant) {
+    println!();
+    println!("{} files searched", stats.searched);
+    println!("{} files with matches", stats.matched);
+    println!("{} lines matched", stats.lines);
+    println!("{} bytes searched", stats.bytes.get());
+    println!("{:.6} seconds spent", started.elapsed().as_secs_f64());
+}
+
+// Bytes are counted as the search consumes them, so a search that stops early
+// doesn't count the rest of the file
+struct Counting<'a, R> {
+    inner: R,
+    bytes: &'a Cell<u64>,
+}
+
+impl<R: Read> Read for Counting<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes.set(self.bytes.get() + read as u64);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Counting<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.bytes.set(self.bytes.get() + amount as u64);
+        self.inner.consume(amount);
+    }
+}
+
+fn paint(colour: Colour, text: &str, color: bool) -> String {
+    match color {
+        true => colour.paint(text).to_string(),
+        false => text.to_string(),
+    }
+}
+
+// Stdin is named the same way as in GNU grep
+fn label(path: &str) -> &str {
+    match path {
+        "-" => "(standard input)",
+        _ => path,
+    }
+}
+
+fn filename_prefix(path: &str, color: bool) -> String {
+    let path = paint(Colour::Purple, label(path), color);
+    format!("{path}{}", paint(Colour::Cyan, ":", color))
+}
+
+// Matches are red and bold the same way as in GNU grep, empty matches are not shown.
+// The line ending stays outside of the colors, so the next line starts clean.
+fn highlight(line: &str, pattern: &Regex) -> String {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+
+    for found in pattern.find_iter(content).filter(|found| !found.is_empty()) {
+        result.push_str(&content[last..found.start()]);
+        result.push_str(&Colour::Red.bold().paint(found.as_str()).to_string());
+        last = found.end();
+    }
+
+    result.push_str(&line[last..]);
+    result
+}
+
+// Lines are matched without the line break the same way as in GNU grep,
+// so $ matches at the end of a line and \s doesn't match the break
+fn content(line: &str) -> &str {
+    line.strip_suffix('\n').unwrap_or(line)
+}
+
+// Empty matches are not counted, the same way as they are not highlighted
+fn count_matches(lines: &[String], pattern: &Regex) -> usize {
+    lines
+        .iter()
+        .map(|line| pattern.find_iter(content(line)).filter(|found| !found.is_empty()).count())
+        .sum()
+}
+
+fn is_binary(reader: &mut impl BufRead) -> Result<bool> {
+    Ok(reader.fill_buf()?.contains(&0))
+}
+
+fn has_match(mut reader: impl BufRead, pattern: &Regex, invert_match: bool) -> Result<bool> {
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if pattern.is_match(content(&String::from_utf8_lossy(&line))) ^ invert_match {
+            return Ok(true);
+        }
+        line.clear();
+    }
+
+    Ok(false)
+}
+
 // --------------------------------------------------
 #[cfg(test)]
 mod tests {
-    use super::{find_files, find_lines};
+    use super::{
+        count_matches, find_files, find_lines, format_json, has_match, highlight, is_binary,
+    };
     use rand::{distributions::Alphanumeric, Rng};
     use regex::{Regex, RegexBuilder};
     use std::io::Cursor;
@@ -187,19 +579,19 @@ mod tests {
     #[test]
     fn test_find_files() {
         // Verify that the function finds a file known to exist
-        let files = find_files(&["./tests/inputs/fox.txt".to_string()], false);
+        let files = find_files(&["./tests/inputs/fox.txt".to_string()], false, true);
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].as_ref().unwrap(), "./tests/inputs/fox.txt");
 
         // The function should reject a directory without the recursive option
-        let files = find_files(&["./tests/inputs".to_string()], false);
+        let files = find_files(&["./tests/inputs".to_string()], false, true);
         assert_eq!(files.len(), 1);
         if let Err(e) = &files[0] {
             assert_eq!(e.to_string(), "./tests/inputs is a directory");
         }
 
         // Verify the function recurses to find four files in the directory
-        let res = find_files(&["./tests/inputs".to_string()], true);
+        let res = find_files(&["./tests/inputs".to_string()], true, true);
         let mut files: Vec<String> = res
             .iter()
             .map(|r| r.as_ref().unwrap().replace('\\', "/"))
@@ -224,7 +616,7 @@ mod tests {
             .collect();
 
         // Verify that the function returns the bad file as an error
-        let files = find_files(&[bad], false);
+        let files = find_files(&[bad], false, true);
         assert_eq!(files.len(), 1);
         assert!(files[0].is_err());
     }
@@ -235,12 +627,12 @@ mod tests {
 
         // The pattern _or_ should match the one line, "Lorem"
         let re1 = Regex::new("or").unwrap();
-        let matches = find_lines(Cursor::new(&text), &re1, false);
+        let matches = find_lines(Cursor::new(&text), &re1, false, None);
         assert!(matches.is_ok());
         assert_eq!(matches.unwrap().len(), 1);
 
         // When inverted, the function should match the other two lines
-        let matches = find_lines(Cursor::new(&text), &re1, true);
+        let matches = find_lines(Cursor::new(&text), &re1, true, None);
         assert!(matches.is_ok());
         assert_eq!(matches.unwrap().len(), 2);
 
@@ -251,13 +643,78 @@ mod tests {
             .unwrap();
 
         // The two lines "Lorem" and "DOLOR" should match
-        let matches = find_lines(Cursor::new(&text), &re2, false);
+        let matches = find_lines(Cursor::new(&text), &re2, false, None);
         assert!(matches.is_ok());
         assert_eq!(matches.unwrap().len(), 2);
 
         // When inverted, the one remaining line should match
-        let matches = find_lines(Cursor::new(&text), &re2, true);
+        let matches = find_lines(Cursor::new(&text), &re2, true, None);
         assert!(matches.is_ok());
         assert_eq!(matches.unwrap().len(), 1);
+
+        // Reading stops after the max count of matching lines
+        let matches = find_lines(Cursor::new(&text), &re2, false, Some(1));
+        assert_eq!(matches.unwrap(), vec!["Lorem\n"]);
+
+        let matches = find_lines(Cursor::new(&text), &re2, false, Some(0));
+        assert!(matches.unwrap().is_empty());
+
+        // Line breaks are not part of the matched text
+        let re3 = Regex::new(r"m$|\s").unwrap();
+        let matches = find_lines(Cursor::new(&text), &re3, false, None);
+        assert_eq!(matches.unwrap(), vec!["Lorem\n", "Ipsum\r\n"]);
+    }
+
+    #[test]
+    fn test_has_match() {
+        let text = b"Lorem\nIpsum\r\nDOLOR";
+        let re = Regex::new("or").unwrap();
+        assert!(has_match(Cursor::new(&text), &re, false).unwrap());
+        assert!(has_match(Cursor::new(&text), &re, true).unwrap());
+        assert!(!has_match(Cursor::new(b""), &re, false).unwrap());
+
+        let re = Regex::new("").unwrap();
+        assert!(!has_match(Cursor::new(&text), &re, true).unwrap());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let re = Regex::new("o+").unwrap();
+        assert_eq!(highlight("foo boo\n", &re), "f\x1b[1;31moo\x1b[0m b\x1b[1;31moo\x1b[0m\n");
+        assert_eq!(highlight("bar\r\n", &re), "bar\r\n");
+
+        let re = Regex::new("x*").unwrap();
+        assert_eq!(highlight("axb", &re), "a\x1b[1;31mx\x1b[0mb");
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(&mut Cursor::new(b"abc\0def\n")).unwrap());
+        assert!(!is_binary(&mut Cursor::new("текст\n".as_bytes())).unwrap());
+        assert!(!is_binary(&mut Cursor::new(b"")).unwrap());
+    }
+
+    #[test]
+    fn test_format_json() {
+        let re = Regex::new(r"(\w+)(!)?(\?)?").unwrap();
+        let captures = re.captures("say \"hi\"!").unwrap();
+        assert_eq!(
+            format_json("a.txt", 3, 100, &captures),
+            r#"{"file":"a.txt","line":3,"offset":100,"text":"say","groups":["say",null,null]}"#
+        );
+
+        let captures = re.captures_at("say \"hi\"!", 5).unwrap();
+        assert_eq!(
+            format_json("a.txt", 3, 100, &captures),
+            r#"{"file":"a.txt","line":3,"offset":105,"text":"hi","groups":["hi",null,null]}"#
+        );
+    }
+
+    #[test]
+    fn test_count_matches() {
+        let lines = ["the cat and the hat\n".to_string(), "The end".to_string()];
+        assert_eq!(count_matches(&lines, &Regex::new("the").unwrap()), 2);
+        assert_eq!(count_matches(&lines, &Regex::new("(?i)the").unwrap()), 3);
+        assert_eq!(count_matches(&lines, &Regex::new("x*").unwrap()), 0);
     }
 }
@@ -1,6 +1,7 @@
 fn main() {
     if let Err(error) = grep::get_args().and_then(grep::run) {
         eprintln!("{error}");
-        std::process::exit(1);
+        // Exit code 1 means that nothing matched
+        std::process::exit(2);
     }
 }
@@ -0,0 +1,124 @@
+use memmap2::Mmap;
+use regex::{Regex, RegexBuilder};
+use std::fs::File;
+
+// Lines are searched one by one, so \A and \z there mean the line start and end and
+// ^ with $ can be made to mean that only via the multi line mode that (?-m) turns off.
+// The check is crude, a pattern it rejects by mistake is just searched line by line.
+const LINE_ONLY: [&str; 3] = ["\\A", "\\z", "-m"];
+
+/// Pattern that finds at least every match of the line pattern in the whole mapped text
+pub fn candidates_pattern(pattern: &str, insensitive: bool) -> Option<Regex> {
+    if LINE_ONLY.iter().any(|part| pattern.contains(part)) {
+        return None;
+    }
+
+    // Multi line mode keeps ^ and $ matching around every line in the mapping
+    RegexBuilder::new(pattern)
+        .case_insensitive(insensitive)
+        .multi_line(true)
+        .build()
+        .ok()
+}
+
+/// Maps a regular non-empty file into memory, stdin and anything else is read as a stream
+pub fn map(path: &str) -> Option<Mmap> {
+    if path == "-" {
+        return None;
+    }
+
+    let file = File::open(path).ok()?;
+    let meta = file.metadata().ok()?;
+    if !meta.is_file() || meta.len() == 0 {
+        return None;
+    }
+
+    // SAFETY: the mapping is only read, a file changed by someone else while it is searched
+    // gives garbled lines the same way as reading it would, there is no other protection
+    unsafe { Mmap::map(&file) }.ok()
+}
+
+/// The same lines as find_lines without invert would give for the text
+///
+/// The regex runs over the whole text and only the lines around its matches are cut out.
+/// Candidate matches can span several lines, so every line is checked again with the
+/// line pattern. That way results are exactly the same as with the line by line search.
+pub fn find_lines(
+    text: &str,
+    candidates: &Regex,
+    pattern: &Regex,
+    max_count: Option<usize>,
+) -> Vec<String> {
+    let mut results = Vec::new();
+    let mut position = 0;
+
+    while max_count.is_none_or(|max| results.len() < max) {
+        // Empty match after the last line ending is not in any line
+        let Some(found) = candidates.find_at(text, position) else {
+            break;
+        };
+        if found.start() == text.len() && text.ends_with('\n') {
+            break;
+        }
+
+        // Line includes its ending the same way as with read_line
+        let start = text[..found.start()].rfind('\n').map_or(0, |end| end + 1);
+        let end = text[found.start()..]
+            .find('\n')
+            .map_or(text.len(), |end| found.start() + end + 1);
+        let line = &text[start..end];
+
+        if pattern.is_match(crate::content(line)) {
+            results.push(line.to_string());
+        }
+
+        // Empty match at the end of the text has no line after it
+        if end == text.len() {
+            break;
+        }
+        position = end;
+    }
+
+    results
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{candidates_pattern, find_lines};
+    use regex::Regex;
+
+    #[test]
+    fn test_candidates_pattern() {
+        assert!(candidates_pattern("^the", false).is_some());
+        assert!(candidates_pattern("\\Athe", false).is_none());
+        assert!(candidates_pattern("dog\\z", false).is_none());
+        assert!(candidates_pattern("(?-m)^dog", false).is_none());
+        assert!(candidates_pattern("*foo", false).is_none());
+    }
+
+    #[test]
+    fn test_find_lines() {
+        let text = "Lorem\nIpsum\r\nDOLOR";
+        let lines = |pattern: &str, max_count| {
+            let candidates = candidates_pattern(pattern, false).unwrap();
+            find_lines(text, &candidates, &Regex::new(pattern).unwrap(), max_count)
+        };
+
+        assert_eq!(lines("or", None), ["Lorem\n"]);
+        assert_eq!(lines("(?i)or", None), ["Lorem\n", "DOLOR"]);
+        assert_eq!(lines("(?i)or", Some(1)), ["Lorem\n"]);
+        assert_eq!(lines("^I", None), ["Ipsum\r\n"]);
+        assert_eq!(lines("m$", None), ["Lorem\n"]);
+        assert_eq!(lines("R$", None), ["DOLOR"]);
+        assert_eq!(lines("", None), ["Lorem\n", "Ipsum\r\n", "DOLOR"]);
+
+        // Candidate that spans lines is not a match in any of them
+        assert!(lines("m\\s+I", None).is_empty());
+
+        let text = "ab\nab x\n";
+        let candidates = candidates_pattern("b\\s+a|x", false).unwrap();
+        let pattern = Regex::new("b\\s+a|x").unwrap();
+        assert_eq!(find_lines(text, &candidates, &pattern, None), ["ab x\n"]);
+    }
+}
@@ -1,7 +1,7 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::{fs, path::Path};
+use std::{fs, io::Write, path::Path};
 use sys_info::os_type;
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -277,3 +277,314 @@ fn stdin_insensitive_count() -> TestResult {
         .stdout(expected);
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn files_with_matches() -> TestResult {
+    run(
+        &["-l", "fox", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.fox.files_with_matches",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn files_without_match() -> TestResult {
+    run(
+        &["--files_without_match", "fox", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.fox.files_without_match",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_always() -> TestResult {
+    run(
+        &["--color=always", "the", BUSTLE, FOX],
+        "tests/expected/bustle.fox.the.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_never() -> TestResult {
+    run(
+        &["--color=never", "The", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.capitalized",
+    )
+}
+
+// --------------------------------------------------
+fn binary_file() -> Result<tempfile::NamedTempFile, Box<dyn std::error::Error>> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(b"header\0\xFF\nthe fox\n")?;
+    Ok(file)
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_matches() -> TestResult {
+    let binary = binary_file()?;
+    let binary = binary.path().to_string_lossy();
+    let expected = format!(
+        "Binary file {binary} matches\n\
+        tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.\n"
+    );
+
+    Command::cargo_bin(PRG)?
+        .args(["fox", &binary, FOX])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_as_text() -> TestResult {
+    let binary = binary_file()?;
+
+    Command::cargo_bin(PRG)?
+        .args(["-a", "fox|header"])
+        .arg(binary.path())
+        .assert()
+        .success()
+        .stdout("header\0\u{FFFD}\nthe fox\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_without_match() -> TestResult {
+    let binary = binary_file()?;
+
+    Command::cargo_bin(PRG)?
+        .args(["--binary_files=without-match", "fox"])
+        .arg(binary.path())
+        .arg(FOX)
+        .assert()
+        .success()
+        .stdout("tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn max_count() -> TestResult {
+    run(
+        &["-m", "2", "-i", "the", BUSTLE, NOBODY],
+        "tests/expected/bustle.nobody.the.insensitive.max_count",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn max_count_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--max_count", "1", "the", BUSTLE])
+        .assert()
+        .success()
+        .stdout("1\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet_match() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-q", "fox", &gen_bad_file(), BUSTLE, FOX])
+        .assert()
+        .code(0)
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet_no_match() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--quiet", "fox", BUSTLE, EMPTY])
+        .assert()
+        .code(1)
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exit_codes() -> TestResult {
+    Command::cargo_bin(PRG)?.args(["fox", FOX]).assert().code(0);
+    Command::cargo_bin(PRG)?.args(["fox", BUSTLE]).assert().code(1);
+    Command::cargo_bin(PRG)?.args(["fox", FOX, &gen_bad_file()]).assert().code(2);
+    Command::cargo_bin(PRG)?.args(["*foo", FOX]).assert().code(2);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn ignored_files() -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let folder = tempfile::tempdir()?;
+    fs::create_dir(folder.path().join(".git"))?;
+    fs::create_dir(folder.path().join("build"))?;
+    fs::write(folder.path().join(".gitignore"), "build/\n*.log\n")?;
+
+    for file in [".git/config", "build/out.txt", "app.log", "main.txt", ".hidden.txt"] {
+        fs::write(folder.path().join(file), "needle\n")?;
+    }
+    Ok(folder)
+}
+
+// --------------------------------------------------
+fn run_sorted_in(folder: &Path, args: &[&str]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin(PRG)?.current_dir(folder).args(args).output()?;
+    let mut lines: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.replace('\\', "/"))
+        .collect();
+    lines.sort();
+    Ok(lines)
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_gitignore() -> TestResult {
+    let folder = ignored_files()?;
+
+    let found = run_sorted_in(folder.path(), &["-rl", "needle", "."])?;
+    assert_eq!(found, ["./.hidden.txt", "./main.txt"]);
+
+    let found = run_sorted_in(folder.path(), &["-rl", "--no_ignore", "needle", "."])?;
+    assert_eq!(
+        found,
+        ["./.git/config", "./.hidden.txt", "./app.log", "./build/out.txt", "./main.txt"]
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mmap_multiple_files() -> TestResult {
+    run(
+        &["--mmap", "The", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.capitalized",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn mmap_insensitive_count() -> TestResult {
+    run(
+        &["--mmap", "-ic", "the", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.lowercase.insensitive.count",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn no_messages() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "fox", &gen_bad_file(), INPUTS_DIR, FOX])
+        .assert()
+        .code(2)
+        .stderr("")
+        .stdout("tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json() -> TestResult {
+    run(&["--json", r"(\w+) (fox|dog)", FOX], "tests/expected/fox.txt.json")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_json_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--json", "-c", "fox", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stats() -> TestResult {
+    let expected = "tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.\n\n\
+        2 files searched\n\
+        1 files with matches\n\
+        1 lines matched\n\
+        45 bytes searched\n\
+        [0-9.]+ seconds spent\n$";
+    Command::cargo_bin(PRG)?
+        .args(["--stats", "fox", EMPTY, FOX])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(format!("^{expected}"))?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn with_filename_single() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-H", "fox", FOX])
+        .assert()
+        .success()
+        .stdout("tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn no_filename_multiple() -> TestResult {
+    run(
+        &["-h", "The", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.capitalized.no_filename",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn with_filename_stdin() -> TestResult {
+    let input = fs::read_to_string(FOX)?;
+
+    Command::cargo_bin(PRG)?
+        .args(["--with_filename", "fox"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("(standard input):The quick brown fox jumps over the lazy dog.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn count_matches() -> TestResult {
+    run(
+        &["--count_matches", "-i", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.lowercase.insensitive.count_matches",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn count_invert_empty_lines() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-cv", "^$", BUSTLE])
+        .assert()
+        .success()
+        .stdout("8\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn count_invert_whitespace() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "-v", r"\s", BUSTLE])
+        .assert()
+        .success()
+        .stdout("1\n");
+    Ok(())
+}
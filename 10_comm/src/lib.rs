@@ -1,81 +1,324 @@
 use anyhow::{anyhow, bail, Result};
 use clap::{arg, Arg, ArgAction, Command};
 use std::cmp::Ordering::{Equal, Greater, Less};
-use std::io::{BufRead, BufReader};
+use std::collections::HashMap;
+use std::hash::Hash;
+use clap::builder::PossibleValuesParser;
+use common::columns::format_row;
+use common::reader::{open, RecordRead, RecordReader};
+use std::io::{BufWriter, Write};
 
 #[derive(Debug)]
 pub struct Config {
-    file1: String,
-    file2: String,
+    files: Vec<String>,
     show_col1: bool,
     show_col2: bool,
     show_col3: bool,
     case_insensitive: bool,
     delimeter: String,
+    order: OrderCheck,
+    total: bool,
+    terminator: u8,
+    unsorted: bool,
+    format: Format,
 }
 
-fn output(config: &Config, column: u8, value: &str) {
-    // Check if we even need to print this column
-    match column {
-        1 if !config.show_col1 => return,
-        2 if !config.show_col2 => return,
-        3 if !config.show_col3 => return,
-        _ => (),
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Columns, // lines indented with delimeters by their column, like GNU comm
+    Json,    // object per line with the column name as the key, e.g. for jq
+    Table,   // header with the column names and aligned columns
+}
+
+/// What to do with input that is not sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderCheck {
+    /// Warn once per file and fail after all the output is done, like GNU comm
+    Warn,
+    /// Fail on the first line that is out of order
+    Strict,
+    /// Trust that the input is sorted
+    Off,
+}
+
+/// Line iterator that checks that every line is not less than the one before it
+struct Ordered<'a, I> {
+    lines: I,
+    path: &'a str,
+    check: OrderCheck,
+    previous: Option<Vec<u8>>,
+    number: usize,
+    warned: bool,
+}
+
+impl<'a, I> Ordered<'a, I> {
+    fn new(lines: I, path: &'a str, check: OrderCheck) -> Self {
+        Ordered { lines, path, check, previous: None, number: 0, warned: false }
     }
+}
 
-    // How many delimeters do we need to print before the value
-    let number_of_delimeters = match column {
-        1 => 0,
-        2 => u8::from(config.show_col1),
-        3 => u8::from(config.show_col1) + u8::from(config.show_col2),
-        _ => panic!("Invalid column number"),
-    };
+impl<I: Iterator<Item = Result<Vec<u8>>>> Iterator for Ordered<'_, I> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(error) => return Some(Err(error)),
+        };
+        self.number += 1;
+
+        if self.check == OrderCheck::Off {
+            return Some(Ok(line));
+        }
+
+        if self.previous.as_ref().is_some_and(|previous| *previous > line) {
+            let path = self.path;
+            let number = self.number;
+            let text = String::from_utf8_lossy(&line);
+            let message = format!("{path}: line {number} is not in sorted order: {text}");
+            match self.check {
+                OrderCheck::Strict => return Some(Err(anyhow!(message))),
+                OrderCheck::Warn if !self.warned => {
+                    eprintln!("{message}");
+                    self.warned = true;
+                }
+                _ => (),
+            }
+        }
+
+        // Reuses the allocation of the previous line
+        match &mut self.previous {
+            Some(previous) => previous.clone_from(&line),
+            None => self.previous = Some(line.clone()),
+        }
+        Some(Ok(line))
+    }
+}
 
-    // Print value in the corresponding column
-    let delimeters = config.delimeter.repeat(number_of_delimeters as usize);
-    println!("{delimeters}{value}");
+/// Which column of the output a line goes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Line is only in the first file
+    First,
+    /// Line is only in the second file
+    Second,
+    /// Line is in both files
+    Both,
 }
 
-pub fn run(config: Config) -> Result<()> {
-    // Case insensitivity
-    let casing = |entry: std::result::Result<String, std::io::Error>| -> Option<String> {
-        match entry {
-            Err(error) => { eprintln!("Error: {error}"); None },
-            Ok(line) => Some(if config.case_insensitive { line.to_ascii_lowercase() } else { line })
+impl Column {
+    const ALL: [Column; 3] = [Column::First, Column::Second, Column::Both];
+
+    /// Name of the column in json and table output
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::First => "only_a",
+            Column::Second => "only_b",
+            Column::Both => "both",
         }
-    };
+    }
+}
+
+impl Config {
+    fn shows(&self, column: Column) -> bool {
+        match column {
+            Column::First => self.show_col1,
+            Column::Second => self.show_col2,
+            Column::Both => self.show_col3,
+        }
+    }
+
+    /// How many delimeters go before the value, hidden columns take no space
+    fn indent(&self, column: Column) -> usize {
+        match column {
+            Column::First => 0,
+            Column::Second => usize::from(self.show_col1),
+            Column::Both => usize::from(self.show_col1) + usize::from(self.show_col2),
+        }
+    }
+}
+
+/// Destination of the lines in the chosen --format
+///
+/// With -z only the input is NUL separated for json and table, their rows end with newlines.
+enum Output {
+    Columns,
+    Json,
+    // Column width depends on all the lines, so the table is printed at the end
+    Table(Vec<(Column, String)>),
+}
+
+impl Output {
+    fn new(format: Format) -> Self {
+        match format {
+            Format::Columns => Output::Columns,
+            Format::Json => Output::Json,
+            Format::Table => Output::Table(Vec::new()),
+        }
+    }
+
+    fn write(
+        &mut self,
+        config: &Config,
+        out: &mut impl Write,
+        column: Column,
+        value: &[u8],
+    ) -> Result<()> {
+        if !config.shows(column) {
+            return Ok(());
+        }
+
+        match self {
+            Output::Columns => {
+                let delimeters = config.delimeter.repeat(config.indent(column));
+                out.write_all(delimeters.as_bytes())?;
+                out.write_all(value)?;
+                out.write_all(&[config.terminator])?;
+            }
+            Output::Json => {
+                let text = common::json::quote(&String::from_utf8_lossy(value));
+                writeln!(out, "{{\"{}\":{text}}}", column.label())?;
+            }
+            Output::Table(rows) => rows.push((column, String::from_utf8_lossy(value).into_owned())),
+        }
+        Ok(())
+    }
+
+    /// Prints what goes after all the lines, totals include the lines of hidden columns too
+    /// the same way as in GNU comm, except for the table that has only the shown columns
+    fn finish(self, config: &Config, out: &mut impl Write, totals: [usize; 3]) -> Result<()> {
+        let [first, second, both] = totals;
+        match self {
+            Output::Columns if config.total => {
+                let delimeter = &config.delimeter;
+                write!(out, "{first}{delimeter}{second}{delimeter}{both}{delimeter}total")?;
+                out.write_all(&[config.terminator])?;
+            }
+            Output::Json if config.total => {
+                let counts = format!(r#""only_a":{first},"only_b":{second},"both":{both}"#);
+                writeln!(out, r#"{{"total":{{{counts}}}}}"#)?;
+            }
+            Output::Table(rows) => print_table(config, out, &rows, totals)?,
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+fn print_table(
+    config: &Config,
+    out: &mut impl Write,
+    rows: &[(Column, String)],
+    totals: [usize; 3],
+) -> Result<()> {
+    let columns: Vec<Column> = Column::ALL.into_iter().filter(|&c| config.shows(c)).collect();
+
+    // Table without columns would be just empty lines, so nothing is printed like with -123
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    let labels: Vec<&str> = columns.iter().map(|column| column.label()).collect();
+    let counts: Vec<String> = columns
+        .iter()
+        .map(|&column| totals[column as usize].to_string())
+        .collect();
+
+    let width = rows
+        .iter()
+        .map(|(_, value)| value.as_str())
+        .chain(labels.iter().copied())
+        .chain(counts.iter().map(String::as_str))
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(0);
+    let separator = "  ";
+
+    writeln!(out, "{}", format_row(&labels, width, separator))?;
+    for (column, value) in rows {
+        // Cells after the value are left out, so rows don't end with spaces
+        let position = columns
+            .iter()
+            .position(|shown| shown == column)
+            .expect("Only lines of the shown columns are kept");
+        let mut cells = vec![""; position];
+        cells.push(value);
+        writeln!(out, "{}", format_row(&cells, width, separator))?;
+    }
+
+    if config.total {
+        let line = "-".repeat(width);
+        let lines = vec![line.as_str(); columns.len()];
+        writeln!(out, "{}", format_row(&lines, width, separator))?;
+        let counts: Vec<&str> = counts.iter().map(String::as_str).collect();
+        writeln!(out, "{}", format_row(&counts, width, separator))?;
+    }
+    Ok(())
+}
+
+/// Records of the file without their terminators, a line also loses its \r the same way
+/// as with `BufRead::lines`
+fn records(path: &str, terminator: u8) -> Result<impl Iterator<Item = Result<Vec<u8>>> + '_> {
+    let mut reader = RecordReader::new(open(path)?, terminator);
+
+    Ok(std::iter::from_fn(move || {
+        let mut record = Vec::new();
+        match reader.read_record(&mut record) {
+            Ok(0) => None,
+            Ok(_) => {
+                if record.last() == Some(&terminator) {
+                    record.pop();
+                    if terminator == b'\n' && record.last() == Some(&b'\r') {
+                        record.pop();
+                    }
+                }
+                Some(Ok(record))
+            }
+            Err(error) => Some(Err(anyhow!("{path}: {error}"))),
+        }
+    }))
+}
 
-    // Open iterators
-    let mut file1 = open(&config.file1)?.lines().filter_map(casing);
-    let mut file2 = open(&config.file2)?.lines().filter_map(casing);
-    let mut a = file1.next();
-    let mut b = file2.next();
+/// Ordered merge of two sorted line streams
+///
+/// Every line is passed to the visitor together with its column. Equal lines are
+/// paired one to one, so a line repeated in one file more often goes to its own column.
+pub fn merge<T, A, B, F>(lines1: A, lines2: B, mut visit: F) -> Result<()>
+where
+    T: Ord,
+    A: IntoIterator<Item = Result<T>>,
+    B: IntoIterator<Item = Result<T>>,
+    F: FnMut(Column, &T) -> Result<()>,
+{
+    let mut lines1 = lines1.into_iter();
+    let mut lines2 = lines2.into_iter();
+    let mut a = lines1.next().transpose()?;
+    let mut b = lines2.next().transpose()?;
 
-    // Line comparison
     loop {
         match (&a, &b) {
             (None, None) => break,
             (Some(a_text), None) => {
-                output(&config, 1, a_text);
-                a = file1.next();
-            },
+                visit(Column::First, a_text)?;
+                a = lines1.next().transpose()?;
+            }
             (None, Some(b_text)) => {
-                output(&config, 2, b_text);
-                b = file2.next();
-            },
+                visit(Column::Second, b_text)?;
+                b = lines2.next().transpose()?;
+            }
             (Some(a_text), Some(b_text)) => match a_text.cmp(b_text) {
                 Equal => {
-                    output(&config, 3, a_text);
-                    a = file1.next();
-                    b = file2.next();
+                    visit(Column::Both, a_text)?;
+                    a = lines1.next().transpose()?;
+                    b = lines2.next().transpose()?;
                 }
                 Less => {
-                    output(&config, 1, a_text);
-                    a = file1.next();
+                    visit(Column::First, a_text)?;
+                    a = lines1.next().transpose()?;
                 }
                 Greater => {
-                    output(&config, 2, b_text);
-                    b = file2.next();
+                    visit(Column::Second, b_text)?;
+                    b = lines2.next().transpose()?;
                 }
             },
         }
@@ -84,25 +327,160 @@ pub fn run(config: Config) -> Result<()> {
     Ok(())
 }
 
+/// The same columns as merge gives for the sorted lines, but for lines in any order
+///
+/// Both inputs are counted in hash maps first, so memory grows with the number of distinct
+/// lines in the files instead of staying constant as with merge. Lines are visited in
+/// sorted order, a line repeated in the files is paired the same way as merge does it.
+pub fn compare_unsorted<T, A, B, F>(lines1: A, lines2: B, mut visit: F) -> Result<()>
+where
+    T: Ord + Hash,
+    A: IntoIterator<Item = Result<T>>,
+    B: IntoIterator<Item = Result<T>>,
+    F: FnMut(Column, &T) -> Result<()>,
+{
+    let counts1 = count(lines1)?;
+    let counts2 = count(lines2)?;
+
+    let mut distinct: Vec<&T> = counts1
+        .keys()
+        .chain(counts2.keys().filter(|line| !counts1.contains_key(*line)))
+        .collect();
+    distinct.sort_unstable();
+
+    for line in distinct {
+        let a = counts1.get(line).copied().unwrap_or(0);
+        let b = counts2.get(line).copied().unwrap_or(0);
+        let both = a.min(b);
+
+        for _ in 0..both {
+            visit(Column::Both, line)?;
+        }
+        for _ in both..a {
+            visit(Column::First, line)?;
+        }
+        for _ in both..b {
+            visit(Column::Second, line)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Ordered merge of any number of sorted line streams
+///
+/// Every line is passed to the visitor together with flags of the files that have it.
+/// Equal lines are paired the same way as in merge. The smallest line is found with a scan
+/// over all the files, that is fast enough for the handful of files people compare.
+pub fn merge_many<T, I, F>(files: &mut [I], mut visit: F) -> Result<()>
+where
+    T: Ord,
+    I: Iterator<Item = Result<T>>,
+    F: FnMut(&[bool], &T) -> Result<()>,
+{
+    let mut heads = files
+        .iter_mut()
+        .map(|file| file.next().transpose())
+        .collect::<Result<Vec<_>>>()?;
+    let mut present = vec![false; files.len()];
+
+    while let Some(smallest) = heads.iter().flatten().min() {
+        for (flag, head) in present.iter_mut().zip(&heads) {
+            *flag = head.as_ref() == Some(smallest);
+        }
+        visit(&present, smallest)?;
+
+        for (index, file) in files.iter_mut().enumerate() {
+            if present[index] {
+                heads[index] = file.next().transpose()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn count<T: Eq + Hash>(lines: impl IntoIterator<Item = Result<T>>) -> Result<HashMap<T, usize>> {
+    let mut counts = HashMap::new();
+    for line in lines {
+        *counts.entry(line?).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+pub fn run(config: Config) -> Result<()> {
+    // Case insensitivity is done by comparing and printing lowercased lines
+    let casing = |line: Result<Vec<u8>>| -> Result<Vec<u8>> {
+        let line = line?;
+        Ok(if config.case_insensitive { line.to_ascii_lowercase() } else { line })
+    };
+
+    let mut files = config
+        .files
+        .iter()
+        .map(|path| {
+            let records = records(path, config.terminator)?.map(casing);
+            Ok(Ordered::new(records, path, config.order))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let compared = match files.as_mut_slice() {
+        [file1, file2] => compare_two(&config, &mut out, file1, file2),
+        files => compare_many(&config, &mut out, files),
+    };
+    out.flush()?;
+    compared?;
+
+    if files.iter().any(|file| file.warned) {
+        bail!("input is not in sorted order");
+    }
+    Ok(())
+}
+
+fn compare_two<L>(config: &Config, out: &mut impl Write, file1: L, file2: L) -> Result<()>
+where
+    L: Iterator<Item = Result<Vec<u8>>>,
+{
+    let mut output = Output::new(config.format);
+    let mut totals = [0; 3];
+    let visit = |column, value: &Vec<u8>| {
+        totals[column as usize] += 1;
+        output.write(config, out, column, value)
+    };
 
-fn open(path: &str) -> Result<Box<dyn BufRead>> {
-    match path {
-        "-" => Ok(Box::new(BufReader::new(std::io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(
-            std::fs::File::open(path).map_err(|e| anyhow!("{path}: {e}"))?,
-        ))),
+    if config.unsorted {
+        compare_unsorted(file1, file2, visit)?;
+    } else {
+        merge(file1, file2, visit)?;
     }
+    output.finish(config, out, totals)
+}
+
+/// Every line goes after a mask with 1 for the files that have it and 0 for the rest,
+/// e.g. 101 is a line from the first and the third file
+fn compare_many<L>(config: &Config, out: &mut impl Write, files: &mut [L]) -> Result<()>
+where
+    L: Iterator<Item = Result<Vec<u8>>>,
+{
+    merge_many(files, |present, value| {
+        let mask: String = present.iter().map(|&flag| if flag { '1' } else { '0' }).collect();
+        write!(out, "{mask}{}", config.delimeter)?;
+        out.write_all(value)?;
+        out.write_all(&[config.terminator])?;
+        Ok(())
+    })
 }
 
 pub fn get_args() -> Result<Config> {
     // CLI arguments
-    let mut matches = Command::new("grep")
+    let mut matches = Command::new("comm")
         .version("1.0")
         .author("FallenGameR")
-        .about("Finds common and unique lines in two sorted files")
+        .about("Finds common and unique lines in sorted files")
         .args([
-            arg!(<FILE1> "First file to process, stdin is -"),
-            arg!(<FILE2> "Second file to process, stdin is -"),
+            arg!(<FILES> ... "Files to compare, stdin is -, more than two are shown with masks")
+                .num_args(2..),
             Arg::new("column1")
                 .short('1')
                 .help("Don't print column1 (unique lines from first file)")
@@ -117,22 +495,55 @@ pub fn get_args() -> Result<Config> {
                 .action(ArgAction::SetTrue),
             arg!(-i --insensitive "Perform case insensitive matching"),
             arg!(-d --delimeter <DELIMETER> "Delimiter to use for columns").default_value("\t"),
+            arg!(--check_order "Fail on the first line that is not in sorted order"),
+            arg!(--nocheck_order "Don't check that the input is sorted")
+                .conflicts_with("check_order"),
+            arg!(--total "Print the number of lines in every column at the end"),
+            arg!(-z --zero_terminated "Lines end with NUL instead of newline, e.g. for -print0"),
+            arg!(--unsorted "Compare files in any order, both of them are kept in memory")
+                .conflicts_with("check_order"),
+            arg!(--format <FORMAT> "Output format, json and table name the columns")
+                .value_parser(PossibleValuesParser::new(["columns", "json", "table"]))
+                .default_value("columns"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let files: Vec<String> = matches
+        .remove_many("FILES")
+        .expect("No file paths provided")
+        .collect();
+    let format: String = matches.remove_one("format").expect("Default value is provided");
 
-    // Check that we don't have both files set to stdin
-    let file1: String = matches.remove_one("FILE1").expect("No first file provided");
-    let file2: String = matches
-        .remove_one("FILE2")
-        .expect("No second file provided");
-    if file1 == "-" && file2 == "-" {
-        bail!("Both input files cannot be STDIN (\"-\")");
+    // Check that we don't have several files set to stdin
+    if files.iter().filter(|file| *file == "-").count() > 1 {
+        match files.len() {
+            2 => bail!("Both input files cannot be STDIN (\"-\")"),
+            _ => bail!("Only one input file can be STDIN (\"-\")"),
+        }
+    }
+
+    // Columns are only for two files, more files are shown with masks
+    if files.len() > 2 {
+        let two_files_only = [
+            ("column1", "-1"),
+            ("column2", "-2"),
+            ("column3", "-3"),
+            ("total", "--total"),
+            ("unsorted", "--unsorted"),
+        ];
+        for (id, name) in two_files_only {
+            if matches.get_flag(id) {
+                bail!("{name} can be used only with two files");
+            }
+        }
+        if format != "columns" {
+            bail!("--format {format} can be used only with two files");
+        }
     }
 
     // Construct config
     Ok(Config {
-        file1,
-        file2,
+        files,
         show_col1: !matches.get_flag("column1"),
         show_col2: !matches.get_flag("column2"),
         show_col3: !matches.get_flag("column3"),
@@ -140,5 +551,91 @@ pub fn get_args() -> Result<Config> {
         delimeter: matches
             .remove_one("delimeter")
             .expect("No delimeter was provided"),
+        order: match (
+            matches.get_flag("check_order"),
+            matches.get_flag("nocheck_order") || matches.get_flag("unsorted"),
+        ) {
+            (true, _) => OrderCheck::Strict,
+            (_, true) => OrderCheck::Off,
+            _ => OrderCheck::Warn,
+        },
+        total: matches.get_flag("total"),
+        terminator: if matches.get_flag("zero_terminated") { b'\0' } else { b'\n' },
+        unsorted: matches.get_flag("unsorted"),
+        format: parse_format(&format),
     })
 }
+
+fn parse_format(name: &str) -> Format {
+    match name {
+        "columns" => Format::Columns,
+        "json" => Format::Json,
+        "table" => Format::Table,
+        unknown => unreachable!("Unsupported format {unknown}"),
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{compare_unsorted, merge, merge_many, Column};
+    use anyhow::{anyhow, Result};
+
+    fn lines(text: &str) -> Vec<Result<String>> {
+        text.lines().map(|line| Ok(line.to_string())).collect()
+    }
+
+    fn columns(first: &str, second: &str) -> Vec<(Column, String)> {
+        let mut result = Vec::new();
+        merge(lines(first), lines(second), |column, value| {
+            result.push((column, value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        result
+    }
+
+    #[test]
+    fn test_merge() {
+        use Column::{Both, First, Second};
+        let pair = |column, value: &str| (column, value.to_string());
+
+        assert!(columns("", "").is_empty());
+        assert_eq!(columns("a\nc", ""), [pair(First, "a"), pair(First, "c")]);
+        assert_eq!(columns("", "b"), [pair(Second, "b")]);
+        assert_eq!(
+            columns("a\nb\nb\nd", "b\nc\nd\ne"),
+            [
+                pair(First, "a"),
+                pair(Both, "b"),
+                pair(First, "b"),
+                pair(Second, "c"),
+                pair(Both, "d"),
+                pair(Second, "e"),
+            ]
+        );
+
+        // Unsorted comparison gives the same columns as merge of the sorted lines
+        let mut unsorted = Vec::new();
+        compare_unsorted(lines("d\nb\na\nb"), lines("e\nd\nb\nc"), |column, value| {
+            unsorted.push((column, value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(unsorted, columns("a\nb\nb\nd", "b\nc\nd\ne"));
+
+        let mut files = [lines("a\nb\nb\nd"), lines("B\nc"), lines("b\nc\nd")].map(Vec::into_iter);
+        let mut masks = Vec::new();
+        merge_many(&mut files, |present, value| {
+            let mask: String = present.iter().map(|&flag| if flag { '1' } else { '0' }).collect();
+            masks.push(format!("{mask} {value}"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(masks, ["010 B", "100 a", "101 b", "100 b", "011 c", "101 d"]);
+
+        let failing = vec![Ok("a".to_string()), Err(anyhow!("broken"))];
+        let result = merge(failing, lines("b"), |_, _| Ok(()));
+        assert_eq!(result.unwrap_err().to_string(), "broken");
+    }
+}
@@ -7,7 +7,12 @@ const PRG: &str = "comm";
 const EMPTY: &str = "tests/inputs/empty.txt";
 const FILE1: &str = "tests/inputs/file1.txt";
 const FILE2: &str = "tests/inputs/file2.txt";
+const FILE3: &str = "tests/inputs/file3.txt";
 const BLANK: &str = "tests/inputs/blank.txt";
+const CITIES1: &str = "tests/inputs/cities1.txt";
+const CITIES2: &str = "tests/inputs/cities2.txt";
+const PATHS1: &str = "tests/inputs/paths1.z";
+const PATHS2: &str = "tests/inputs/paths2.z";
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -340,6 +345,152 @@ fn blank_file1() -> TestResult {
     run(&[BLANK, FILE1], "tests/expected/blank_file1.out")
 }
 
+// --------------------------------------------------
+#[test]
+fn file1_file2_total() -> TestResult {
+    run(&["--total", FILE1, FILE2], "tests/expected/file1_file2.total.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_12_total_delim() -> TestResult {
+    run(
+        &[FILE1, FILE2, "-12", "--total", "-d", ":"],
+        "tests/expected/file1_file2.12.total.delim.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_total_json() -> TestResult {
+    run(
+        &[FILE1, FILE2, "--total", "--format", "json"],
+        "tests/expected/file1_file2.total.json.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_total_table() -> TestResult {
+    run(
+        &[FILE1, FILE2, "--total", "--format", "table"],
+        "tests/expected/file1_file2.total.table.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_2_table() -> TestResult {
+    run(&[FILE1, FILE2, "-2", "--format", "table"], "tests/expected/file1_file2.2.table.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_123_total_table() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([FILE1, FILE2, "-123", "--total", "--format", "table"])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn paths1_paths2_zero_terminated() -> TestResult {
+    let expected = fs::read("tests/expected/paths1_paths2.z.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["-z", PATHS1, PATHS2])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn paths1_paths2_3_total_zero_terminated() -> TestResult {
+    let expected = fs::read("tests/expected/paths1_paths2.3.total.z.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-3", "--total", PATHS1, PATHS2])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_warns() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/cities1_cities2.out")?;
+    Command::cargo_bin(PRG)?
+        .args([CITIES1, CITIES2])
+        .assert()
+        .failure()
+        .stdout(expected)
+        .stderr(predicate::str::contains(format!(
+            "{CITIES1}: line 2 is not in sorted order: Denton\n\
+             {CITIES2}: line 2 is not in sorted order: Denver\n\
+             input is not in sorted order"
+        )));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_check_order() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--check_order", CITIES1, CITIES2])
+        .assert()
+        .failure()
+        .stdout("Jackson\n")
+        .stderr(format!("{CITIES1}: line 2 is not in sorted order: Denton\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_nocheck_order() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/cities1_cities2.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--nocheck_order", CITIES1, CITIES2])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_set_comparison() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/cities1_cities2.unsorted.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--unsorted", CITIES1, CITIES2])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_file3() -> TestResult {
+    run(&[FILE1, FILE2, FILE3], "tests/expected/file1_file2_file3.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_columns_with_three_files() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-3", FILE1, FILE2, FILE3])
+        .assert()
+        .failure()
+        .stderr("-3 can be used only with two files\n");
+    Ok(())
+}
+
 //// --------------------------------------------------
 //#[test]
 //fn file1_blanks() -> TestResult {
@@ -0,0 +1,185 @@
+use common::reader::{AsyncRecordRead, RecordReader};
+use std::fmt::{self, Display};
+use std::fs::Metadata;
+use std::io::SeekFrom;
+use tokio::fs::{self, File};
+use tokio::io::{AsyncSeekExt, BufReader};
+
+type Reader = RecordReader<BufReader<File>>;
+
+// Device and inode on unix, a file that is recreated under the same name gets new ones
+#[cfg(unix)]
+type Identity = (u64, u64);
+
+#[cfg(unix)]
+fn identity(meta: &Metadata) -> Identity {
+    use std::os::unix::fs::MetadataExt;
+    (meta.dev(), meta.ino())
+}
+
+// Elsewhere a rotated file is noticed only when it is shorter than the read part of the old one
+#[cfg(not(unix))]
+type Identity = ();
+
+#[cfg(not(unix))]
+fn identity(_meta: &Metadata) -> Identity {}
+
+/// Change of the followed file that is reported to the user
+#[derive(Debug, PartialEq)]
+pub enum Event {
+    Appeared,
+    Replaced,
+    Truncated,
+    Inaccessible(String),
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Appeared => write!(f, "has appeared, following new file"),
+            Event::Replaced => write!(f, "has been replaced, following new file"),
+            Event::Truncated => write!(f, "file truncated"),
+            Event::Inaccessible(error) => write!(f, "has become inaccessible: {error}"),
+        }
+    }
+}
+
+/// File that -F keeps reading by its name
+///
+/// The open handle is read till its end first, so lines written to a log right before
+/// it is rotated are not lost, and only then the name is checked for a new file behind it.
+/// Reads are async, so all the followed files are polled from a single thread.
+pub struct Followed<'a> {
+    path: &'a str,
+    file: Option<(Reader, Identity)>,
+    position: u64,
+}
+
+impl<'a> Followed<'a> {
+    /// Starts at the end of the file, its tail is expected to be printed already
+    pub async fn new(path: &'a str) -> Self {
+        let mut followed = Followed { path, file: None, position: 0 };
+        if let Some((mut reader, identity)) = open(path).await {
+            if let Ok(end) = reader.get_mut().seek(SeekFrom::End(0)).await {
+                followed.position = end;
+                followed.file = Some((reader, identity));
+            }
+        }
+        followed
+    }
+
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /// Appends what was written to the file since the last poll to `data`
+    pub async fn poll(&mut self, data: &mut Vec<u8>) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        if let Some((reader, _)) = &mut self.file {
+            // Truncated in place, e.g. by logrotate copytruncate
+            let meta = reader.get_mut().get_ref().metadata().await;
+            if meta.is_ok_and(|meta| meta.len() < self.position)
+                && reader.get_mut().seek(SeekFrom::Start(0)).await.is_ok()
+            {
+                events.push(Event::Truncated);
+                self.position = 0;
+            }
+            self.position += read_all(reader, data).await;
+        }
+
+        match fs::metadata(self.path).await {
+            Err(error) => {
+                if self.file.take().is_some() {
+                    events.push(Event::Inaccessible(error.to_string()));
+                }
+            }
+            Ok(meta) if self.file.as_ref().map(|(_, known)| *known) != Some(identity(&meta)) => {
+                if let Some((mut reader, identity)) = open(self.path).await {
+                    let event = if self.file.is_some() { Event::Replaced } else { Event::Appeared };
+                    events.push(event);
+                    self.position = read_all(&mut reader, data).await;
+                    self.file = Some((reader, identity));
+                }
+            }
+            Ok(_) => (),
+        }
+
+        events
+    }
+}
+
+async fn open(path: &str) -> Option<(Reader, Identity)> {
+    let file = File::open(path).await.ok()?;
+    let identity = identity(&file.metadata().await.ok()?);
+    Some((RecordReader::lines(BufReader::new(file)), identity))
+}
+
+// Line that is still being written comes without its ending, the rest of it comes with
+// the next poll. Bytes that can't be read now are read on the next poll too.
+async fn read_all(reader: &mut Reader, data: &mut Vec<u8>) -> u64 {
+    let start = data.len();
+    while let Ok(read) = reader.read_record(data).await {
+        if read == 0 {
+            break;
+        }
+    }
+    (data.len() - start) as u64
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{Event, Followed};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    async fn poll(followed: &mut Followed<'_>) -> (String, Vec<Event>) {
+        let mut data = Vec::new();
+        let events = followed.poll(&mut data).await;
+        (String::from_utf8(data).unwrap(), events)
+    }
+
+    // Open files can't be renamed on Windows and replaced files are told apart by inode
+    #[cfg(unix)]
+    #[test]
+    fn test_poll() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let path = path.to_str().unwrap();
+        fs::write(path, "old\n").unwrap();
+
+        let append = |text: &str| {
+            let mut file = OpenOptions::new().append(true).open(path).unwrap();
+            file.write_all(text.as_bytes()).unwrap();
+        };
+
+        common::reader::runtime().unwrap().block_on(async {
+            let mut followed = Followed::new(path).await;
+            assert_eq!(poll(&mut followed).await, (String::new(), vec![]));
+
+            append("one\n");
+            assert_eq!(poll(&mut followed).await, ("one\n".to_string(), vec![]));
+
+            // Rotation, the last line of the old file comes before the new file
+            append("two\n");
+            fs::rename(path, dir.path().join("app.log.1")).unwrap();
+            fs::write(path, "three\n").unwrap();
+            assert_eq!(
+                poll(&mut followed).await,
+                ("two\nthree\n".to_string(), vec![Event::Replaced])
+            );
+
+            fs::write(path, "4\n").unwrap();
+            assert_eq!(poll(&mut followed).await, ("4\n".to_string(), vec![Event::Truncated]));
+
+            fs::remove_file(path).unwrap();
+            let (data, events) = poll(&mut followed).await;
+            assert!(data.is_empty());
+            assert!(matches!(events[..], [Event::Inaccessible(_)]));
+
+            fs::write(path, "five\n").unwrap();
+            assert_eq!(poll(&mut followed).await, ("five\n".to_string(), vec![Event::Appeared]));
+        });
+    }
+}
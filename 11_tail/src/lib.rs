@@ -1,6 +1,9 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::{arg, Command};
-use std::{io::{BufReader, Read, Write}, fs::File};
+use follow::Followed;
+use std::{io::{BufReader, Read, Write}, fs::File, time::Duration};
+
+mod follow;
 
 #[derive(Debug)]
 pub struct Config {
@@ -8,6 +11,9 @@ pub struct Config {
     lines: Position,
     bytes: Option<Position>,
     quiet: bool,
+    header_format: String,
+    follow: bool,
+    sleep_interval: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -37,8 +43,15 @@ pub fn get_args() -> Result<Config> {
                 .value_parser(parse_position)
                 .conflicts_with_all(["lines"]),
             arg!(-q --quiet "When printing multiple files, don't print the header with file names"),
+            arg!(--header_format <FORMAT> "Header of multiple files, {path} and {n} are replaced")
+                .allow_hyphen_values(true)
+                .default_value(common::header::DEFAULT_FORMAT),
+            arg!(-F --follow "Keep printing added lines, reopen rotated and truncated files"),
+            arg!(-s --sleep_interval <SECONDS> "How often -F checks the files for changes")
+                .value_parser(parse_interval)
+                .default_value("1.0"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     // Construct config
     Ok(Config {
@@ -46,9 +59,17 @@ pub fn get_args() -> Result<Config> {
         lines: matches.remove_one("lines").expect("Default value is provided"),
         bytes: matches.remove_one("bytes"),
         quiet: matches.get_flag("quiet"),
+        header_format: matches.remove_one("header_format").expect("Default value is provided"),
+        follow: matches.get_flag("follow"),
+        sleep_interval: matches.remove_one("sleep_interval").expect("Default value is provided"),
     })
 }
 
+fn parse_interval(text: &str) -> Result<Duration> {
+    let seconds: f64 = text.parse()?;
+    Ok(Duration::try_from_secs_f64(seconds)?)
+}
+
 fn parse_position(text: &str) -> Result<Position> {
     match text.parse::<i64>() {
         Ok(value) if text.starts_with('+') => Ok(Position::FromHead(TryInto::<usize>::try_into(value)?.saturating_sub(1))),
@@ -64,18 +85,70 @@ pub fn run(config: Config) -> Result<()> {
     for (index, file) in config.files.iter().enumerate() {
         if is_header_needed {
             let spacer = if index > 0 { "\n" } else { "" };
-            println!("{spacer}==> {file} <==");
+            let header = common::header::format(&config.header_format, file, index + 1);
+            println!("{spacer}{header}");
         }
 
-        match config.bytes.as_ref() {
-            Some(bytes) => print_tail(file, &bytes, Total::Bytes(count_bytes(&file)?))?,
-            None => print_tail(file, &config.lines, Total::Lines(count_lines(&file)?))?,
+        let printed = match config.bytes.as_ref() {
+            Some(bytes) => count_bytes(file).map(Total::Bytes).map(|total| (bytes, total)),
+            None => count_lines(file).map(Total::Lines).map(|total| (&config.lines, total)),
         }
+        .and_then(|(position, total)| print_tail(file, position, total));
+
+        // Followed file can appear later, so it is not an error for it to be missing now
+        match printed {
+            Err(error) if config.follow => eprintln!("{file}: {error}"),
+            printed => printed?,
+        }
+    }
+
+    if config.follow {
+        follow(&config, is_header_needed)?;
     }
 
     Ok(())
 }
 
+// Runs till the process is killed, the same way as GNU tail -F
+fn follow(config: &Config, is_header_needed: bool) -> Result<()> {
+    common::reader::runtime()?.block_on(follow_all(config, is_header_needed))
+}
+
+async fn follow_all(config: &Config, is_header_needed: bool) -> Result<()> {
+    let mut followed = Vec::with_capacity(config.files.len());
+    for file in &config.files {
+        followed.push(Followed::new(file).await);
+    }
+
+    let mut last_printed = config.files.len() - 1;
+    let mut data = Vec::new();
+    let mut stdout = std::io::stdout();
+
+    loop {
+        tokio::time::sleep(config.sleep_interval).await;
+
+        for (index, file) in followed.iter_mut().enumerate() {
+            data.clear();
+            for event in file.poll(&mut data).await {
+                eprintln!("{}: {event}", file.path());
+            }
+            if data.is_empty() {
+                continue;
+            }
+
+            // Header tells what file the lines are from when the output switches between files
+            if is_header_needed && index != last_printed {
+                let header = common::header::format(&config.header_format, file.path(), index + 1);
+                writeln!(stdout, "\n{header}")?;
+            }
+            last_printed = index;
+
+            stdout.write_all(&data).map_err(|error| anyhow!("stdout: {error}"))?;
+            stdout.flush()?;
+        }
+    }
+}
+
 // To make it faster we need to read from the end of the file and use IoSlice for output
 // Or use File::seek =)
 fn print_tail(file: &str, position: &Position, total: Total) -> Result<()> {
@@ -105,7 +178,7 @@ fn print_tail(file: &str, position: &Position, total: Total) -> Result<()> {
                 skipped += 1;
             }
 
-            return true;
+            true
         })
         .collect::<Vec<u8>>();
 
@@ -1,8 +1,9 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::fs::{self, File};
-use std::io::Read;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::time::Duration;
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -49,7 +50,7 @@ fn dies_bad_bytes() -> TestResult {
     let bad = random_string();
     let expected = format!("invalid value '{}'", &bad);
     Command::cargo_bin(PRG)?
-        .args(&["-c", &bad, EMPTY])
+        .args(["-c", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -63,7 +64,7 @@ fn dies_bad_lines() -> TestResult {
     let bad = random_string();
     let expected = format!("invalid value '{}'", &bad);
     Command::cargo_bin(PRG)?
-        .args(&["-n", &bad, EMPTY])
+        .args(["-n", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -78,7 +79,7 @@ fn dies_bytes_and_lines() -> TestResult {
                used with '--bytes <BYTES>'";
 
     Command::cargo_bin(PRG)?
-        .args(&["-n", "1", "-c", "2"])
+        .args(["-n", "1", "-c", "2"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(msg));
@@ -92,7 +93,7 @@ fn skips_bad_file() -> TestResult {
     let bad = gen_bad_file();
     let expected = ".* [(]os error 2[)]";
     Command::cargo_bin(PRG)?
-        .args(&[ONE, &bad, TWO])
+        .args([ONE, &bad, TWO])
         .assert()
         .stderr(predicate::str::is_match(expected)?);
 
@@ -114,7 +115,7 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
     let stdout = &output.stdout;
 
     dbg!(String::from_utf8_lossy(&expected));
-    dbg!(String::from_utf8_lossy(&stdout));
+    dbg!(String::from_utf8_lossy(stdout));
     assert_eq!(&expected, stdout);
 
     Ok(())
@@ -834,3 +835,52 @@ fn multiple_files_c_plus_3() -> TestResult {
         "tests/expected/all.c+3.out",
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn multiple_files_header_format() -> TestResult {
+    run(
+        &["-n", "1", "--header_format", "--- {n}: {path}", ONE, TWO],
+        "tests/expected/all.n1.header.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_rotated_and_truncated() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("app.log");
+    fs::write(&log, "1\n")?;
+
+    // Changes are made while tail runs, every step waits for several of its polls
+    let changes = {
+        let log = log.clone();
+        let rotated = dir.path().join("app.log.1");
+        std::thread::spawn(move || -> std::io::Result<()> {
+            let step = || std::thread::sleep(Duration::from_millis(500));
+            step();
+            OpenOptions::new().append(true).open(&log)?.write_all(b"2\n")?;
+            step();
+            fs::rename(&log, rotated)?;
+            fs::write(&log, "3 three\n")?;
+            step();
+            fs::write(&log, "4\n")?;
+            Ok(())
+        })
+    };
+
+    let assert = Command::cargo_bin(PRG)?
+        .args(["-F", "-s", "0.05", "-n", "1", log.to_str().unwrap()])
+        .timeout(Duration::from_millis(2500))
+        .assert()
+        .interrupted();
+    changes.join().expect("Changes thread panicked")?;
+
+    let output = assert.get_output();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n2\n3 three\n4\n");
+    assert!(stderr.contains("app.log: has been replaced, following new file"));
+    assert!(stderr.contains("app.log: file truncated"));
+
+    Ok(())
+}
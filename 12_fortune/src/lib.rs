@@ -47,7 +47,7 @@ pub fn get_args() -> Result<Config> {
             arg!(-s --seed <RANDOM_SEED> "Random seed to use for the random number generator")
                 .value_parser(clap::value_parser!(u64)),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     // Construct regex
     let pattern = matches.remove_one::<String>("pattern").map(|text| {
@@ -169,7 +169,7 @@ mod tests {
         let files = res.unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(
-            files.get(0).unwrap().to_string_lossy(),
+            files.first().unwrap().to_string_lossy(),
             "./tests/inputs/jokes"
         );
 
@@ -184,7 +184,7 @@ mod tests {
         // Check number and order of files
         let files = res.unwrap();
         assert_eq!(files.len(), 5);
-        let first = files.get(0).unwrap().display().to_string();
+        let first = files.first().unwrap().display().to_string();
         assert!(first.contains("ascii-art"));
         let last = files.last().unwrap().display().to_string();
         assert!(last.contains("quotes"));
@@ -1,4 +1,5 @@
 use anyhow::{bail, Ok, Result};
+use common::locale::LOCALES;
 
 // Month follows type convention in chrono crate
 #[derive(Debug, Clone, Copy)]
@@ -14,21 +15,6 @@ pub struct Date {
     pub month: Option<Month>,
 }
 
-pub const MONTH_NAMES: [&str; 12] = [
-    "January",
-    "February",
-    "March",
-    "April",
-    "May",
-    "June",
-    "July",
-    "August",
-    "September",
-    "October",
-    "November",
-    "December",
-];
-
 impl Date {
     /// Parse date from string:
     /// - `year`: 2023
@@ -63,11 +49,16 @@ impl Date {
         Ok(Year(year))
     }
 
+    /// Month is parsed from a number or from a month name prefix,
+    /// names are looked up in English first and then in other locales
     pub fn parse_month(month_text: &str) -> Result<Month> {
         let month_text = month_text.to_lowercase();
-        let month_index = MONTH_NAMES
-            .iter()
-            .position(|&m| m.to_lowercase().starts_with(&month_text));
+        let month_index = LOCALES.iter().find_map(|locale| {
+            locale
+                .months
+                .iter()
+                .position(|&m| m.to_lowercase().starts_with(&month_text))
+        });
 
         let month = match month_index {
             None => month_text.parse::<u32>()?,
@@ -3,6 +3,7 @@ use ansi_term::{Style, Color};
 use anyhow::{bail, Ok, Result};
 use chrono::{Datelike, Local, NaiveDate, Weekday};
 use clap::{arg, Command};
+use common::locale::Locale;
 use date::{Date, Month, Year};
 
 const DAY_WIDTH: usize = 2;
@@ -20,6 +21,7 @@ pub struct Config {
     year: Year,
     show_full_year: bool,
     use_color: bool,
+    locale: &'static Locale,
 }
 
 pub fn get_args() -> Result<Config> {
@@ -36,9 +38,10 @@ pub fn get_args() -> Result<Config> {
                 .value_parser(Date::parse_month),
             arg!(-y --show_full_year "Show calendar for the whole year")
                 .conflicts_with("month"),
-            arg!(-C --dont_use_color "Don't use color during output")
+            arg!(-C --dont_use_color "Don't use color during output"),
+            arg!(--locale <LOCALE> "Locale for month and weekday names, e.g. de_DE\nTaken from LC_ALL, LC_TIME or LANG if absent"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     // Parse arguments
     let today = Local::now().naive_local();
@@ -50,6 +53,7 @@ pub fn get_args() -> Result<Config> {
     };
     let explicit_year = date.map(|d| d.year);
     let explicit_month = date.and_then(|d| d.month).or(month);
+    let locale = Locale::resolve(matches.remove_one::<String>("locale").as_deref())?;
 
     // Sanity check, can't specify month twice
     if date.is_some_and(|d| d.month.is_some()) && month.is_some() {
@@ -80,13 +84,14 @@ pub fn get_args() -> Result<Config> {
         show_full_year: matches.get_flag("show_full_year")
             || (explicit_month.is_none() && explicit_year.is_some()),
         use_color: !matches.get_flag("dont_use_color"),
+        locale,
     })
 }
 
 pub fn run(config: Config) -> Result<()> {
     // Rendering a single month annotated with year
     if !config.show_full_year {
-        for line in format_month(config.year.0, config.month.0, Some(config.today), true, config.use_color) {
+        for line in format_month(config.year.0, config.month.0, Some(config.today), true, config.use_color, config.locale) {
             println!("{line}");
         }
         return Ok(());
@@ -97,7 +102,7 @@ pub fn run(config: Config) -> Result<()> {
     println!();
 
     let months = (1..=MONTHS_IN_YEAR)
-        .map(|month| format_month(config.year.0, month, Some(config.today), false, config.use_color))
+        .map(|month| format_month(config.year.0, month, Some(config.today), false, config.use_color, config.locale))
         .collect::<Vec<_>>();
     let months_chunks = months.chunks(YEAR_WIDTH_IN_COLUMNS);
     let last_chunk_index = months_chunks.len() - 1;
@@ -127,6 +132,7 @@ pub fn run(config: Config) -> Result<()> {
 /// - `year` and `month` - identify the month to render
 /// - `add_year_annitation` - if true, year is added to the header
 /// - `highlighted_day` - date that would be highlighted (usually today)
+/// - `locale` - provides month and weekday names
 ///
 /// # Example
 ///
@@ -146,18 +152,17 @@ fn format_month(
     highlighted_day: Option<NaiveDate>,
     do_year_annotation: bool,
     do_colorization: bool,
+    locale: &Locale,
 ) -> Vec<String> {
     let mut result = Vec::with_capacity(WEEK_HEIGHT);
     let mut date = NaiveDate::from_ymd_opt(year, month, 1).expect("Date must be valid");
 
     // Header
-    let month_text = date::MONTH_NAMES
-        .get(date.month0() as usize)
-        .expect("Date must be valid");
+    let month_text = locale.month(date.month0());
     let mut header_text = if do_year_annotation {
         format!("{month_text} {year}")
     } else {
-        format!("{month_text}")
+        month_text.to_string()
     };
     header_text = format!("{header_text:^WEEK_WIDTH$}  ");
     if do_colorization {
@@ -182,8 +187,7 @@ fn format_month(
 
     // Labels
     result.push(process_week(&mut |weekday: Weekday| -> String {
-        let mut day_name = weekday.to_string();
-        day_name.truncate(DAY_WIDTH);
+        let mut day_name = locale.weekday_short(weekday.num_days_from_monday()).to_string();
         if do_colorization {
             day_name = Color::Cyan.normal().paint(day_name).to_string();
         }
@@ -222,6 +226,7 @@ fn format_month(
 mod tests {
     use super::format_month;
     use chrono::NaiveDate;
+    use common::locale::{ENGLISH, GERMAN};
 
     #[test]
     fn test_format_month() {
@@ -235,7 +240,7 @@ mod tests {
             "24 25 26 27 28 29     ",
             "                      ",
         ];
-        assert_eq!(format_month(2020, 2, None, true, false), leap_february);
+        assert_eq!(format_month(2020, 2, None, true, false, &ENGLISH), leap_february);
 
         let may = vec![
             "        May           ",
@@ -247,7 +252,7 @@ mod tests {
             "25 26 27 28 29 30 31  ",
             "                      ",
         ];
-        assert_eq!(format_month(2020, 5, None, false, false), may);
+        assert_eq!(format_month(2020, 5, None, false, false, &ENGLISH), may);
 
         let april_hl = vec![
             "     April 2021       ",
@@ -260,6 +265,18 @@ mod tests {
             "                      ",
         ];
         let today = NaiveDate::from_ymd_opt(2021, 4, 7).unwrap();
-        assert_eq!(format_month(2021, 4, Some(today), true, false), april_hl);
+        assert_eq!(format_month(2021, 4, Some(today), true, false, &ENGLISH), april_hl);
+
+        let march_de = vec![
+            "     März 2020        ",
+            "Mo Di Mi Do Fr Sa So  ",
+            "                   1  ",
+            " 2  3  4  5  6  7  8  ",
+            " 9 10 11 12 13 14 15  ",
+            "16 17 18 19 20 21 22  ",
+            "23 24 25 26 27 28 29  ",
+            "30 31                 ",
+        ];
+        assert_eq!(format_month(2020, 3, None, true, false, &GERMAN), march_de);
     }
 }
@@ -104,6 +104,7 @@ fn month_num() -> TestResult {
 
     for (num, month) in expected {
         Command::cargo_bin(PRG)?
+            .env("LC_ALL", "C")
             .args(["-m", num])
             .assert()
             .success()
@@ -131,6 +132,7 @@ fn partial_month() -> TestResult {
 
     for (arg, month) in expected {
         Command::cargo_bin(PRG)?
+            .env("LC_ALL", "C")
             .args(["-m", arg])
             .assert()
             .success()
@@ -147,6 +149,7 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
     file.read_to_end(&mut expected)?;
 
     let assert = Command::cargo_bin(PRG)?
+        .env("LC_ALL", "C")
         .args(args)
         .assert()
         .success();
@@ -155,7 +158,7 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
     let stdout = &output.stdout;
 
     dbg!(String::from_utf8_lossy(&expected));
-    dbg!(String::from_utf8_lossy(&stdout));
+    dbg!(String::from_utf8_lossy(stdout));
     assert_eq!(&expected, stdout);
 
     Ok(())
@@ -164,7 +167,11 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
 // --------------------------------------------------
 #[test]
 fn default_one_month() -> TestResult {
-    let cmd = Command::cargo_bin(PRG)?.arg("-C").assert().success();
+    let cmd = Command::cargo_bin(PRG)?
+        .env("LC_ALL", "C")
+        .arg("-C")
+        .assert()
+        .success();
     let out = cmd.get_output();
     let stdout = String::from_utf8(out.stdout.clone())?;
     let lines: Vec<_> = stdout.split('\n').collect();
@@ -206,3 +213,34 @@ fn year() -> TestResult {
     assert_eq!(lines.len(), 38);
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn test_2_2020_locale() -> TestResult {
+    run(&["-m", "2", "2020", "-C", "--locale", "de_DE"], "tests/expected/2-2020.de.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_2_2020_locale_env() -> TestResult {
+    let expected = std::fs::read_to_string("tests/expected/2-2020.de.txt")?;
+    Command::cargo_bin(PRG)?
+        .env_remove("LC_ALL")
+        .env("LC_TIME", "de_DE.UTF-8")
+        .args(["-m", "2", "2020", "-C"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_locale() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--locale", "xx_XX"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unsupported locale 'xx_XX'"));
+    Ok(())
+}
@@ -1,25 +1,17 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
 use clap::{arg, Command};
+use common::{listing, locale::Locale};
 use std::{
     fs,
-    os::unix::prelude::MetadataExt,
     path::{Path, PathBuf},
 };
-use tabular::{Row, Table};
-
-const READ: u32 = 0b100;
-const WRITE: u32 = 0b010;
-const EXECUTE: u32 = 0b001;
-const OTHER: u32 = 0;
-const GROUP: u32 = 3;
-const USER: u32 = 6;
 
 #[derive(Debug)]
 pub struct Config {
     paths: Vec<String>,
     use_long_format: bool,
     show_hidden: bool,
+    locale: &'static Locale,
 }
 
 pub fn get_args() -> Result<Config> {
@@ -31,8 +23,9 @@ pub fn get_args() -> Result<Config> {
             arg!([PATHS] ... "Paths to process, current folder is .").default_value("."),
             arg!(-l --long "Use long format that shows each entry per line"),
             arg!(-a --all "Show all file system entries, including hidden ones"),
+            arg!(--locale <LOCALE> "Locale for month names in long format, e.g. de_DE\nTaken from LC_ALL, LC_TIME or LANG if absent"),
         ])
-        .get_matches();
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
 
     Ok(Config {
         paths: matches
@@ -41,6 +34,7 @@ pub fn get_args() -> Result<Config> {
             .collect(),
         use_long_format: matches.get_flag("long"),
         show_hidden: matches.get_flag("all"),
+        locale: Locale::resolve(matches.remove_one::<String>("locale").as_deref())?,
     })
 }
 
@@ -48,7 +42,7 @@ pub fn run(config: Config) -> Result<()> {
     let paths = find_files(&config.paths, config.show_hidden);
 
     if config.use_long_format {
-        println!("{output}", output = format_output(&paths)?);
+        println!("{output}", output = format_output(&paths, config.locale)?);
     } else {
         for path in paths {
             println!("{:8}", path.display());
@@ -118,73 +112,21 @@ fn find_files(paths: &[String], include_hidden: bool) -> Vec<PathBuf> {
     result
 }
 
-fn format_output(paths: &[PathBuf]) -> Result<String> {
-    //         1   2     3     4     5     6     7     8
-    let fmt = "{:<}{:<}  {:>}  {:<}  {:<}  {:>}  {:<}  {:<}";
-    let mut table = Table::new(fmt);
-
-    for path in paths {
-        let meta = path.metadata()?;
-
-        let kind = if meta.is_dir() { "d" } else { "-" };
-        let mode = format_mode(meta.mode());
-        let links = meta.nlink();
-        let user = users::get_user_by_uid(meta.uid())
-            .map(|u| u.name().to_string_lossy().into_owned())
-            .unwrap_or(meta.uid().to_string());
-        let group = users::get_group_by_gid(meta.gid())
-            .map(|g| g.name().to_string_lossy().into_owned())
-            .unwrap_or(meta.gid().to_string());
-        let length = meta.len();
-        let modified = meta.modified()?;
-        let modified: DateTime<Utc> = modified.into();
-        let modified = modified.format("%Y-%b-%d %H:%M");
-
-        table.add_row(
-            Row::new()
-                .with_cell(kind) // 1 - directory or else
-                .with_cell(mode) // 2 - rwx permissions
-                .with_cell(links) // 3 - number of hard links
-                .with_cell(user) // 4 - onwer user name
-                .with_cell(group) // 5 - owner group name
-                .with_cell(length) // 6 - file size in bytes
-                .with_cell(modified) // 7 - last modified date
-                .with_cell(path.display()), // 8 - path
-        );
-    }
-
-    Ok(format!("{table}"))
-}
-
-fn format_mode(mode: u32) -> String {
-    let render = |part: u32| -> String {
-        let mut result = String::with_capacity(3);
-
-        let print = |mask: u32, char: char| -> char {
-            if part & mask != 0 {
-                char
-            } else {
-                '-'
-            }
-        };
-
-        result.push(print(READ, 'r'));
-        result.push(print(WRITE, 'w'));
-        result.push(print(EXECUTE, 'x'));
-        result
-    };
+fn format_output(paths: &[PathBuf], locale: &Locale) -> Result<String> {
+    let metas = paths
+        .iter()
+        .map(|path| path.metadata())
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut result = String::with_capacity(9);
-    result.push_str(&render(mode >> USER));
-    result.push_str(&render(mode >> GROUP));
-    result.push_str(&render(mode >> OTHER));
-    result
+    let entries = paths.iter().map(PathBuf::as_path).zip(&metas);
+    Ok(listing::format_long(entries, locale)?)
 }
 
 // --------------------------------------------------
 #[cfg(test)]
 mod test {
-    use super::{find_files, format_mode, format_output};
+    use super::{find_files, format_output};
+    use common::locale::ENGLISH;
     use std::path::PathBuf;
 
     #[test]
@@ -280,7 +222,7 @@ mod test {
         let bustle_path = "tests/inputs/bustle.txt";
         let bustle = PathBuf::from(bustle_path);
 
-        let res = format_output(&[bustle]);
+        let res = format_output(&[bustle], &ENGLISH);
         assert!(res.is_ok());
 
         let out = res.unwrap();
@@ -293,10 +235,13 @@ mod test {
 
     #[test]
     fn test_format_output_two() {
-        let res = format_output(&[
-            PathBuf::from("tests/inputs/dir"),
-            PathBuf::from("tests/inputs/empty.txt"),
-        ]);
+        let res = format_output(
+            &[
+                PathBuf::from("tests/inputs/dir"),
+                PathBuf::from("tests/inputs/empty.txt"),
+            ],
+            &ENGLISH,
+        );
         assert!(res.is_ok());
 
         let out = res.unwrap();
@@ -315,10 +260,4 @@ mod test {
         let dir_line = lines.remove(0);
         long_match(dir_line, "tests/inputs/dir", "drwxr-xr-x", None);
     }
-
-    #[test]
-    fn test_format_mode() {
-        assert_eq!(format_mode(0o755), "rwxr-xr-x");
-        assert_eq!(format_mode(0o421), "r---w---x");
-    }
 }
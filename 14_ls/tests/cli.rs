@@ -1,4 +1,6 @@
 use assert_cmd::Command;
+use chrono::{DateTime, Datelike, Utc};
+use common::locale::GERMAN;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
 use std::{error::Error, fs};
@@ -246,3 +248,19 @@ fn dir2_long_all() -> TestResult {
         ],
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn bustle_long_locale() -> TestResult {
+    let modified: DateTime<Utc> = fs::metadata(BUSTLE)?.modified()?.into();
+    let month = GERMAN.month_short(modified.month0());
+
+    let cmd = Command::cargo_bin(PRG)?
+        .args(["--long", "--locale", "de", BUSTLE])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
+    let parts: Vec<_> = stdout.split_whitespace().collect();
+    assert_eq!(parts.get(5).unwrap(), &format!("{}-{month}-{:02}", modified.year(), modified.day()));
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use anyhow::{bail, Result};
+use clap::{arg, Command};
+use common::reader::open;
+use std::io::{BufRead, BufWriter, Write};
+
+const DEFAULT_TAB_SIZE: usize = 8;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    tabs: TabStops,
+    convert_all: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TabStops {
+    Every(usize),          // -t 4 means stops at columns 4, 8, 12, ...
+    Positions(Vec<usize>), // -t 4,10 means stops at columns 4 and 10 only
+}
+
+impl TabStops {
+    fn is_stop(&self, column: usize) -> bool {
+        match self {
+            TabStops::Every(size) => column > 0 && column.is_multiple_of(*size),
+            TabStops::Positions(stops) => stops.binary_search(&column).is_ok(),
+        }
+    }
+
+    // After the last explicit stop a tab is rendered as a single space
+    fn next_stop(&self, column: usize) -> usize {
+        match self {
+            TabStops::Every(size) => (column / size + 1) * size,
+            TabStops::Positions(stops) => stops
+                .iter()
+                .copied()
+                .find(|&stop| stop > column)
+                .unwrap_or(column + 1),
+        }
+    }
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("unexpand")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Converts runs of spaces into tabs, the inverse of expand")
+        .args([
+            arg!([FILES] ... "Files to process, stdin is -").default_value("-"),
+            arg!(-a --all "Convert all blanks, instead of just the leading ones"),
+            arg!(--first_only "Convert only the leading blanks, overrides -a and -t"),
+            arg!(-t --tabs <TABS> "Tab size or comma separated list of tab stops, implies -a")
+                .value_parser(parse_tab_stops),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Tab stops imply conversion of all blanks like in GNU unexpand
+    let tabs: Option<TabStops> = matches.remove_one("tabs");
+    let convert_all = (matches.get_flag("all") || tabs.is_some()) && !matches.get_flag("first_only");
+
+    // Construct config
+    Ok(Config {
+        files: matches
+            .remove_many("FILES")
+            .expect("No file paths provided")
+            .collect(),
+        tabs: tabs.unwrap_or(TabStops::Every(DEFAULT_TAB_SIZE)),
+        convert_all,
+    })
+}
+
+fn parse_tab_stops(text: &str) -> Result<TabStops> {
+    let stops = text
+        .split(',')
+        .map(|stop| match stop.trim().parse::<usize>() {
+            Ok(0) => bail!("Tab stop can't be zero in '{text}'"),
+            Ok(value) => Ok(value),
+            Err(error) => bail!("Invalid tab stop '{stop}' - {error}"),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if stops.windows(2).any(|pair| pair[0] >= pair[1]) {
+        bail!("Tab stops must be ascending in '{text}'");
+    }
+
+    match stops.as_slice() {
+        [size] => Ok(TabStops::Every(*size)),
+        _ => Ok(TabStops::Positions(stops)),
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+
+    for path in &config.files {
+        match open(path) {
+            Ok(reader) => process_file(reader, &mut writer, &config)?,
+            Err(error) => eprintln!("Can't open file {error}"),
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn process_file(mut reader: impl BufRead, writer: &mut impl Write, config: &Config) -> Result<()> {
+    let mut line = String::new();
+
+    loop {
+        // Read line together with line endings
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        writer.write_all(unexpand_line(&line, &config.tabs, config.convert_all).as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn unexpand_line(line: &str, tabs: &TabStops, convert_all: bool) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut pending = String::new();
+    let mut column = 0;
+    let mut leading = true;
+
+    let is_blank = |char: char| char == ' ' || char == '\t';
+    let mut chars = line.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if is_blank(char) && (leading || convert_all) {
+            // Blanks are buffered until we know if they reach a tab stop
+            column = if char == '\t' { tabs.next_stop(column) } else { column + 1 };
+            pending.push(char);
+
+            if tabs.is_stop(column) {
+                // A single space before a tab stop is not worth a tab
+                // unless more blanks follow it, that's how GNU unexpand does it
+                if pending == " " && !chars.peek().is_some_and(|&next| is_blank(next)) {
+                    result.push(' ');
+                } else {
+                    result.push('\t');
+                }
+                pending.clear();
+            }
+            continue;
+        }
+
+        // Blanks that didn't reach a tab stop are kept as is
+        result.push_str(&pending);
+        pending.clear();
+        result.push(char);
+
+        column = if char == '\t' { tabs.next_stop(column) } else { column + 1 };
+        leading &= is_blank(char);
+    }
+
+    result.push_str(&pending);
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_tab_stops, unexpand_line, TabStops::*};
+
+    #[test]
+    fn test_parse_tab_stops() {
+        assert_eq!(parse_tab_stops("4").unwrap(), Every(4));
+        assert_eq!(parse_tab_stops("4,10, 12").unwrap(), Positions(vec![4, 10, 12]));
+
+        let res = parse_tab_stops("0");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Tab stop can't be zero in '0'");
+
+        let res = parse_tab_stops("8,4");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Tab stops must be ascending in '8,4'");
+
+        let res = parse_tab_stops("4,a");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid tab stop 'a' - invalid digit found in string");
+    }
+
+    #[test]
+    fn test_unexpand_leading() {
+        let tabs = Every(4);
+        assert_eq!(unexpand_line("        a    b\n", &tabs, false), "\t\ta    b\n");
+        assert_eq!(unexpand_line("      a\n", &tabs, false), "\t  a\n");
+        assert_eq!(unexpand_line("  \t a\n", &tabs, false), "\t a\n");
+        assert_eq!(unexpand_line("a       b\n", &tabs, false), "a       b\n");
+        assert_eq!(unexpand_line("", &tabs, false), "");
+    }
+
+    #[test]
+    fn test_unexpand_all() {
+        let tabs = Every(4);
+        assert_eq!(unexpand_line("    a   b\n", &tabs, true), "\ta\tb\n");
+        assert_eq!(unexpand_line("abc d\n", &tabs, true), "abc d\n");
+        assert_eq!(unexpand_line("ab  c  \n", &tabs, true), "ab\tc  \n");
+        assert_eq!(unexpand_line("ab\t  c\n", &tabs, true), "ab\t  c\n");
+        assert_eq!(unexpand_line("abc     d\n", &tabs, true), "abc\t\td\n");
+    }
+
+    #[test]
+    fn test_unexpand_positions() {
+        let tabs = Positions(vec![2, 6]);
+        assert_eq!(unexpand_line("  a   b       c\n", &tabs, true), "\ta\tb       c\n");
+    }
+}
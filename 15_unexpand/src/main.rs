@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = unexpand::get_args().and_then(unexpand::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
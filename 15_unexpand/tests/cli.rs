@@ -0,0 +1,129 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "unexpand";
+const CODE: &str = "tests/inputs/code.txt";
+const TABLE: &str = "tests/inputs/table.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, EMPTY])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_tabs() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-t", "8,4", CODE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Tab stops must be ascending in '8,4'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn code() -> TestResult {
+    run(&[CODE], "tests/expected/code.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn code_all() -> TestResult {
+    run(&["-a", CODE], "tests/expected/code.txt.a.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn code_tabs4() -> TestResult {
+    run(&["-t", "4", CODE], "tests/expected/code.txt.t4.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table() -> TestResult {
+    run(&[TABLE], "tests/expected/table.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table_all() -> TestResult {
+    run(&["--all", TABLE], "tests/expected/table.txt.a.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table_tabs4() -> TestResult {
+    run(&["--tabs", "4", TABLE], "tests/expected/table.txt.t4.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table_first_only() -> TestResult {
+    run(&["-a", "--first_only", TABLE], "tests/expected/table.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table_stdin() -> TestResult {
+    let input = fs::read_to_string(TABLE)?;
+    let expected = fs::read_to_string("tests/expected/table.txt.a.out")?;
+    Command::cargo_bin(PRG)?
+        .arg("-a")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    run(&[CODE, TABLE], "tests/expected/all.out")
+}
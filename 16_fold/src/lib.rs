@@ -0,0 +1,227 @@
+use anyhow::{bail, Result};
+use clap::{arg, Command};
+use common::reader::open;
+use std::io::{BufRead, BufWriter, Write};
+use unicode_width::UnicodeWidthChar;
+
+const TAB_SIZE: usize = 8;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    width: usize,
+    count_bytes: bool,
+    break_at_spaces: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("fold")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Wraps input lines to fit into the specified width")
+        .args([
+            arg!([FILES] ... "Files to process, stdin is -").default_value("-"),
+            arg!(-w --width <WIDTH> "Maximum line width in columns")
+                .value_parser(parse_width)
+                .default_value("80"),
+            arg!(-b --bytes "Count bytes rather than display columns"),
+            arg!(-s --spaces "Break lines at the last blank that fits into the width"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Construct config
+    Ok(Config {
+        files: matches
+            .remove_many("FILES")
+            .expect("No file paths provided")
+            .collect(),
+        width: matches.remove_one("width").expect("Default value is provided"),
+        count_bytes: matches.get_flag("bytes"),
+        break_at_spaces: matches.get_flag("spaces"),
+    })
+}
+
+fn parse_width(text: &str) -> Result<usize> {
+    match text.parse::<usize>() {
+        Ok(0) => bail!("Width can't be zero"),
+        Ok(width) => Ok(width),
+        Err(error) => bail!("Invalid width '{text}' - {error}"),
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+
+    for path in &config.files {
+        match open(path) {
+            Ok(reader) => process_file(reader, &mut writer, &config)?,
+            Err(error) => eprintln!("Can't open file {error}"),
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn process_file(mut reader: impl BufRead, writer: &mut impl Write, config: &Config) -> Result<()> {
+    let mut line = Vec::new();
+
+    loop {
+        // Read line as raw bytes, byte mode must not depend on UTF-8 validity
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        // Line ending is never counted towards the width
+        let ending = if line.ends_with(b"\n") { &b"\n"[..] } else { &[] };
+        let content = &line[..line.len() - ending.len()];
+
+        if config.count_bytes {
+            writer.write_all(&fold_bytes(content, config.width, config.break_at_spaces))?;
+        } else {
+            writer.write_all(&fold_columns(content, config.width, config.break_at_spaces))?;
+        }
+        writer.write_all(ending)?;
+    }
+
+    Ok(())
+}
+
+// Characters are folded as their byte slices, so bytes that are not UTF-8 are written
+// back as they were. Each of them takes a column, the same as in GNU fold.
+fn fold_columns(line: &[u8], width: usize, break_at_spaces: bool) -> Vec<u8> {
+    let mut units: Vec<&[u8]> = Vec::with_capacity(line.len());
+    for chunk in line.utf8_chunks() {
+        let valid = chunk.valid();
+        let chars = valid.char_indices().map(|(i, char)| &valid.as_bytes()[i..i + char.len_utf8()]);
+        units.extend(chars);
+        units.extend(chunk.invalid().chunks(1));
+    }
+
+    let advance = |column: usize, unit: &[u8]| match std::str::from_utf8(unit) {
+        Ok("\t") => (column / TAB_SIZE + 1) * TAB_SIZE,
+        Ok("\x08") => column.saturating_sub(1),
+        Ok("\r") => 0,
+        Ok(char) => column + char.chars().map(|char| char.width().unwrap_or(0)).sum::<usize>(),
+        Err(_) => column + 1,
+    };
+
+    let is_blank = |unit: &&[u8]| matches!(*unit, b" " | b"\t");
+    fold(&units, width, break_at_spaces, &b"\n"[..], is_blank, advance).concat()
+}
+
+fn fold_bytes(line: &[u8], width: usize, break_at_spaces: bool) -> Vec<u8> {
+    fold(line, width, break_at_spaces, b'\n', |&b| b == b' ' || b == b'\t', |column, _| column + 1)
+}
+
+/// Inserts line breaks into `units` so every output line fits into `width`
+///
+/// - `is_blank` - tells where lines can be broken when `break_at_spaces` is set,
+///   the blank stays at the end of the broken line like in GNU fold
+/// - `advance` - computes the column after a unit is printed at the given column
+fn fold<T: Copy>(
+    units: &[T],
+    width: usize,
+    break_at_spaces: bool,
+    newline: T,
+    is_blank: impl Fn(&T) -> bool,
+    advance: impl Fn(usize, T) -> usize,
+) -> Vec<T> {
+    let mut result = Vec::with_capacity(units.len() + units.len() / width + 1);
+    let mut line_start = 0;
+    let mut column = 0;
+
+    for &unit in units {
+        let mut next = advance(column, unit);
+
+        // A unit wider than the width still needs to be printed somewhere,
+        // so we never break an empty line
+        while next > width && result.len() > line_start {
+            let split = if break_at_spaces {
+                result[line_start..]
+                    .iter()
+                    .rposition(&is_blank)
+                    .map_or(result.len(), |position| line_start + position + 1)
+            } else {
+                result.len()
+            };
+
+            result.insert(split, newline);
+            line_start = split + 1;
+            column = result[line_start..].iter().fold(0, |column, &unit| advance(column, unit));
+            next = advance(column, unit);
+        }
+
+        result.push(unit);
+        column = next;
+    }
+
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{fold_bytes, fold_columns, parse_width};
+
+    fn columns(line: &str, width: usize, break_at_spaces: bool) -> String {
+        String::from_utf8(fold_columns(line.as_bytes(), width, break_at_spaces)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_width() {
+        assert_eq!(parse_width("12").unwrap(), 12);
+
+        let res = parse_width("0");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Width can't be zero");
+
+        let res = parse_width("-1");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid width '-1' - invalid digit found in string");
+    }
+
+    #[test]
+    fn test_fold_columns() {
+        assert_eq!(columns("", 5, false), "");
+        assert_eq!(columns("abcde", 5, false), "abcde");
+        assert_eq!(columns("abcdefghij12", 5, false), "abcde\nfghij\n12");
+        assert_eq!(columns("a\tb", 5, false), "a\n\t\nb");
+        assert_eq!(columns("abc\rabcdef", 5, false), "abc\rabcde\nf");
+    }
+
+    #[test]
+    fn test_fold_spaces() {
+        assert_eq!(columns("the quick brown fox", 10, true), "the quick \nbrown fox");
+        assert_eq!(columns("the quick brown fox", 10, false), "the quick \nbrown fox");
+        assert_eq!(columns("a bcdefghijkl", 5, true), "a \nbcdef\nghijk\nl");
+        assert_eq!(columns("abcdefgh", 4, true), "abcd\nefgh");
+    }
+
+    #[test]
+    fn test_fold_wide() {
+        // Each of these characters takes two columns in a terminal
+        assert_eq!(columns("日本語です", 4, false), "日本\n語で\nす");
+        assert_eq!(columns("日本語", 5, false), "日本\n語");
+        assert_eq!(columns("日本", 1, false), "日\n本");
+
+        // Combining accent doesn't take any space
+        assert_eq!(columns("e\u{301}e\u{301}e\u{301}", 2, false), "e\u{301}e\u{301}\ne\u{301}");
+    }
+
+    #[test]
+    fn test_fold_invalid() {
+        // Bytes that are not UTF-8 are kept as is and take a column each
+        assert_eq!(fold_columns(b"\xffab\xfe\xfd", 2, false), b"\xffa\nb\xfe\n\xfd");
+        assert_eq!(fold_columns(b"\xe6\x97x", 5, false), b"\xe6\x97x");
+    }
+
+    #[test]
+    fn test_fold_bytes() {
+        assert_eq!(fold_bytes(b"abcdef", 4, false), b"abcd\nef");
+        assert_eq!(fold_bytes("日本".as_bytes(), 4, false), b"\xe6\x97\xa5\xe6\n\x9c\xac");
+        assert_eq!(fold_bytes(b"ab cd ef", 4, true), b"ab \ncd \nef");
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = fold::get_args().and_then(fold::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
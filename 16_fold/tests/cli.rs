@@ -0,0 +1,128 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "fold";
+const FOX: &str = "tests/inputs/fox.txt";
+const WIDE: &str = "tests/inputs/wide.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, EMPTY])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_width() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "0", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Width can't be zero"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(input_file: &str, args: &[&str], expected_file: &str) -> TestResult {
+    let input = fs::read_to_string(input_file)?;
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_spaces() -> TestResult {
+    run(&["-s", "-w", "20", EMPTY], "tests/expected/empty.txt.s20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> TestResult {
+    run(&[FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_stdin() -> TestResult {
+    run_stdin(FOX, &["-w", "20"], "tests/expected/fox.txt.w20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_width() -> TestResult {
+    run(&["-w", "20", FOX], "tests/expected/fox.txt.w20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_spaces() -> TestResult {
+    run(&["-s", "-w", "20", FOX], "tests/expected/fox.txt.s20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_bytes() -> TestResult {
+    run(&["--bytes", "--width", "20", FOX], "tests/expected/fox.txt.b20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn wide() -> TestResult {
+    run(&["-w", "20", WIDE], "tests/expected/wide.txt.w20.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    run(&["-w", "10", FOX, EMPTY, FOX], "tests/expected/all.w10.out")
+}
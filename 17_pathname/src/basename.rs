@@ -0,0 +1,59 @@
+use crate::{base_name, Style};
+use anyhow::{bail, Result};
+use clap::{arg, Command};
+
+#[derive(Debug)]
+pub struct Config {
+    names: Vec<String>,
+    suffix: Option<String>,
+    zero: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("basename")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Prints path without leading directories and an optional suffix")
+        .args([
+            arg!(<NAMES> ... "Paths to process, without -a the second one is the suffix"),
+            arg!(-a --multiple "Treat every argument as a path"),
+            arg!(-s --suffix <SUFFIX> "Suffix to remove, implies -a"),
+            arg!(-z --zero "End each output line with NUL, not newline"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let mut names: Vec<String> = matches
+        .remove_many("NAMES")
+        .expect("At least one path is required")
+        .collect();
+    let mut suffix: Option<String> = matches.remove_one("suffix");
+
+    // Classic form is `basename NAME [SUFFIX]`
+    if !matches.get_flag("multiple") && suffix.is_none() {
+        if names.len() > 2 {
+            bail!("Extra operand '{}', use -a to process multiple paths", names[2]);
+        }
+        if names.len() == 2 {
+            suffix = names.pop();
+        }
+    }
+
+    // Construct config
+    Ok(Config {
+        names,
+        suffix,
+        zero: matches.get_flag("zero"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let ending = if config.zero { '\0' } else { '\n' };
+    let style = Style::native();
+
+    for name in &config.names {
+        print!("{}{ending}", base_name(name, config.suffix.as_deref(), style));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = pathname::basename::get_args().and_then(pathname::basename::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = pathname::dirname::get_args().and_then(pathname::dirname::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{dir_name, Style};
+use anyhow::Result;
+use clap::{arg, Command};
+
+#[derive(Debug)]
+pub struct Config {
+    names: Vec<String>,
+    zero: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("dirname")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Prints path without its last component")
+        .args([
+            arg!(<NAMES> ... "Paths to process"),
+            arg!(-z --zero "End each output line with NUL, not newline"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Construct config
+    Ok(Config {
+        names: matches
+            .remove_many("NAMES")
+            .expect("At least one path is required")
+            .collect(),
+        zero: matches.get_flag("zero"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let ending = if config.zero { '\0' } else { '\n' };
+    let style = Style::native();
+
+    for name in &config.names {
+        print!("{}{ending}", dir_name(name, style));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+pub mod basename;
+pub mod dirname;
+
+/// Path conventions used to split a path into components
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Unix,    // only / separates components, \ is a regular file name character
+    Windows, // both / and \ separate components, C: drive prefix is kept as is
+}
+
+impl Style {
+    /// Style of the platform the tool was built for
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            Style::Windows
+        } else {
+            Style::Unix
+        }
+    }
+
+    fn is_separator(self, char: char) -> bool {
+        match self {
+            Style::Unix => char == '/',
+            Style::Windows => char == '/' || char == '\\',
+        }
+    }
+
+    // Splits C:\foo into C: and \foo, the prefix is never a part of a file name
+    fn split_prefix(self, path: &str) -> (&str, &str) {
+        let bytes = path.as_bytes();
+        let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+
+        match self {
+            Style::Windows if has_drive => path.split_at(2),
+            _ => ("", path),
+        }
+    }
+}
+
+/// Last path component without trailing separators, root stays root
+///
+/// `suffix` is removed from the result unless it is the whole name.
+pub fn base_name<'a>(path: &'a str, suffix: Option<&str>, style: Style) -> &'a str {
+    let (prefix, rest) = style.split_prefix(path);
+    let trimmed = rest.trim_end_matches(|c| style.is_separator(c));
+
+    // Path consists of the root only
+    if trimmed.is_empty() {
+        return &path[..prefix.len() + rest.len().min(1)];
+    }
+
+    let name = match trimmed.rfind(|c| style.is_separator(c)) {
+        Some(index) => &trimmed[index + 1..],
+        None => trimmed,
+    };
+
+    match suffix {
+        Some(suffix) if name != suffix => name.strip_suffix(suffix).unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Path without its last component and trailing separators,
+/// `.` when there is no directory part
+pub fn dir_name(path: &str, style: Style) -> &str {
+    let (prefix, rest) = style.split_prefix(path);
+    let trimmed = rest.trim_end_matches(|c| style.is_separator(c));
+    let root = &path[..prefix.len() + rest.len().min(1)];
+
+    // Path consists of the root only, it is its own parent
+    if trimmed.is_empty() {
+        return if root.is_empty() { "." } else { root };
+    }
+
+    let Some(index) = trimmed.rfind(|c| style.is_separator(c)) else {
+        return if prefix.is_empty() { "." } else { prefix };
+    };
+
+    match trimmed[..index].trim_end_matches(|c| style.is_separator(c)) {
+        "" => &path[..prefix.len() + 1],
+        parent => &path[..prefix.len() + parent.len()],
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{base_name, dir_name, Style::*};
+
+    #[test]
+    fn test_base_name() {
+        assert_eq!(base_name("/usr/bin/sort", None, Unix), "sort");
+        assert_eq!(base_name("include/stdio.h", Some(".h"), Unix), "stdio");
+        assert_eq!(base_name("dir/", None, Unix), "dir");
+        assert_eq!(base_name("dir//", None, Unix), "dir");
+        assert_eq!(base_name("a.txt", None, Unix), "a.txt");
+        assert_eq!(base_name("/", None, Unix), "/");
+        assert_eq!(base_name("///", None, Unix), "/");
+        assert_eq!(base_name("", None, Unix), "");
+        assert_eq!(base_name(".h", Some(".h"), Unix), ".h");
+        assert_eq!(base_name("dir\\file", None, Unix), "dir\\file");
+    }
+
+    #[test]
+    fn test_base_name_windows() {
+        assert_eq!(base_name("C:\\Users\\fox\\a.txt", Some(".txt"), Windows), "a");
+        assert_eq!(base_name("C:/Users/fox/", None, Windows), "fox");
+        assert_eq!(base_name("dir\\file", None, Windows), "file");
+        assert_eq!(base_name("C:\\", None, Windows), "C:\\");
+        assert_eq!(base_name("C:file", None, Windows), "file");
+        assert_eq!(base_name("C:", None, Windows), "C:");
+    }
+
+    #[test]
+    fn test_dir_name() {
+        assert_eq!(dir_name("/usr/bin/", Unix), "/usr");
+        assert_eq!(dir_name("dir1/str", Unix), "dir1");
+        assert_eq!(dir_name("a//b//", Unix), "a");
+        assert_eq!(dir_name("stdio.h", Unix), ".");
+        assert_eq!(dir_name("/usr", Unix), "/");
+        assert_eq!(dir_name("//usr", Unix), "/");
+        assert_eq!(dir_name("/", Unix), "/");
+        assert_eq!(dir_name("", Unix), ".");
+        assert_eq!(dir_name("dir\\file", Unix), ".");
+    }
+
+    #[test]
+    fn test_dir_name_windows() {
+        assert_eq!(dir_name("C:\\Users\\fox\\a.txt", Windows), "C:\\Users\\fox");
+        assert_eq!(dir_name("C:\\Users", Windows), "C:\\");
+        assert_eq!(dir_name("C:/Users\\", Windows), "C:/");
+        assert_eq!(dir_name("C:\\", Windows), "C:\\");
+        assert_eq!(dir_name("C:file", Windows), "C:");
+        assert_eq!(dir_name("dir\\file", Windows), "dir");
+        assert_eq!(dir_name("\\file", Windows), "\\");
+    }
+}
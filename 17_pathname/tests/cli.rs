@@ -0,0 +1,100 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const BASENAME: &str = "basename";
+const DIRNAME: &str = "dirname";
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> TestResult {
+    for prg in [BASENAME, DIRNAME] {
+        Command::cargo_bin(prg)?
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Usage:"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_extra_operand() -> TestResult {
+    Command::cargo_bin(BASENAME)?
+        .args(["a", "b", "c"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Extra operand 'c'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(prg: &str, args: &[&str], expected: &str) -> TestResult {
+    Command::cargo_bin(prg)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_one() -> TestResult {
+    run(BASENAME, &["/usr/bin/sort"], "sort\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_trailing_slash() -> TestResult {
+    run(BASENAME, &["/usr/bin/"], "bin\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_root() -> TestResult {
+    run(BASENAME, &["//"], "/\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_suffix_operand() -> TestResult {
+    run(BASENAME, &["include/stdio.h", ".h"], "stdio\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_multiple() -> TestResult {
+    run(BASENAME, &["-a", "any/str1", "any/str2"], "str1\nstr2\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_suffix() -> TestResult {
+    run(BASENAME, &["-s", ".h", "a/b.h", "c.h", ".h"], "b\nc\n.h\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn basename_zero() -> TestResult {
+    run(BASENAME, &["-z", "-a", "a/b", "c/d/"], "b\0d\0")
+}
+
+// --------------------------------------------------
+#[test]
+fn dirname_one() -> TestResult {
+    run(DIRNAME, &["/usr/bin/"], "/usr\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn dirname_multiple() -> TestResult {
+    run(DIRNAME, &["dir1/str", "dir2/str", "stdio.h", "/"], "dir1\ndir2\n.\n/\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn dirname_zero() -> TestResult {
+    run(DIRNAME, &["--zero", "a//b//", "/usr"], "a\0/\0")
+}
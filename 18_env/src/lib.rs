@@ -0,0 +1,164 @@
+use anyhow::{bail, Result};
+use clap::{arg, ArgAction, Command};
+use common::process::{exit_code, spawn_error_code};
+use std::ffi::OsString;
+use std::io::{BufWriter, Write};
+
+#[derive(Debug)]
+pub struct Config {
+    ignore_environment: bool,
+    unset: Vec<String>,
+    assignments: Vec<(String, String)>,
+    command: Vec<String>,
+    null: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    // @argfile expansion is not used since arguments of the command must be passed as is
+    let mut matches = Command::new("env")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Prints the environment or runs a command in a modified environment")
+        .args([
+            arg!([ARGS] ... "NAME=VALUE assignments followed by the command to run")
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true),
+            arg!(-i --ignore_environment "Start with an empty environment"),
+            arg!(-u --unset <NAME> "Remove variable from the environment")
+                .action(ArgAction::Append),
+            arg!(--null "End each output line with NUL, not newline").short('0'),
+        ])
+        .get_matches();
+
+    // Assignments go first, everything after them is the command
+    let args: Vec<String> = matches.remove_many("ARGS").unwrap_or_default().collect();
+    let split = args.iter().position(|arg| parse_assignment(arg).is_none()).unwrap_or(args.len());
+    let assignments = args[..split].iter().filter_map(|arg| parse_assignment(arg)).collect();
+    let command = args[split..].to_vec();
+
+    let null = matches.get_flag("null");
+    if null && !command.is_empty() {
+        bail!("Can't use --null together with a command");
+    }
+
+    // Construct config
+    Ok(Config {
+        ignore_environment: matches.get_flag("ignore_environment"),
+        unset: matches.remove_many("unset").unwrap_or_default().collect(),
+        assignments,
+        command,
+        null,
+    })
+}
+
+// NAME=VALUE where the name is not empty, value can be
+fn parse_assignment(arg: &str) -> Option<(String, String)> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Some((name.to_string(), value.to_string())),
+        _ => None,
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    match config.command.split_first() {
+        Some((program, args)) => run_command(program, args, &config),
+        None => print_environment(&config),
+    }
+}
+
+fn print_environment(config: &Config) -> Result<()> {
+    let ending: &[u8] = if config.null { b"\0" } else { b"\n" };
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+
+    for (name, value) in modified_environment(config) {
+        writer.write_all(name.as_encoded_bytes())?;
+        writer.write_all(b"=")?;
+        writer.write_all(value.as_encoded_bytes())?;
+        writer.write_all(ending)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+// Keeps the order of the inherited variables, new ones go to the end
+fn modified_environment(config: &Config) -> Vec<(OsString, OsString)> {
+    let mut variables: Vec<(OsString, OsString)> = if config.ignore_environment {
+        Vec::new()
+    } else {
+        std::env::vars_os().collect()
+    };
+
+    variables.retain(|(name, _)| !config.unset.iter().any(|unset| name == unset.as_str()));
+
+    for (name, value) in &config.assignments {
+        match variables.iter_mut().find(|(existing, _)| existing == name.as_str()) {
+            Some((_, existing)) => *existing = value.into(),
+            None => variables.push((name.into(), value.into())),
+        }
+    }
+
+    variables
+}
+
+fn run_command(program: &str, args: &[String], config: &Config) -> Result<()> {
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+
+    if config.ignore_environment {
+        command.env_clear();
+    }
+    for name in &config.unset {
+        command.env_remove(name);
+    }
+    command.envs(config.assignments.iter().map(|(name, value)| (name, value)));
+
+    let status = match command.status() {
+        Ok(status) => status,
+        Err(error) => {
+            eprintln!("Can't run '{program}', error {error}");
+            std::process::exit(spawn_error_code(&error));
+        }
+    };
+
+    if !status.success() {
+        std::process::exit(exit_code(status));
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{modified_environment, parse_assignment, Config};
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(parse_assignment("A=1"), Some(("A".to_string(), "1".to_string())));
+        assert_eq!(parse_assignment("A="), Some(("A".to_string(), String::new())));
+        assert_eq!(parse_assignment("A=b=c"), Some(("A".to_string(), "b=c".to_string())));
+        assert_eq!(parse_assignment("=1"), None);
+        assert_eq!(parse_assignment("ls"), None);
+    }
+
+    #[test]
+    fn test_modified_environment() {
+        let config = Config {
+            ignore_environment: true,
+            unset: vec!["B".to_string()],
+            assignments: vec![
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "2".to_string()),
+                ("A".to_string(), "3".to_string()),
+            ],
+            command: Vec::new(),
+            null: false,
+        };
+
+        // Unset is applied before assignments, later assignment wins
+        let variables = modified_environment(&config);
+        assert_eq!(variables, [("A".into(), "3".into()), ("B".into(), "2".into())]);
+    }
+}
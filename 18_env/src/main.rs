@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = env::get_args().and_then(env::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
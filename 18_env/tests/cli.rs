@@ -0,0 +1,116 @@
+use assert_cmd::{cargo::cargo_bin, Command};
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "env";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn prints_environment() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("ENV_TEST_FOX", "quick brown")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ENV_TEST_FOX=quick brown\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsets_variable() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("ENV_TEST_FOX", "quick brown")
+        .args(["-u", "ENV_TEST_FOX"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ENV_TEST_FOX").not());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn ignore_environment() -> TestResult {
+    run(&["-i", "A=1", "B=two words"], "A=1\nB=two words\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn ignore_environment_override() -> TestResult {
+    run(&["-i", "A=1", "A=2", "C="], "A=2\nC=\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn null_terminated() -> TestResult {
+    run(&["-i", "-0", "A=1", "B=2"], "A=1\0B=2\0")
+}
+
+// --------------------------------------------------
+#[test]
+fn runs_command() -> TestResult {
+    // The command is env itself, so it prints the environment it got
+    let env = cargo_bin(PRG);
+    let env = env.to_str().expect("Test binary path is UTF-8");
+    run(&["-i", "A=1", env, "B=2"], "A=1\nB=2\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn runs_command_unset() -> TestResult {
+    let env = cargo_bin(PRG);
+    Command::cargo_bin(PRG)?
+        .env("ENV_TEST_FOX", "quick brown")
+        .arg("-u")
+        .arg("ENV_TEST_FOX")
+        .arg(env)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ENV_TEST_FOX").not());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn passes_exit_code() -> TestResult {
+    // Nested env fails on usage error and its exit code is passed through
+    let env = cargo_bin(PRG);
+    Command::cargo_bin(PRG)?
+        .arg(env)
+        .arg("-u")
+        .assert()
+        .code(2);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_command_not_found() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["A=1", "no-such-command-for-env-test"])
+        .assert()
+        .code(127)
+        .stderr(predicate::str::contains("Can't run 'no-such-command-for-env-test'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_null_with_command() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-0", "ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't use --null together with a command"));
+    Ok(())
+}
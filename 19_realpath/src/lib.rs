@@ -0,0 +1,248 @@
+use anyhow::{anyhow, bail, Result};
+use clap::{arg, Command};
+use std::collections::VecDeque;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+
+// Same limit as in Linux, protects from symlink loops
+const MAX_SYMLINKS: usize = 40;
+
+#[derive(Debug)]
+pub struct Config {
+    paths: Vec<PathBuf>,
+    mode: Mode,
+    relative_to: Option<PathBuf>,
+    zero: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Existing, // all components but the last one must exist, that's GNU realpath default
+    Missing,  // no component needs to exist, -m
+    Logical,  // symlinks are not resolved and file system is not touched, -s
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("realpath")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Prints absolute path with all symlinks, . and .. resolved")
+        .args([
+            arg!(<PATHS> ... "Paths to resolve")
+                .value_parser(clap::value_parser!(PathBuf)),
+            arg!(-m --canonicalize_missing "No path component needs to exist"),
+            arg!(-s --strip "Don't resolve symlinks, only normalize the path"),
+            arg!(--relative_to <DIR> "Print paths relative to DIR")
+                .value_parser(clap::value_parser!(PathBuf)),
+            arg!(-z --zero "End each output line with NUL, not newline"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let mode = if matches.get_flag("strip") {
+        Mode::Logical
+    } else if matches.get_flag("canonicalize_missing") {
+        Mode::Missing
+    } else {
+        Mode::Existing
+    };
+
+    // Construct config
+    Ok(Config {
+        paths: matches
+            .remove_many("PATHS")
+            .expect("At least one path is required")
+            .collect(),
+        mode,
+        relative_to: matches.remove_one("relative_to"),
+        zero: matches.get_flag("zero"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let current = std::env::current_dir()?;
+    let ending: &[u8] = if config.zero { b"\0" } else { b"\n" };
+    let mut writer = BufWriter::new(io::stdout().lock());
+
+    let base = match &config.relative_to {
+        Some(dir) => Some(
+            resolve(&current.join(dir), config.mode)
+                .map_err(|error| anyhow!("{}: {error}", dir.display()))?,
+        ),
+        None => None,
+    };
+
+    let mut failed = false;
+    for path in &config.paths {
+        let resolved = match resolve(&current.join(path), config.mode) {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                failed = true;
+                continue;
+            }
+        };
+
+        let output = match &base {
+            Some(base) => relative_path(&resolved, base),
+            None => resolved,
+        };
+
+        writer.write_all(output.as_os_str().as_encoded_bytes())?;
+        writer.write_all(ending)?;
+    }
+
+    writer.flush()?;
+
+    // The rest of the paths is still printed, only the exit code tells about the failure
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Resolves absolute path component by component
+///
+/// Symlink targets are spliced into the queue of not yet processed
+/// components, so `..` after a symlink goes to the parent of the target.
+fn resolve(path: &Path, mode: Mode) -> Result<PathBuf> {
+    let mut pending: VecDeque<PathBuf> = components(path);
+    let mut resolved = PathBuf::new();
+    let mut symlinks = 0;
+    let mut missing = false;
+
+    while let Some(component) = pending.pop_front() {
+        match component.components().next() {
+            Some(Component::Prefix(_) | Component::RootDir) => resolved.push(&component),
+            Some(Component::ParentDir) => {
+                resolved.pop();
+
+                // Going up from a missing directory can lead back to existing ones
+                missing = missing && !resolved.is_dir();
+            }
+            Some(Component::Normal(_)) => {
+                let candidate = resolved.join(&component);
+
+                // Below a missing directory nothing can exist
+                if mode == Mode::Logical || missing {
+                    resolved = candidate;
+                    continue;
+                }
+
+                match std::fs::symlink_metadata(&candidate) {
+                    Ok(metadata) if metadata.file_type().is_symlink() => {
+                        symlinks += 1;
+                        if symlinks > MAX_SYMLINKS {
+                            bail!("Too many levels of symbolic links");
+                        }
+
+                        // Relative target is resolved against the symlink's directory
+                        let target = std::fs::read_link(&candidate)?;
+                        for component in components(&target).into_iter().rev() {
+                            pending.push_front(component);
+                        }
+                    }
+                    Ok(metadata) => {
+                        if !metadata.is_dir() && !pending.is_empty() {
+                            if mode != Mode::Missing {
+                                bail!("{}", io::Error::from(io::ErrorKind::NotADirectory));
+                            }
+
+                            // Nothing exists below a file, the same as below a missing directory
+                            missing = true;
+                        }
+                        resolved = candidate;
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                        if mode == Mode::Existing && !pending.is_empty() {
+                            bail!("{error}");
+                        }
+                        missing = true;
+                        resolved = candidate;
+                    }
+                    Err(error) => bail!("{error}"),
+                }
+            }
+            Some(Component::CurDir) | None => {}
+        }
+    }
+
+    Ok(resolved)
+}
+
+fn components(path: &Path) -> VecDeque<PathBuf> {
+    path.components()
+        .map(|component| PathBuf::from(component.as_os_str()))
+        .collect()
+}
+
+/// Path from `base` directory to `path`, both are absolute and resolved
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut result = PathBuf::new();
+    for _ in common..base.len() {
+        result.push("..");
+    }
+    for component in &path[common..] {
+        result.push(component);
+    }
+
+    if result.as_os_str().is_empty() {
+        result.push(".");
+    }
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{relative_path, resolve, Mode};
+    use std::path::Path;
+
+    #[test]
+    fn test_resolve_logical() {
+        let res = resolve(Path::new("/a/./b/../c//d/"), Mode::Logical);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), Path::new("/a/c/d"));
+
+        let res = resolve(Path::new("/../.."), Mode::Logical);
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), Path::new("/"));
+    }
+
+    #[test]
+    fn test_resolve_missing() {
+        let missing = std::env::temp_dir().join("realpath-missing-dir");
+
+        let res = resolve(&missing.join("file"), Mode::Existing);
+        assert!(res.is_err());
+
+        let res = resolve(&missing, Mode::Existing);
+        assert!(res.is_ok());
+        assert!(res.unwrap().ends_with("realpath-missing-dir"));
+
+        let res = resolve(&missing.join("a/../b"), Mode::Missing);
+        assert!(res.is_ok());
+        assert!(res.unwrap().ends_with("realpath-missing-dir/b"));
+
+        let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let res = resolve(&file.join("x"), Mode::Existing);
+        assert!(res.is_err());
+
+        let res = resolve(&file.join("x/../y"), Mode::Missing);
+        assert!(res.is_ok());
+        assert!(res.unwrap().ends_with("Cargo.toml/y"));
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new("/a/b"), Path::new("/a/b")), Path::new("."));
+        assert_eq!(relative_path(Path::new("/a"), Path::new("/a/b")), Path::new(".."));
+        assert_eq!(relative_path(Path::new("/etc"), Path::new("/usr/bin")), Path::new("../../etc"));
+        assert_eq!(relative_path(Path::new("/a/b/c"), Path::new("/a")), Path::new("b/c"));
+    }
+}
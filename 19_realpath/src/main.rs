@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = realpath::get_args().and_then(realpath::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
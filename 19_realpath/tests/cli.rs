@@ -0,0 +1,127 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "realpath";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+fn current_dir() -> String {
+    std::env::current_dir()
+        .unwrap()
+        .canonicalize()
+        .unwrap()
+        .display()
+        .to_string()
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_missing_dir() -> TestResult {
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .args([&format!("{bad}/file"), "src"])
+        .assert()
+        .failure()
+        .stdout(format!("{}/src\n", current_dir()))
+        .stderr(predicate::str::is_match(format!("{bad}/file: .* [(]os error 2[)]"))?);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn existing() -> TestResult {
+    let current = current_dir();
+    run(&["src/../tests/.", "."], &format!("{current}/tests\n{current}\n"))
+}
+
+// --------------------------------------------------
+#[test]
+fn missing_last() -> TestResult {
+    run(&["src/missing.rs"], &format!("{}/src/missing.rs\n", current_dir()))
+}
+
+// --------------------------------------------------
+#[test]
+fn canonicalize_missing() -> TestResult {
+    run(&["-m", "/missing/dir/../file"], "/missing/file\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn canonicalize_below_file() -> TestResult {
+    run(&["-m", "Cargo.toml/x"], &format!("{}/Cargo.toml/x\n", current_dir()))
+}
+
+// --------------------------------------------------
+#[test]
+fn strip() -> TestResult {
+    run(&["-s", "/missing/./dir//../file/"], "/missing/file\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn relative_to() -> TestResult {
+    run(&["--relative_to", "tests", "src", "tests", "."], "../src\n.\n..\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn zero() -> TestResult {
+    run(&["-z", "-s", "/a", "/b/"], "/a\0/b\0")
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn symlinks() -> TestResult {
+    let root = std::env::temp_dir().join(format!("realpath-test-{}", std::process::id()));
+    fs::create_dir_all(root.join("a/b"))?;
+    std::os::unix::fs::symlink("a/b", root.join("link"))?;
+    std::os::unix::fs::symlink("loop", root.join("loop"))?;
+    let root = root.canonicalize()?;
+    let path = |name: &str| root.join(name).display().to_string();
+
+    // Parent of a symlink is the parent of its target
+    run(&[&path("link/../c")], &format!("{}\n", path("a/c")))?;
+    run(&["-s", &path("link/../c")], &format!("{}\n", path("c")))?;
+
+    // Symlinks are resolved again once .. leads out of a missing directory
+    run(&["-m", &path("nope/../link")], &format!("{}\n", path("a/b")))?;
+
+    Command::cargo_bin(PRG)?
+        .arg(path("loop"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Too many levels of symbolic links"));
+
+    fs::remove_dir_all(&root)?;
+    Ok(())
+}
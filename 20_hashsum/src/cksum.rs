@@ -0,0 +1,51 @@
+// Polynomial of the POSIX cksum CRC, bits are processed from the most significant one
+const POLYNOMIAL: u32 = 0x04C1_1DB7;
+
+const TABLE: [u32; 256] = table();
+
+const fn table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = (index as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000_0000 != 0 { (crc << 1) ^ POLYNOMIAL } else { crc << 1 };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
+
+/// CRC of POSIX cksum, the data length is appended to the data before the CRC is finished
+#[derive(Default)]
+pub struct Cksum {
+    crc: u32,
+    len: u64,
+}
+
+impl Cksum {
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.push(byte);
+        }
+        self.len += data.len() as u64;
+    }
+
+    /// CRC and the size in bytes, the way cksum prints them
+    pub fn finalize(mut self) -> (u32, u64) {
+        // Only the significant bytes of the length, the lowest one first
+        let mut len = self.len;
+        while len > 0 {
+            self.push(len as u8);
+            len >>= 8;
+        }
+        (!self.crc, self.len)
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.crc = (self.crc << 8) ^ TABLE[((self.crc >> 24) as u8 ^ byte) as usize];
+    }
+}
@@ -0,0 +1,334 @@
+mod cksum;
+
+use anyhow::{bail, Result};
+use cksum::Cksum;
+use clap::{arg, builder::PossibleValuesParser, Command};
+use common::reader::open;
+use md5::{Digest, Md5};
+use rayon::prelude::*;
+use sha1::Sha1;
+use sha2::Sha256;
+use std::io::{BufRead, BufWriter, Write};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    algorithm: Algorithm,
+    check: bool,
+    quiet: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    // CRC of POSIX cksum, printed as CRC SIZE FILE the same as in GNU cksum
+    Crc32,
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl Algorithm {
+    fn hasher(self) -> Hasher {
+        match self {
+            Algorithm::Crc32 => Hasher::Crc32(Cksum::default()),
+            Algorithm::Md5 => Hasher::Md5(Md5::new()),
+            Algorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            Algorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+
+    // Length of the digest in hex digits, cksum digests are decimal instead
+    fn hex_len(self) -> Option<usize> {
+        match self {
+            Algorithm::Crc32 => None,
+            Algorithm::Md5 => Some(32),
+            Algorithm::Sha1 => Some(40),
+            Algorithm::Sha256 => Some(64),
+        }
+    }
+
+    // Between the digest and the file name
+    fn separator(self) -> &'static str {
+        match self {
+            Algorithm::Crc32 => " ",
+            _ => "  ",
+        }
+    }
+}
+
+enum Hasher {
+    Crc32(Cksum),
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Crc32(hasher) => hasher.update(data),
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Sha1(hasher) => hasher.update(data),
+            Hasher::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Crc32(hasher) => {
+                let (crc, size) = hasher.finalize();
+                format!("{crc} {size}")
+            }
+            Hasher::Md5(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha1(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("hashsum")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Computes or checks file digests, lines are DIGEST  FILE or CRC SIZE FILE for crc32")
+        .args([
+            arg!([FILES] ... "Files to process, stdin is -").default_value("-"),
+            arg!(-a --algorithm <ALGORITHM> "Digest algorithm, crc32 is the one of POSIX cksum")
+                .value_parser(PossibleValuesParser::new(["crc32", "md5", "sha1", "sha256"]))
+                .default_value("sha256"),
+            arg!(-c --check "Read digests from the files and verify them"),
+            arg!(--quiet "Don't print OK for each successfully verified file"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let algorithm: String = matches.remove_one("algorithm").expect("Default value is provided");
+
+    // Construct config
+    Ok(Config {
+        files: matches
+            .remove_many("FILES")
+            .expect("No file paths provided")
+            .collect(),
+        algorithm: match algorithm.as_str() {
+            "crc32" => Algorithm::Crc32,
+            "md5" => Algorithm::Md5,
+            "sha1" => Algorithm::Sha1,
+            "sha256" => Algorithm::Sha256,
+            unknown => unreachable!("Unsupported algorithm {unknown}"),
+        },
+        check: matches.get_flag("check"),
+        quiet: matches.get_flag("quiet"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    if config.check {
+        check_files(&config)
+    } else {
+        hash_files(&config)
+    }
+}
+
+fn hash(path: &str, algorithm: Algorithm) -> Result<String> {
+    let mut reader = open(path)?;
+    let mut hasher = algorithm.hasher();
+
+    // Large files are streamed instead of being read into memory
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+
+        let length = buffer.len();
+        hasher.update(buffer);
+        reader.consume(length);
+    }
+
+    Ok(hasher.finalize())
+}
+
+// Files are hashed in parallel, but results are printed in the order of the arguments
+fn hash_all(paths: &[&str], algorithm: Algorithm) -> Vec<Result<String>> {
+    paths
+        .par_iter()
+        .with_max_len(1)
+        .map(|path| hash(path, algorithm))
+        .collect()
+}
+
+fn hash_files(config: &Config) -> Result<()> {
+    let paths: Vec<&str> = config.files.iter().map(String::as_str).collect();
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+    let mut failed = false;
+
+    for (path, digest) in paths.iter().zip(hash_all(&paths, config.algorithm)) {
+        match digest {
+            Ok(digest) => writeln!(writer, "{digest}{}{path}", config.algorithm.separator())?,
+            Err(error) => {
+                eprintln!("Can't hash file {error}");
+                failed = true;
+            }
+        }
+    }
+
+    writer.flush()?;
+
+    // The rest of the files is still hashed, only the exit code tells about the failure
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Parses `DIGEST  FILE` line, `*` in front of the file marks binary mode
+fn parse_check_line(line: &str, algorithm: Algorithm) -> Option<(&str, &str)> {
+    let Some(hex_len) = algorithm.hex_len() else {
+        return parse_cksum_line(line);
+    };
+
+    let (digest, rest) = line.split_once(' ')?;
+    let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+
+    let valid = digest.len() == hex_len
+        && digest.chars().all(|char| char.is_ascii_hexdigit())
+        && !path.is_empty();
+
+    valid.then_some((digest, path))
+}
+
+/// Parses `CRC SIZE FILE` line of cksum, the size is checked together with the CRC
+fn parse_cksum_line(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.splitn(3, ' ');
+    let (crc, size, path) = (parts.next()?, parts.next()?, parts.next()?);
+
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    let valid = is_number(crc) && is_number(size) && !path.is_empty();
+
+    valid.then_some((&line[..crc.len() + 1 + size.len()], path))
+}
+
+fn check_files(config: &Config) -> Result<()> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut improper = 0;
+    let mut errors = Vec::new();
+
+    for path in &config.files {
+        let reader = match open(path) {
+            Ok(reader) => reader,
+            Err(error) => {
+                errors.push(format!("Can't open file {error}"));
+                continue;
+            }
+        };
+
+        let listed = entries.len();
+        for line in reader.lines() {
+            let line = line?;
+            match parse_check_line(line.trim_end_matches('\r'), config.algorithm) {
+                Some((digest, path)) => entries.push((digest.to_lowercase(), path.to_string())),
+                None => improper += 1,
+            }
+        }
+
+        // Most likely a wrong file or a wrong algorithm, the same as in GNU sha256sum
+        if entries.len() == listed {
+            errors.push(format!("{path}: no properly formatted checksum lines found"));
+        }
+    }
+
+    let paths: Vec<&str> = entries.iter().map(|(_, path)| path.as_str()).collect();
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+    let mut unreadable = 0;
+    let mut mismatched = 0;
+
+    for ((expected, path), actual) in entries.iter().zip(hash_all(&paths, config.algorithm)) {
+        match actual {
+            Ok(actual) if actual == *expected => {
+                if !config.quiet {
+                    writeln!(writer, "{path}: OK")?;
+                }
+            }
+            Ok(_) => {
+                mismatched += 1;
+                writeln!(writer, "{path}: FAILED")?;
+            }
+            Err(error) => {
+                unreadable += 1;
+                eprintln!("Can't hash file {error}");
+                writeln!(writer, "{path}: FAILED open or read")?;
+            }
+        }
+    }
+    writer.flush()?;
+
+    if improper > 0 {
+        eprintln!("WARNING: {improper} line(s) are improperly formatted");
+    }
+
+    let mut warnings = errors;
+    if unreadable > 0 {
+        warnings.push(format!("WARNING: {unreadable} listed file(s) could not be read"));
+    }
+    if mismatched > 0 {
+        warnings.push(format!("WARNING: {mismatched} computed checksum(s) did NOT match"));
+    }
+    if !warnings.is_empty() {
+        bail!(warnings.join("\n"));
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_check_line, Algorithm};
+
+    #[test]
+    fn test_hasher() {
+        let digest = |algorithm: Algorithm| {
+            let mut hasher = algorithm.hasher();
+            hasher.update(b"The quick brown fox ");
+            hasher.update(b"jumps over the lazy dog");
+            hasher.finalize()
+        };
+
+        assert_eq!(digest(Algorithm::Crc32), "2074844392 43");
+
+        // Lengths above 255 are appended with more than one byte
+        let mut hasher = Algorithm::Crc32.hasher();
+        hasher.update(&[b'a'; 1000]);
+        assert_eq!(hasher.finalize(), "145108180 1000");
+        assert_eq!(digest(Algorithm::Md5), "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(digest(Algorithm::Sha1), "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        assert_eq!(
+            digest(Algorithm::Sha256),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592"
+        );
+    }
+
+    #[test]
+    fn test_parse_check_line() {
+        let md5 = "9e107d9d372bb6826bd81d3542a419d6";
+        assert_eq!(
+            parse_check_line(&format!("{md5}  fox.txt"), Algorithm::Md5),
+            Some((md5, "fox.txt"))
+        );
+        assert_eq!(
+            parse_check_line(&format!("{md5} *with space.bin"), Algorithm::Md5),
+            Some((md5, "with space.bin"))
+        );
+        assert_eq!(parse_check_line(&format!("{md5}  fox.txt"), Algorithm::Sha1), None);
+        assert_eq!(parse_check_line(&format!("{md5} fox.txt"), Algorithm::Md5), None);
+        assert_eq!(parse_check_line("zz  fox.txt", Algorithm::Crc32), None);
+        assert_eq!(
+            parse_check_line("2382472371 44 with space.txt", Algorithm::Crc32),
+            Some(("2382472371 44", "with space.txt"))
+        );
+        assert_eq!(parse_check_line(&format!("{md5}  fox.txt"), Algorithm::Crc32), None);
+        assert_eq!(parse_check_line("", Algorithm::Md5), None);
+    }
+}
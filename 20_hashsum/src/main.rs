@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = hashsum::get_args().and_then(hashsum::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,163 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "hashsum";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const LOREM: &str = "tests/inputs/lorem.txt";
+const GOOD: &str = "tests/inputs/good.md5";
+const BAD: &str = "tests/inputs/bad.md5";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, EMPTY])
+        .assert()
+        .failure()
+        .stdout(predicate::str::ends_with(format!("  {EMPTY}\n")))
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_algorithm() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-a", "sha3", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'sha3'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sha256() -> TestResult {
+    run(&[EMPTY, FOX, LOREM], "tests/expected/all.sha256.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn md5() -> TestResult {
+    run(&["-a", "md5", EMPTY, FOX, LOREM], "tests/expected/all.md5.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sha1() -> TestResult {
+    run(&["--algorithm", "sha1", FOX], "tests/expected/fox.sha1.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn crc32() -> TestResult {
+    run(&["-a", "crc32", EMPTY, FOX, LOREM], "tests/expected/all.crc32.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read_to_string(FOX)?)
+        .assert()
+        .success()
+        .stdout("c03905fcdab297513a620ec81ed46ca44ddb62d41cbbd83eb4a5a3592be26a69  -\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_good() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-a", "md5", "-c", GOOD])
+        .assert()
+        .success()
+        .stdout(format!("{EMPTY}: OK\n{FOX}: OK\n{LOREM}: OK\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_good_quiet() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-a", "md5", "--check", "--quiet", GOOD])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_crc32() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-a", "crc32", "-c", "tests/inputs/good.cksum"])
+        .assert()
+        .success()
+        .stdout(format!("{EMPTY}: OK\n{FOX}: OK\n{LOREM}: OK\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_bad() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-a", "md5", "-c", BAD])
+        .assert()
+        .failure()
+        .stdout(format!(
+            "{EMPTY}: OK\n{FOX}: FAILED\ntests/inputs/missing.txt: FAILED open or read\n"
+        ))
+        .stderr(predicate::str::contains("WARNING: 1 line(s) are improperly formatted"))
+        .stderr(predicate::str::contains("WARNING: 1 listed file(s) could not be read"))
+        .stderr(predicate::str::contains("WARNING: 1 computed checksum(s) did NOT match"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_no_formatted_lines() -> TestResult {
+    let garbage = "tests/inputs/garbage.md5";
+    Command::cargo_bin(PRG)?
+        .args(["-a", "md5", "-c", garbage])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains(format!(
+            "{garbage}: no properly formatted checksum lines found"
+        )));
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local};
+use clap::{arg, Command};
+use common::columns::{format_row, grid, Order};
+use common::reader::open;
+use std::ffi::OsString;
+use std::io::{BufRead, BufWriter, Write};
+
+// Page layout of GNU pr: 2 blank lines, header, 2 blank lines on top
+// and 5 blank lines at the bottom of every page
+const HEADER_LINES: usize = 5;
+const TRAILER_LINES: usize = 5;
+const TAB_SIZE: usize = 8;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    length: usize,
+    width: usize,
+    columns: usize,
+    order: Order,
+    omit_header: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let args = common::args::expand_args(std::env::args_os())?;
+    let mut matches = Command::new("pr")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Paginates text files for printing")
+        .args([
+            arg!([FILES] ... "Files to process, stdin is -").default_value("-"),
+            arg!(-l --length <LINES> "Page length in lines, including header and trailer")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("66"),
+            arg!(-w --width <WIDTH> "Page width in characters for multi-column output")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("72"),
+            arg!(--columns <COLUMNS> "Number of columns, can also be passed as -COLUMNS")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+            arg!(-a --across "Fill columns across the page instead of down"),
+            arg!(-t --omit_header "Don't print page headers and trailers"),
+        ])
+        .get_matches_from(expand_column_args(args));
+
+    let length: usize = matches.remove_one("length").expect("Default value is provided");
+    let width: usize = matches.remove_one("width").expect("Default value is provided");
+    let columns: usize = matches.remove_one("columns").expect("Default value is provided");
+
+    if length == 0 {
+        bail!("Page length can't be zero");
+    }
+    if columns == 0 {
+        bail!("Number of columns can't be zero");
+    }
+    if columns > 1 && column_width(width, columns) == 0 {
+        bail!("Page width {width} is too small for {columns} columns");
+    }
+
+    // Construct config
+    Ok(Config {
+        files: matches
+            .remove_many("FILES")
+            .expect("No file paths provided")
+            .collect(),
+        length,
+        width,
+        columns,
+        order: if matches.get_flag("across") { Order::Across } else { Order::Down },
+        // Like in GNU pr there is no room for headers on short pages
+        omit_header: matches.get_flag("omit_header") || length <= HEADER_LINES + TRAILER_LINES,
+    })
+}
+
+// Clap can't parse numeric flags, so -3 is rewritten as --columns 3
+fn expand_column_args(args: Vec<OsString>) -> Vec<OsString> {
+    let mut result = Vec::with_capacity(args.len());
+    let mut expand = true;
+
+    for arg in args {
+        if arg == "--" {
+            expand = false;
+        }
+
+        let digits = arg.to_str().and_then(|text| text.strip_prefix('-'));
+        let is_number = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+
+        match digits {
+            Some(digits) if expand && is_number(digits) => {
+                result.push("--columns".into());
+                result.push(digits.into());
+            }
+            _ => result.push(arg),
+        }
+    }
+
+    result
+}
+
+// Columns are separated by a single space
+fn column_width(width: usize, columns: usize) -> usize {
+    width.saturating_sub(columns - 1) / columns
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+
+    for path in &config.files {
+        match open(path) {
+            Ok(reader) => process_file(reader, path, &mut writer, &config)?,
+            Err(error) => eprintln!("Can't open file {error}"),
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn process_file(
+    reader: impl BufRead,
+    path: &str,
+    writer: &mut impl Write,
+    config: &Config,
+) -> Result<()> {
+    let body_lines = if config.omit_header {
+        config.length
+    } else {
+        config.length - HEADER_LINES - TRAILER_LINES
+    };
+
+    let date = modification_date(path).format("%Y-%m-%d %H:%M").to_string();
+    let name = if path == "-" { "" } else { path };
+
+    let mut lines = reader.lines();
+    let mut page_number = 1;
+
+    loop {
+        // Every page holds up to body_lines lines in each column
+        let page = lines
+            .by_ref()
+            .take(body_lines * config.columns)
+            .collect::<std::io::Result<Vec<String>>>()?;
+        if page.is_empty() {
+            break;
+        }
+
+        let rows = format_page(&page, config);
+
+        if !config.omit_header {
+            let header = format_header(&date, name, page_number, config.width);
+            write!(writer, "\n\n{header}\n\n\n")?;
+        }
+
+        for row in &rows {
+            writeln!(writer, "{row}")?;
+        }
+
+        if !config.omit_header {
+            let padding = body_lines - rows.len() + TRAILER_LINES;
+            write!(writer, "{}", "\n".repeat(padding))?;
+        }
+
+        page_number += 1;
+    }
+
+    Ok(())
+}
+
+// Stdin has no modification date, current time is used for it
+fn modification_date(path: &str) -> DateTime<Local> {
+    match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(modified) if path != "-" => modified.into(),
+        _ => Local::now(),
+    }
+}
+
+/// Date on the left, file name in the middle and page number on the right
+fn format_header(date: &str, name: &str, page: usize, width: usize) -> String {
+    let page = format!("Page {page}");
+    let used = date.chars().count() + name.chars().count() + page.chars().count();
+    let space = width.saturating_sub(used).max(2);
+    let left = space / 2;
+    let right = space - left;
+
+    format!("{date}{}{name}{}{page}", " ".repeat(left), " ".repeat(right))
+}
+
+fn format_page(lines: &[String], config: &Config) -> Vec<String> {
+    if config.columns == 1 {
+        return lines.to_vec();
+    }
+
+    // Tabs have to be expanded, otherwise they break the column alignment
+    let lines: Vec<String> = lines.iter().map(|line| expand_tabs(line)).collect();
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let width = column_width(config.width, config.columns);
+
+    grid(&lines, config.columns, config.order)
+        .iter()
+        .map(|row| format_row(row, width, " "))
+        .collect()
+}
+
+fn expand_tabs(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut column = 0;
+
+    for char in line.chars() {
+        if char == '\t' {
+            let spaces = TAB_SIZE - column % TAB_SIZE;
+            result.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            result.push(char);
+            column += 1;
+        }
+    }
+
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{expand_column_args, expand_tabs, format_header};
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_expand_column_args() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            expand_column_args(args(&["pr", "-3", "-l", "20", "a.txt"])),
+            args(&["pr", "--columns", "3", "-l", "20", "a.txt"])
+        );
+        assert_eq!(
+            expand_column_args(args(&["pr", "-", "--", "-2"])),
+            args(&["pr", "-", "--", "-2"])
+        );
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("\tTabbed\tline"), "        Tabbed  line");
+        assert_eq!(expand_tabs("no tabs"), "no tabs");
+        assert_eq!(expand_tabs("á\tb\t\tc"), "á       b               c");
+    }
+
+    #[test]
+    fn test_format_header() {
+        // Same spacing as in GNU pr
+        assert_eq!(
+            format_header("2026-10-16 10:35", "n.txt", 1, 72),
+            format!("2026-10-16 10:35{}n.txt{}Page 1", " ".repeat(22), " ".repeat(23))
+        );
+
+        // Long names still get a space on both sides
+        assert_eq!(
+            format_header("2026-10-16 10:35", "long-name.txt", 12, 20),
+            "2026-10-16 10:35 long-name.txt Page 12"
+        );
+    }
+}
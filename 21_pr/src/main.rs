@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = pr::get_args().and_then(pr::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,142 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "pr";
+const NUMBERS: &str = "tests/inputs/numbers.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, EMPTY])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_narrow_page() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-5", "-w", "8", NUMBERS])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Page width 8 is too small for 5 columns"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+// Header starts with the file modification date, it is replaced with DATE placeholder
+fn run_headers(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+
+    let actual: String = String::from_utf8(output.stdout)?
+        .split_inclusive('\n')
+        .map(|line| match line.contains("Page ") {
+            true => format!("{:16}{}", "DATE", &line[16..]),
+            false => line.to_string(),
+        })
+        .collect();
+
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    Command::cargo_bin(PRG)?.arg(EMPTY).assert().success().stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_headers() -> TestResult {
+    run_headers(&["-l", "15", NUMBERS], "tests/expected/numbers.txt.l15.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_headers_columns() -> TestResult {
+    run_headers(&["-3", "-l", "15", NUMBERS], "tests/expected/numbers.txt.3l15.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_header_date() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg(NUMBERS)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(
+            r"^\n\n\d{4}-\d{2}-\d{2} \d{2}:\d{2} +tests/inputs/numbers.txt +Page 1\n\n\n1\n",
+        )?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_omit_header() -> TestResult {
+    run(&["-t", NUMBERS], "tests/expected/numbers.txt.t.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_columns() -> TestResult {
+    run(&["-t", "-3", "-l", "5", NUMBERS], "tests/expected/numbers.txt.t3l5.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_across() -> TestResult {
+    run(&["-t", "--columns", "2", "-a", "-l", "5", NUMBERS], "tests/expected/numbers.txt.t2al5.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_truncated() -> TestResult {
+    run(&["-t", "-3", "-w", "40", FOX], "tests/expected/fox.txt.t3w40.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    run(&["-t", "-l", "2", NUMBERS, FOX], "tests/expected/all.tl2.out")
+}
@@ -0,0 +1,218 @@
+use anyhow::{bail, Result};
+use clap::{arg, Command};
+use common::reader::open;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufWriter, Read, Write};
+
+#[derive(Debug)]
+pub struct Config {
+    file: String,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("tsort")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Sorts nodes topologically, input is a list of 'before after' pairs")
+        .args([arg!([FILE] "File to process, stdin is -").default_value("-")])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Construct config
+    Ok(Config {
+        file: matches.remove_one("FILE").expect("Default value is provided"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut text = String::new();
+    open(&config.file)?.read_to_string(&mut text)?;
+
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if !tokens.len().is_multiple_of(2) {
+        bail!("{}: input contains an odd number of tokens", config.file);
+    }
+
+    let mut graph = Graph::default();
+    for pair in tokens.chunks(2) {
+        graph.add_edge(pair[0], pair[1]);
+    }
+
+    let (order, loops) = graph.sort();
+
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+    for node in order {
+        writeln!(writer, "{node}")?;
+    }
+    writer.flush()?;
+
+    // Nodes of a loop are still printed, but that's an error like in GNU tsort
+    if !loops.is_empty() {
+        let reports: Vec<String> = loops
+            .iter()
+            .map(|nodes| format!("{}: input contains a loop:\n{}", config.file, nodes.join("\n")))
+            .collect();
+        bail!(reports.join("\n"));
+    }
+
+    Ok(())
+}
+
+/// Directed graph where nodes are referenced by index in the order they first appear
+#[derive(Debug, Default)]
+struct Graph<'a> {
+    names: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    fn node(&mut self, name: &'a str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+
+        self.names.push(name);
+        self.successors.push(Vec::new());
+        self.predecessors.push(Vec::new());
+        self.indices.insert(name, self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    // Pair of the same node only declares that node
+    fn add_edge(&mut self, from: &'a str, to: &'a str) {
+        let from = self.node(from);
+        let to = self.node(to);
+
+        if from != to {
+            self.successors[from].push(to);
+            self.predecessors[to].push(from);
+        }
+    }
+
+    /// Kahn's algorithm with the same output order as GNU tsort for acyclic input:
+    /// initial nodes go in name order, successors in reverse order of appearance
+    ///
+    /// When every remaining node has a predecessor there must be a loop.
+    /// It is reported and broken by removing one of its edges, then sorting continues.
+    fn sort(mut self) -> (Vec<&'a str>, Vec<Vec<&'a str>>) {
+        let count = self.names.len();
+        let mut incoming: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut done = vec![false; count];
+        let mut order = Vec::with_capacity(count);
+        let mut loops = Vec::new();
+
+        let mut by_name: Vec<usize> = (0..count).collect();
+        by_name.sort_by_key(|&index| self.names[index]);
+
+        let mut queue: VecDeque<usize> = by_name
+            .iter()
+            .copied()
+            .filter(|&index| incoming[index] == 0)
+            .collect();
+
+        while order.len() < count {
+            let Some(node) = queue.pop_front() else {
+                // Smallest remaining name makes the loop report stable
+                let start = *by_name.iter().find(|&&index| !done[index]).expect("Nodes remain");
+                let cycle = self.find_cycle(start, &done);
+
+                // Remove the edge that closes the loop
+                let first = cycle[0];
+                let last = cycle[cycle.len() - 1];
+                remove_one(&mut self.successors[last], first);
+                remove_one(&mut self.predecessors[first], last);
+                incoming[first] -= 1;
+
+                loops.push(cycle.iter().map(|&index| self.names[index]).collect());
+                if incoming[first] == 0 {
+                    queue.push_back(first);
+                }
+                continue;
+            };
+
+            done[node] = true;
+            order.push(self.names[node]);
+
+            for &next in self.successors[node].iter().rev() {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order, loops)
+    }
+
+    // Every remaining node has a remaining predecessor, so walking
+    // predecessors eventually revisits a node, that closes a loop
+    fn find_cycle(&self, start: usize, done: &[bool]) -> Vec<usize> {
+        let mut path = vec![start];
+        let mut position = HashMap::from([(start, 0)]);
+        let mut current = start;
+
+        loop {
+            current = *self.predecessors[current]
+                .iter()
+                .find(|&&index| !done[index])
+                .expect("Remaining node has a remaining predecessor");
+
+            if let Some(&index) = position.get(&current) {
+                // Path goes backwards along the edges, loop is reported forwards
+                let mut cycle = path.split_off(index);
+                cycle[1..].reverse();
+                return cycle;
+            }
+
+            position.insert(current, path.len());
+            path.push(current);
+        }
+    }
+}
+
+fn remove_one(items: &mut Vec<usize>, item: usize) {
+    if let Some(position) = items.iter().position(|&other| other == item) {
+        items.remove(position);
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+
+    fn sort<'a>(pairs: &[(&'a str, &'a str)]) -> (Vec<&'a str>, Vec<Vec<&'a str>>) {
+        let mut graph = Graph::default();
+        for &(from, to) in pairs {
+            graph.add_edge(from, to);
+        }
+        graph.sort()
+    }
+
+    #[test]
+    fn test_sort() {
+        let pairs = [("a", "b"), ("b", "c"), ("a", "c"), ("d", "e"), ("x", "y"), ("y", "z")];
+        let (order, loops) = sort(&pairs);
+        assert_eq!(order, ["a", "d", "x", "b", "e", "y", "c", "z"]);
+        assert!(loops.is_empty());
+
+        let (order, _) = sort(&[("z", "a"), ("y", "a"), ("x", "b"), ("b", "a")]);
+        assert_eq!(order, ["x", "y", "z", "b", "a"]);
+
+        let (order, _) = sort(&[("a", "a"), ("b", "b")]);
+        assert_eq!(order, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_sort_loops() {
+        let (order, loops) = sort(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")]);
+        assert_eq!(order, ["a", "b", "c", "d"]);
+        assert_eq!(loops, [["a", "b", "c"]]);
+
+        let (order, loops) = sort(&[("b", "a"), ("a", "b"), ("c", "d"), ("d", "c")]);
+        assert_eq!(order, ["a", "b", "c", "d"]);
+        assert_eq!(loops, [["a", "b"], ["c", "d"]]);
+    }
+}
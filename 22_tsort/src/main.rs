@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = tsort::get_args().and_then(tsort::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
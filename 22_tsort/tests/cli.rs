@@ -0,0 +1,108 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "tsort";
+const DAG: &str = "tests/inputs/dag.txt";
+const BUILD: &str = "tests/inputs/build.txt";
+const LOOP: &str = "tests/inputs/loop.txt";
+const ODD: &str = "tests/inputs/odd.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .arg(&bad)
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_odd_tokens() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg(ODD)
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(format!("{ODD}: input contains an odd number of tokens\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_loop() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/loop.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .arg(LOOP)
+        .assert()
+        .failure()
+        .stdout(expected)
+        .stderr(format!("{LOOP}: input contains a loop:\na\nb\nc\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dag() -> TestResult {
+    run(&[DAG], "tests/expected/dag.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dag_stdin() -> TestResult {
+    let input = fs::read_to_string(DAG)?;
+    let expected = fs::read_to_string("tests/expected/dag.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn build() -> TestResult {
+    run(&[BUILD], "tests/expected/build.txt.out")
+}
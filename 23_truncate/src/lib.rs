@@ -0,0 +1,190 @@
+use anyhow::{anyhow, bail, Result};
+use clap::{arg, Command};
+use common::units;
+use std::fs::OpenOptions;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    size: Option<Size>,
+    reference: Option<u64>,
+    no_create: bool,
+}
+
+/// Requested file size, prefix of the --size value tells how it relates to the current size
+#[derive(Debug, Clone, PartialEq)]
+enum Size {
+    Exact(u64),     // 1M
+    Extend(u64),    // +1M
+    Reduce(u64),    // -1M
+    AtMost(u64),    // <1M
+    AtLeast(u64),   // >1M
+    RoundDown(u64), // /4K
+    RoundUp(u64),   // %4K
+}
+
+impl Size {
+    fn is_relative(&self) -> bool {
+        !matches!(self, Size::Exact(_))
+    }
+
+    fn apply(&self, current: u64) -> Result<u64> {
+        Ok(match *self {
+            Size::Exact(size) => size,
+            Size::Extend(size) => current
+                .checked_add(size)
+                .ok_or_else(|| anyhow!("Size {current} + {size} is too large"))?,
+            Size::Reduce(size) => current.saturating_sub(size),
+            Size::AtMost(size) => current.min(size),
+            Size::AtLeast(size) => current.max(size),
+            Size::RoundDown(0) | Size::RoundUp(0) => bail!("Division by zero"),
+            Size::RoundDown(size) => current / size * size,
+            Size::RoundUp(size) => current
+                .div_ceil(size)
+                .checked_mul(size)
+                .ok_or_else(|| anyhow!("Size {current} rounded up to {size} is too large"))?,
+        })
+    }
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("truncate")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Shrinks or extends files to the specified size")
+        .args([
+            arg!(<FILES> ... "Files to resize, missing ones are created"),
+            arg!(-s --size <SIZE> "Size like 10K or 1MB, + - < > / % prefix makes it relative")
+                .value_parser(parse_size)
+                .allow_hyphen_values(true),
+            arg!(-r --reference <RFILE> "Use size of RFILE, --size is then relative to it"),
+            arg!(-c --no_create "Don't create missing files"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let size: Option<Size> = matches.remove_one("size");
+    let reference = match matches.remove_one::<String>("reference") {
+        Some(path) => Some(
+            std::fs::metadata(&path)
+                .map_err(|error| anyhow!("Can't read reference file {path}: {error}"))?
+                .len(),
+        ),
+        None => None,
+    };
+
+    match (&size, reference) {
+        (None, None) => bail!("Either --size or --reference must be specified"),
+        (Some(size), Some(_)) if !size.is_relative() => {
+            bail!("Only relative --size can be used together with --reference")
+        }
+        _ => {}
+    }
+
+    // Construct config
+    Ok(Config {
+        files: matches
+            .remove_many("FILES")
+            .expect("At least one file is required")
+            .collect(),
+        size,
+        reference,
+        no_create: matches.get_flag("no_create"),
+    })
+}
+
+fn parse_size(text: &str) -> Result<Size> {
+    let mut chars = text.chars();
+    let kind: fn(u64) -> Size = match chars.next() {
+        Some('+') => Size::Extend,
+        Some('-') => Size::Reduce,
+        Some('<') => Size::AtMost,
+        Some('>') => Size::AtLeast,
+        Some('/') => Size::RoundDown,
+        Some('%') => Size::RoundUp,
+        _ => {
+            chars = text.chars();
+            Size::Exact
+        }
+    };
+
+    // K and KiB are powers of 1024, KB is a power of 1000, the same as in GNU truncate
+    let size = units::parse_size(chars.as_str().trim_start())?;
+    Ok(kind(size))
+}
+
+pub fn run(config: Config) -> Result<()> {
+    for path in &config.files {
+        if let Err(error) = resize(path, &config) {
+            eprintln!("Can't resize file {path}: {error}");
+        }
+    }
+
+    Ok(())
+}
+
+fn resize(path: &str, config: &Config) -> Result<()> {
+    // Missing file is silently skipped with --no_create like in GNU truncate
+    if config.no_create && std::fs::metadata(path).is_err() {
+        return Ok(());
+    }
+
+    let file = OpenOptions::new().write(true).create(true).truncate(false).open(path)?;
+    let current = config.reference.unwrap_or(file.metadata()?.len());
+
+    let size = match &config.size {
+        Some(size) => size.apply(current)?,
+        None => current,
+    };
+
+    file.set_len(size)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_size, Size::*};
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512").unwrap(), Exact(512));
+        assert_eq!(parse_size("+1M").unwrap(), Extend(1024 * 1024));
+        assert_eq!(parse_size("-512").unwrap(), Reduce(512));
+        assert_eq!(parse_size("<1KB").unwrap(), AtMost(1000));
+        assert_eq!(parse_size(">2KiB").unwrap(), AtLeast(2048));
+        assert_eq!(parse_size("/4k").unwrap(), RoundDown(4096));
+        assert_eq!(parse_size("%1G").unwrap(), RoundUp(1 << 30));
+
+        let res = parse_size("10.5");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid size: '10.5'");
+
+        let res = parse_size("+");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid size: ''");
+
+        let res = parse_size("1Q");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid size: '1Q'");
+
+        let res = parse_size("100E");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Size is too large: '100E'");
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Exact(10).apply(100).unwrap(), 10);
+        assert_eq!(Extend(10).apply(100).unwrap(), 110);
+        assert_eq!(Reduce(200).apply(100).unwrap(), 0);
+        assert_eq!(AtMost(10).apply(100).unwrap(), 10);
+        assert_eq!(AtMost(1000).apply(100).unwrap(), 100);
+        assert_eq!(AtLeast(1000).apply(100).unwrap(), 1000);
+        assert_eq!(RoundDown(64).apply(100).unwrap(), 64);
+        assert_eq!(RoundUp(64).apply(100).unwrap(), 128);
+        assert_eq!(RoundUp(64).apply(128).unwrap(), 128);
+        assert!(RoundUp(0).apply(100).is_err());
+        assert!(Extend(u64::MAX).apply(1).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = truncate::get_args().and_then(truncate::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,139 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "truncate";
+
+// --------------------------------------------------
+// Every test works with its own file in the temp folder, tests run in parallel
+fn temp_file(name: &str, size: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("truncate-{}-{name}", std::process::id()));
+    if size > 0 {
+        fs::write(&path, "x".repeat(size)).unwrap();
+    } else {
+        let _ = fs::remove_file(&path);
+    }
+    path
+}
+
+// --------------------------------------------------
+fn file_size(path: &PathBuf) -> u64 {
+    fs::metadata(path).unwrap().len()
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], path: &PathBuf) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .arg(path)
+        .assert()
+        .success();
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_size() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("file.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Either --size or --reference must be specified"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_size() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "10.5", "file.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value '10.5' for '--size <SIZE>': Invalid size: '10.5'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_absolute_size_with_reference() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "10", "-r", "Cargo.toml", "file.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Only relative --size can be used together with --reference",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn creates_file() -> TestResult {
+    let path = temp_file("create", 0);
+    run(&["-s", "1K"], &path)?;
+    assert_eq!(file_size(&path), 1024);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn no_create() -> TestResult {
+    let path = temp_file("no-create", 0);
+    run(&["-c", "-s", "1K"], &path)?;
+    assert!(!path.exists());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn shrinks_and_keeps_content() -> TestResult {
+    let path = temp_file("shrink", 100);
+    run(&["-s", "-90"], &path)?;
+    assert_eq!(fs::read_to_string(&path)?, "x".repeat(10));
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn extends_with_zeros() -> TestResult {
+    let path = temp_file("extend", 2);
+    run(&["--size", "+2"], &path)?;
+    assert_eq!(fs::read(&path)?, b"xx\0\0");
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn rounds() -> TestResult {
+    let path = temp_file("round", 1000);
+    run(&["-s", "%1KiB"], &path)?;
+    assert_eq!(file_size(&path), 1024);
+    run(&["-s", "/1KB"], &path)?;
+    assert_eq!(file_size(&path), 1000);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn reference() -> TestResult {
+    let reference = temp_file("reference", 300);
+    let path = temp_file("sized-by-reference", 5);
+    let reference_arg = reference.display().to_string();
+
+    run(&["-r", &reference_arg], &path)?;
+    assert_eq!(file_size(&path), 300);
+
+    run(&["-r", &reference_arg, "-s", "+1K"], &path)?;
+    assert_eq!(file_size(&path), 1324);
+
+    fs::remove_file(&path)?;
+    fs::remove_file(&reference)?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use anyhow::{anyhow, bail, Result};
+use clap::{arg, Command};
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+// Same lower bound as in procps watch
+const MIN_INTERVAL: f64 = 0.1;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const REVERSE: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug)]
+pub struct Config {
+    command: String,
+    interval: Duration,
+    differences: bool,
+    exit_on_change: bool,
+    no_title: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    // @argfile expansion is not used since arguments of the command must be passed as is
+    let mut matches = Command::new("watch")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Runs command repeatedly and shows its output full screen")
+        .args([
+            arg!(<COMMAND> ... "Command to run, it is passed to the shell")
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true),
+            arg!(-n --interval <SECONDS> "Seconds to wait between runs")
+                .value_parser(parse_interval)
+                .default_value("2"),
+            arg!(-d --differences "Highlight changes since the previous run"),
+            arg!(-g --chgexit "Exit when the output of the command changes"),
+            arg!(-t --no_title "Don't show the header line"),
+        ])
+        .get_matches();
+
+    // Construct config
+    Ok(Config {
+        command: matches
+            .remove_many::<String>("COMMAND")
+            .expect("Command is required")
+            .collect::<Vec<_>>()
+            .join(" "),
+        interval: matches.remove_one("interval").expect("Default value is provided"),
+        differences: matches.get_flag("differences"),
+        exit_on_change: matches.get_flag("chgexit"),
+        no_title: matches.get_flag("no_title"),
+    })
+}
+
+fn parse_interval(text: &str) -> Result<Duration> {
+    // Infinite or too long for a Duration is rejected too
+    let interval = match text.parse::<f64>() {
+        Ok(seconds) if seconds >= 0.0 => {
+            Duration::try_from_secs_f64(seconds.max(MIN_INTERVAL)).ok()
+        }
+        _ => None,
+    };
+    match interval {
+        Some(interval) => Ok(interval),
+        None => bail!("Invalid interval '{text}', expected a positive number of seconds"),
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut previous: Option<String> = None;
+    let clear = std::io::stdout().is_terminal();
+
+    loop {
+        let output = execute(&config.command)?;
+
+        let body = match &previous {
+            Some(previous) if config.differences => highlight(previous, &output),
+            _ => output.clone(),
+        };
+
+        let mut stdout = std::io::stdout().lock();
+        if clear {
+            write!(stdout, "{CLEAR_SCREEN}")?;
+        }
+        if !config.no_title {
+            let interval = config.interval.as_secs_f64();
+            let time = chrono::Local::now().format("%a %b %e %H:%M:%S %Y");
+            writeln!(stdout, "Every {interval:.1}s: {}    {time}\n", config.command)?;
+        }
+        write!(stdout, "{body}")?;
+        stdout.flush()?;
+        drop(stdout);
+
+        let changed = previous.as_ref().is_some_and(|previous| *previous != output);
+        if config.exit_on_change && changed {
+            return Ok(());
+        }
+
+        previous = Some(output);
+        std::thread::sleep(config.interval);
+    }
+}
+
+// Command line is interpreted by the shell, so pipes and globs work like in procps watch
+fn execute(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        std::process::Command::new("cmd").arg("/C").arg(command).output()
+    } else {
+        std::process::Command::new("sh").arg("-c").arg(command).output()
+    }
+    .map_err(|error| anyhow!("Can't run '{command}', error {error}"))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(text)
+}
+
+/// Marks characters that differ from the same position of the previous output
+fn highlight(previous: &str, current: &str) -> String {
+    let mut result = String::with_capacity(current.len());
+    let mut previous_lines = previous.split_inclusive('\n');
+
+    for line in current.split_inclusive('\n') {
+        let mut previous_chars = previous_lines.next().unwrap_or_default().chars();
+        let mut highlighted = false;
+
+        for char in line.chars() {
+            let changed = char != '\n' && previous_chars.next() != Some(char);
+
+            if changed != highlighted {
+                result.push_str(if changed { REVERSE } else { RESET });
+                highlighted = changed;
+            }
+            result.push(char);
+        }
+
+        if highlighted {
+            result.push_str(RESET);
+        }
+    }
+
+    result
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{highlight, parse_interval};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("2").unwrap(), Duration::from_secs(2));
+        assert_eq!(parse_interval("0.5").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_interval("0").unwrap(), Duration::from_millis(100));
+
+        let res = parse_interval("-1");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid interval '-1', expected a positive number of seconds"
+        );
+        assert!(parse_interval("soon").is_err());
+
+        let res = parse_interval("1e20");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid interval '1e20', expected a positive number of seconds"
+        );
+        assert!(parse_interval("inf").is_err());
+    }
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(highlight("abc\n", "abc\n"), "abc\n");
+        assert_eq!(highlight("abc\n", "abd\n"), "ab\x1b[7md\x1b[0m\n");
+        assert_eq!(highlight("abc\n", "xbcd\n"), "\x1b[7mx\x1b[0mbc\x1b[7md\x1b[0m\n");
+        assert_eq!(highlight("1\n", "1\n2\n"), "1\n\x1b[7m2\x1b[0m\n");
+        assert_eq!(highlight("", "new"), "\x1b[7mnew\x1b[0m");
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = watch::get_args().and_then(watch::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
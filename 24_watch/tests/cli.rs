@@ -0,0 +1,82 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "watch";
+
+// --------------------------------------------------
+// Command that prints how many times it was run, so its output changes every time
+fn counter_command(name: &str) -> (String, PathBuf) {
+    let path = std::env::temp_dir().join(format!("watch-{}-{name}", std::process::id()));
+    let _ = fs::remove_file(&path);
+    (format!("echo run >> {0}; wc -l < {0}", path.display()), path)
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_command() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage:"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_interval() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "soon", "ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid interval 'soon'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn exits_on_change() -> TestResult {
+    let (command, counter) = counter_command("change");
+    Command::cargo_bin(PRG)?
+        .args(["-g", "-t", "-n", "0.1", &command])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout("1\n2\n");
+    fs::remove_file(counter)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn highlights_differences() -> TestResult {
+    let (command, counter) = counter_command("differences");
+    Command::cargo_bin(PRG)?
+        .args(["-g", "-d", "-t", "-n", "0.1", &command])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout("1\n\x1b[7m2\x1b[0m\n");
+    fs::remove_file(counter)?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn shows_title() -> TestResult {
+    let (command, counter) = counter_command("title");
+    Command::cargo_bin(PRG)?
+        .args(["--chgexit", "--interval", "0.1", &command])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^Every 0.1s: echo run .*\n\n1\nEvery 0.1s: ")?);
+    fs::remove_file(counter)?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use anyhow::{bail, Result};
+use clap::{arg, Command};
+use common::process::{exit_code, spawn_error_code};
+use std::process::Child;
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+// Exit codes of GNU timeout
+const TIMED_OUT: i32 = 124;
+const KILLED: i32 = 128 + SIGKILL;
+
+const SIGKILL: i32 = 9;
+
+#[cfg(unix)]
+const SIGNALS: [(&str, i32); 8] = [
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+];
+
+// There are no signals on Windows, the process is always terminated
+#[cfg(not(unix))]
+const SIGNALS: [(&str, i32); 2] = [("KILL", SIGKILL), ("TERM", 15)];
+
+// Numbers above these are not signals, SIGRTMAX on Linux and NSIG - 1 on the BSDs
+#[cfg(target_os = "linux")]
+const MAX_SIGNAL: i32 = 64;
+
+#[cfg(all(unix, not(target_os = "linux")))]
+const MAX_SIGNAL: i32 = 31;
+
+#[cfg(not(unix))]
+const MAX_SIGNAL: i32 = 15;
+
+#[derive(Debug)]
+pub struct Config {
+    duration: Option<Duration>,
+    command: Vec<String>,
+    signal: i32,
+    kill_after: Option<Duration>,
+    preserve_status: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    // @argfile expansion is not used since arguments of the command must be passed as is
+    let mut matches = Command::new("timeout")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Runs command and stops it if it is still running after the duration")
+        .args([
+            arg!(<DURATION> "Time limit, number with optional s, m, h or d suffix, 0 disables it")
+                .value_parser(parse_duration),
+            arg!(<COMMAND> ... "Command to run and its arguments")
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true),
+            arg!(-s --signal <SIGNAL> "Signal to send on timeout, name like TERM or a number")
+                .value_parser(parse_signal)
+                .default_value("TERM"),
+            arg!(-k --kill_after <DURATION> "Send KILL if still running this long after the signal")
+                .value_parser(parse_duration),
+            arg!(--preserve_status "Exit with the status of the command even when it timed out"),
+        ])
+        .get_matches();
+
+    // Construct config
+    Ok(Config {
+        duration: matches.remove_one("DURATION").expect("Duration is required"),
+        command: matches
+            .remove_many("COMMAND")
+            .expect("Command is required")
+            .collect(),
+        signal: matches.remove_one("signal").expect("Default value is provided"),
+        kill_after: matches.remove_one::<Option<Duration>>("kill_after").flatten(),
+        preserve_status: matches.get_flag("preserve_status"),
+    })
+}
+
+// Zero duration means there is no time limit
+fn parse_duration(text: &str) -> Result<Option<Duration>> {
+    let (number, multiplier) = match text.char_indices().last() {
+        Some((index, 's')) => (&text[..index], 1.0),
+        Some((index, 'm')) => (&text[..index], 60.0),
+        Some((index, 'h')) => (&text[..index], 60.0 * 60.0),
+        Some((index, 'd')) => (&text[..index], 24.0 * 60.0 * 60.0),
+        _ => (text, 1.0),
+    };
+
+    match number.parse::<f64>() {
+        Ok(0.0) => Ok(None),
+        // Infinite or too long for a Duration is rejected too
+        Ok(seconds) if seconds > 0.0 => match Duration::try_from_secs_f64(seconds * multiplier) {
+            Ok(duration) => Ok(Some(duration)),
+            Err(_) => bail!("Invalid duration '{text}'"),
+        },
+        _ => bail!("Invalid duration '{text}'"),
+    }
+}
+
+fn parse_signal(text: &str) -> Result<i32> {
+    // 0 only checks that the process exists and would never stop the command
+    match text.parse::<i32>() {
+        Ok(number @ 1..=MAX_SIGNAL) => return Ok(number),
+        Ok(_) => bail!("Unknown signal '{text}', numbers go from 1 to {MAX_SIGNAL}"),
+        Err(_) => (),
+    }
+
+    let name = text.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+
+    match SIGNALS.iter().find(|(known, _)| *known == name) {
+        Some(&(_, number)) => Ok(number),
+        None => {
+            let known: Vec<&str> = SIGNALS.iter().map(|(known, _)| *known).collect();
+            bail!("Unknown signal '{text}', supported are: {}", known.join(", "))
+        }
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let (program, args) = config.command.split_first().expect("Command is required");
+
+    let mut child = match std::process::Command::new(program).args(args).spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            eprintln!("Can't run '{program}', error {error}");
+            std::process::exit(spawn_error_code(&error));
+        }
+    };
+
+    let status = match config.duration {
+        Some(duration) => child.wait_timeout(duration)?,
+        None => Some(child.wait()?),
+    };
+
+    // Command finished in time, its status is passed on
+    if let Some(status) = status {
+        if !status.success() {
+            std::process::exit(exit_code(status));
+        }
+        return Ok(());
+    }
+
+    send_signal(&mut child, config.signal)?;
+    let mut killed = config.signal == SIGKILL;
+
+    let status = match config.kill_after {
+        Some(kill_after) => match child.wait_timeout(kill_after)? {
+            Some(status) => status,
+            None => {
+                child.kill()?;
+                killed = true;
+                child.wait()?
+            }
+        },
+        None => child.wait()?,
+    };
+
+    let code = match (config.preserve_status, killed) {
+        (true, _) => exit_code(status),
+        (false, true) => KILLED,
+        (false, false) => TIMED_OUT,
+    };
+    std::process::exit(code);
+}
+
+#[cfg(unix)]
+fn send_signal(child: &mut Child, signal: i32) -> Result<()> {
+    let pid = libc::pid_t::try_from(child.id())?;
+
+    // SAFETY: kill only sends a signal, pid belongs to the child we spawned and haven't reaped yet
+    if unsafe { libc::kill(pid, signal) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_signal(child: &mut Child, _signal: i32) -> Result<()> {
+    child.kill()?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{parse_duration, parse_signal, SIGKILL};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("10").unwrap(), Some(Duration::from_secs(10)));
+        assert_eq!(parse_duration("0.5s").unwrap(), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2m").unwrap(), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5h").unwrap(), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("1d").unwrap(), Some(Duration::from_secs(86400)));
+        assert_eq!(parse_duration("0").unwrap(), None);
+
+        let res = parse_duration("5x");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid duration '5x'");
+        assert!(parse_duration("-1").is_err());
+        assert!(parse_duration("s").is_err());
+
+        let res = parse_duration("1e20");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid duration '1e20'");
+        assert!(parse_duration("inf").is_err());
+        assert!(parse_duration("1e18d").is_err());
+    }
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("KILL").unwrap(), SIGKILL);
+        assert_eq!(parse_signal("sigkill").unwrap(), SIGKILL);
+        assert_eq!(parse_signal("9").unwrap(), SIGKILL);
+        assert_eq!(parse_signal("TERM").unwrap(), 15);
+
+        let res = parse_signal("NOPE");
+        assert!(res.is_err());
+        assert!(res.unwrap_err().to_string().starts_with("Unknown signal 'NOPE', supported are: "));
+
+        for number in ["0", "-9", "1000"] {
+            let error = parse_signal(number).unwrap_err().to_string();
+            assert!(error.starts_with(&format!("Unknown signal '{number}'")));
+        }
+    }
+}
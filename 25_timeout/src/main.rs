@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = timeout::get_args().and_then(timeout::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
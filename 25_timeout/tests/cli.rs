@@ -0,0 +1,112 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "timeout";
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_duration() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["5x", "ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid duration '5x'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_signal() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "NOPE", "1", "ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown signal 'NOPE'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_command_not_found() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["1", "no-such-command-for-timeout-test"])
+        .assert()
+        .code(127)
+        .stderr(predicate::str::contains("Can't run 'no-such-command-for-timeout-test'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn finishes_in_time() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["5", "echo", "-n", "in time"])
+        .assert()
+        .success()
+        .stdout("in time");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn passes_exit_code() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["5s", "sh", "-c", "exit 3"])
+        .assert()
+        .code(3);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn times_out() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["0.2", "sleep", "5"])
+        .timeout(std::time::Duration::from_secs(3))
+        .assert()
+        .code(124);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn times_out_preserve_status() -> TestResult {
+    // Sleep is stopped by TERM, that is signal 15
+    Command::cargo_bin(PRG)?
+        .args(["--preserve_status", "0.2", "sleep", "5"])
+        .timeout(std::time::Duration::from_secs(3))
+        .assert()
+        .code(128 + 15);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn times_out_kill_signal() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--signal", "KILL", "0.2", "sleep", "5"])
+        .timeout(std::time::Duration::from_secs(3))
+        .assert()
+        .code(137);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn kill_after() -> TestResult {
+    // The command ignores TERM, so only KILL can stop it
+    Command::cargo_bin(PRG)?
+        .args(["-k", "0.2", "0.2", "sh", "-c", "trap '' TERM; while true; do :; done"])
+        .timeout(std::time::Duration::from_secs(3))
+        .assert()
+        .code(137);
+    Ok(())
+}
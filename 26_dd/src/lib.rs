@@ -0,0 +1,323 @@
+use anyhow::{anyhow, bail, Result};
+use clap::{arg, Command};
+use common::units::{self, Scale};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+
+// Same default block size as in GNU dd
+const DEFAULT_BLOCK_SIZE: usize = 512;
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    input: Option<String>,
+    output: Option<String>,
+    block_size: usize,
+    count: Option<u64>,
+    skip: u64,
+    seek: u64,
+    status: Status,
+}
+
+/// How much transfer statistics is printed to stderr
+#[derive(Debug, PartialEq)]
+enum Status {
+    Default,  // records and transfer summary at the end
+    NoXfer,   // records summary only
+    None,     // nothing
+    Progress, // periodic transfer line and the default summary
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let matches = Command::new("dd")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Copies a file block by block")
+        .after_help(
+            "Operands:\n  \
+            if=FILE       read from FILE instead of stdin\n  \
+            of=FILE       write to FILE instead of stdout\n  \
+            bs=BYTES      read and write up to BYTES at a time, default is 512\n  \
+            count=N       copy only N input blocks\n  \
+            skip=N        skip N input blocks at the start of input\n  \
+            seek=N        skip N output blocks at the start of output\n  \
+            status=LEVEL  none, noxfer or progress\n\n\
+            BYTES and N may have suffix c=1, w=2, b=512, K=1024, KB=1000\n\
+            and the same for M, G, T, P, E",
+        )
+        .args([arg!([OPERANDS] ... "Operands in the form of name=value")])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Construct config
+    let operands: Vec<&String> = matches.get_many("OPERANDS").unwrap_or_default().collect();
+    parse_operands(&operands)
+}
+
+fn parse_operands(operands: &[impl AsRef<str>]) -> Result<Config> {
+    let mut config = Config {
+        input: None,
+        output: None,
+        block_size: DEFAULT_BLOCK_SIZE,
+        count: None,
+        skip: 0,
+        seek: 0,
+        status: Status::Default,
+    };
+
+    for operand in operands {
+        let operand = operand.as_ref();
+        let Some((name, value)) = operand.split_once('=') else {
+            bail!("Unknown operand '{operand}'");
+        };
+        let number = || parse_bytes(value).ok_or_else(|| anyhow!("Invalid number in '{operand}'"));
+
+        match name {
+            "if" => config.input = Some(value.to_string()),
+            "of" => config.output = Some(value.to_string()),
+            "bs" => match usize::try_from(number()?) {
+                Ok(0) => bail!("Block size can't be zero"),
+                Ok(size) => config.block_size = size,
+                Err(_) => bail!("Block size is too large: '{operand}'"),
+            },
+            "count" => config.count = Some(number()?),
+            "skip" => config.skip = number()?,
+            "seek" => config.seek = number()?,
+            "status" => {
+                config.status = match value {
+                    "none" => Status::None,
+                    "noxfer" => Status::NoXfer,
+                    "progress" => Status::Progress,
+                    _ => bail!("Invalid status level '{value}', expected none, noxfer or progress"),
+                }
+            }
+            _ => bail!("Unknown operand '{operand}'"),
+        }
+    }
+
+    if config.seek > 0 && config.output.is_none() {
+        bail!("Operand seek= can only be used together with of=");
+    }
+
+    Ok(config)
+}
+
+// Number with optional multiplier suffix, the same suffixes as in GNU dd
+fn parse_bytes(text: &str) -> Option<u64> {
+    // Block multipliers of dd, the rest are the size units shared with truncate
+    let (number, multiplier) = match text.char_indices().last()? {
+        (index, 'c') => (&text[..index], 1),
+        (index, 'w') => (&text[..index], 2),
+        (index, 'b') => (&text[..index], 512),
+        _ => (text, 1),
+    };
+
+    units::parse_size(number).ok()?.checked_mul(multiplier)
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let block_size = config.block_size as u64;
+    let skip = config.skip.checked_mul(block_size).ok_or_else(|| anyhow!("Skip is too large"))?;
+    let seek = config.seek.checked_mul(block_size).ok_or_else(|| anyhow!("Seek is too large"))?;
+
+    let mut input = open_input(config.input.as_deref(), skip)?;
+    let mut output = open_output(config.output.as_deref(), seek)?;
+
+    let mut stats = Stats::new();
+    let mut buffer = vec![0; config.block_size];
+    let mut last_progress = Instant::now();
+    let mut progress_shown = false;
+
+    while config.count.is_none_or(|count| stats.records() < count) {
+        let read = read_block(&mut input, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+
+        // Every block is written as soon as it is read, the same as dd does without conv=
+        output.write_all(&buffer[..read])?;
+        stats.add(read, read == buffer.len());
+
+        if config.status == Status::Progress && last_progress.elapsed() >= PROGRESS_INTERVAL {
+            eprint!("\r{}", stats.transfer());
+            last_progress = Instant::now();
+            progress_shown = true;
+        }
+    }
+    output.flush()?;
+
+    if progress_shown {
+        eprintln!();
+    }
+    match config.status {
+        Status::None => {}
+        Status::NoXfer => eprintln!("{}", stats.records_summary()),
+        Status::Default | Status::Progress => {
+            eprintln!("{}\n{}", stats.records_summary(), stats.transfer())
+        }
+    }
+
+    Ok(())
+}
+
+fn open_input(path: Option<&str>, skip: u64) -> Result<Box<dyn Read>> {
+    let Some(path) = path else {
+        let mut stdin = std::io::stdin().lock();
+        std::io::copy(&mut stdin.by_ref().take(skip), &mut std::io::sink())?;
+        return Ok(Box::new(stdin));
+    };
+
+    let mut file = File::open(path).map_err(|error| anyhow!("{path}: {error}"))?;
+    file.seek(SeekFrom::Start(skip))?;
+    Ok(Box::new(file))
+}
+
+fn open_output(path: Option<&str>, seek: u64) -> Result<Box<dyn Write>> {
+    let Some(path) = path else {
+        return Ok(Box::new(std::io::stdout().lock()));
+    };
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|error| anyhow!("{path}: {error}"))?;
+
+    // Data before the seek offset is preserved, everything after it is replaced.
+    // Devices like /dev/null can't be truncated, they are only positioned.
+    if file.metadata()?.is_file() {
+        file.set_len(seek)?;
+    }
+    file.seek(SeekFrom::Start(seek))?;
+    Ok(Box::new(file))
+}
+
+// Single read call, so a short read from a pipe becomes a partial record like in dd
+fn read_block(input: &mut impl Read, buffer: &mut [u8]) -> Result<usize> {
+    loop {
+        match input.read(buffer) {
+            Ok(read) => return Ok(read),
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+/// Counters of the copied blocks, full blocks are the size of bs= and partial ones are shorter
+struct Stats {
+    full: u64,
+    partial: u64,
+    bytes: u64,
+    start: Instant,
+}
+
+impl Stats {
+    fn new() -> Self {
+        Self { full: 0, partial: 0, bytes: 0, start: Instant::now() }
+    }
+
+    fn add(&mut self, bytes: usize, full: bool) {
+        if full {
+            self.full += 1;
+        } else {
+            self.partial += 1;
+        }
+        self.bytes += bytes as u64;
+    }
+
+    fn records(&self) -> u64 {
+        self.full + self.partial
+    }
+
+    // Records out are the same as records in since blocks are written unchanged
+    fn records_summary(&self) -> String {
+        let records = format!("{}+{}", self.full, self.partial);
+        format!("{records} records in\n{records} records out")
+    }
+
+    fn transfer(&self) -> String {
+        let seconds = self.start.elapsed().as_secs_f64();
+        let rate = if seconds > 0.0 { self.bytes as f64 / seconds } else { 0.0 };
+        format!("{} bytes copied, {seconds:.6} s, {}/s", self.bytes, human_size(rate))
+    }
+}
+
+// Decimal units with two or three significant digits, like 7.4 MB or 294 MB
+fn human_size(bytes: f64) -> String {
+    let size = units::format(bytes, Scale::Si);
+    match size.char_indices().last() {
+        Some((index, 'K')) => format!("{} kB", &size[..index]),
+        Some((index, prefix)) if prefix.is_ascii_alphabetic() => {
+            format!("{} {prefix}B", &size[..index])
+        }
+        _ => format!("{size} B"),
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{human_size, parse_bytes, parse_operands, Status};
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("512"), Some(512));
+        assert_eq!(parse_bytes("3c"), Some(3));
+        assert_eq!(parse_bytes("3w"), Some(6));
+        assert_eq!(parse_bytes("2b"), Some(1024));
+        assert_eq!(parse_bytes("1K"), Some(1024));
+        assert_eq!(parse_bytes("1KB"), Some(1000));
+        assert_eq!(parse_bytes("1MiB"), Some(1 << 20));
+        assert_eq!(parse_bytes("1G"), Some(1 << 30));
+        assert_eq!(parse_bytes(""), None);
+        assert_eq!(parse_bytes("1x"), None);
+        assert_eq!(parse_bytes("-1"), None);
+        assert_eq!(parse_bytes("100E"), None);
+    }
+
+    #[test]
+    fn test_parse_operands() {
+        let config = parse_operands(&["if=a", "of=b", "bs=1K", "count=2", "skip=3", "seek=4"])
+            .unwrap();
+        assert_eq!(config.input.as_deref(), Some("a"));
+        assert_eq!(config.output.as_deref(), Some("b"));
+        assert_eq!(config.block_size, 1024);
+        assert_eq!(config.count, Some(2));
+        assert_eq!(config.skip, 3);
+        assert_eq!(config.seek, 4);
+        assert_eq!(config.status, Status::Default);
+
+        let config = parse_operands(&["status=progress"]).unwrap();
+        assert_eq!(config.status, Status::Progress);
+        assert_eq!(config.block_size, 512);
+
+        let res = parse_operands(&["bs=1x"]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Invalid number in 'bs=1x'");
+
+        let res = parse_operands(&["bs=0"]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Block size can't be zero");
+
+        let res = parse_operands(&["conv=ucase"]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "Unknown operand 'conv=ucase'");
+
+        let res = parse_operands(&["seek=1"]);
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Operand seek= can only be used together with of="
+        );
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(288.0), "288 B");
+        assert_eq!(human_size(7_400_000.0), "7.4 MB");
+        assert_eq!(human_size(294_000_000.0), "294 MB");
+        assert_eq!(human_size(1500.0), "1.5 kB");
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = dd::get_args().and_then(dd::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,172 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "dd";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .arg(format!("if={bad}"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_unknown_operand() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([&format!("if={FOX}"), "conv=ucase"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown operand 'conv=ucase'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_block_size() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([&format!("if={FOX}"), "bs=1x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid number in 'bs=1x'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .arg("status=none")
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> TestResult {
+    run(&["if=tests/inputs/fox.txt"], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_stdin() -> TestResult {
+    let input = fs::read_to_string(FOX)?;
+    Command::cargo_bin(PRG)?
+        .args(["bs=10", "skip=2", "status=none"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/fox.txt.bs10.skip2.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_count() -> TestResult {
+    run(
+        &["if=tests/inputs/fox.txt", "bs=10", "count=3"],
+        "tests/expected/fox.txt.bs10.count3.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_skip() -> TestResult {
+    run(
+        &["if=tests/inputs/fox.txt", "bs=10", "skip=2"],
+        "tests/expected/fox.txt.bs10.skip2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_skip_count() -> TestResult {
+    run(
+        &["if=tests/inputs/fox.txt", "bs=7", "skip=3", "count=4"],
+        "tests/expected/fox.txt.bs7.skip3.count4.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_large_block() -> TestResult {
+    run(
+        &["if=tests/inputs/fox.txt", "bs=1K", "count=1"],
+        "tests/expected/fox.txt.bs1K.count1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn reports_records() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([&format!("if={FOX}"), "bs=100"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(
+            "^2\\+1 records in\n2\\+1 records out\n288 bytes copied, .* s, .*/s\n$",
+        )?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn reports_records_noxfer() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([&format!("if={EMPTY}"), "status=noxfer"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("0+0 records in\n0+0 records out\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn seeks_output() -> TestResult {
+    let path = std::env::temp_dir().join(format!("dd-{}-seek", std::process::id()));
+    fs::write(&path, "0123456789abcdef")?;
+
+    Command::cargo_bin(PRG)?
+        .arg(format!("of={}", path.display()))
+        .args(["bs=4", "seek=1", "status=none"])
+        .write_stdin("XYZ")
+        .assert()
+        .success();
+
+    // Everything after the written block is truncated like in GNU dd
+    assert_eq!(fs::read_to_string(&path)?, "0123XYZ");
+    fs::remove_file(&path)?;
+    Ok(())
+}
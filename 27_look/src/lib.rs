@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+use clap::{arg, Command};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+// Same default as in util-linux look
+const DEFAULT_FILE: &str = "/usr/share/dict/words";
+
+#[derive(Debug)]
+pub struct Config {
+    prefix: String,
+    file: String,
+    fold_case: bool,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("look")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Shows lines of a sorted file that begin with the prefix")
+        .args([
+            arg!(<PREFIX> "Prefix to look for"),
+            arg!([FILE] "Sorted file to search, it needs to be seekable")
+                .default_value(DEFAULT_FILE),
+            arg!(-f --ignore_case "Ignore case, the file needs to be sorted with sort -f"),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    // Construct config
+    Ok(Config {
+        prefix: matches.remove_one("PREFIX").expect("Prefix is required"),
+        file: matches.remove_one("FILE").expect("Default value is provided"),
+        fold_case: matches.get_flag("ignore_case"),
+    })
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let file = File::open(&config.file).map_err(|error| anyhow!("{}: {error}", config.file))?;
+    let mut searcher = Searcher::new(file, config.prefix.as_bytes(), config.fold_case)?;
+
+    let start = searcher.first_match()?;
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+    let found = searcher.write_matches(start, &mut writer)?;
+    writer.flush()?;
+
+    // Nothing found is reported with the exit code, the same as in look
+    if !found {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Binary search over byte offsets of a file with sorted lines
+///
+/// Only a logarithmic number of lines is read to find the first match,
+/// after that matching lines are read sequentially.
+struct Searcher<R> {
+    reader: R,
+    size: u64,
+    prefix: Vec<u8>,
+    fold_case: bool,
+    line: Vec<u8>,
+}
+
+impl<F: Read + Seek> Searcher<BufReader<F>> {
+    fn new(mut file: F, prefix: &[u8], fold_case: bool) -> Result<Self> {
+        let size = file.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            size,
+            prefix: fold(prefix, fold_case),
+            fold_case,
+            line: Vec::new(),
+        })
+    }
+}
+
+impl<R: BufRead + Seek> Searcher<R> {
+    /// Offset of the first line that is not less than the prefix
+    fn first_match(&mut self) -> Result<u64> {
+        // First line after any offset below low is less than the prefix, after high it is not
+        let mut low = 0;
+        let mut high = self.size;
+
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let start = self.line_start(middle)?;
+
+            if start < self.size && self.compare_line(start)? == Ordering::Less {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        self.line_start(low)
+    }
+
+    /// Start of the first line at or after the offset, size of the file if there is none
+    fn line_start(&mut self, offset: u64) -> Result<u64> {
+        if offset == 0 {
+            return Ok(0);
+        }
+
+        // Line that contains the byte before the offset ends where the next one starts
+        self.reader.seek(SeekFrom::Start(offset - 1))?;
+        self.line.clear();
+        let read = self.reader.read_until(b'\n', &mut self.line)?;
+        Ok(offset - 1 + read as u64)
+    }
+
+    // Line is cut to the prefix length, so every line that begins with the prefix is equal to it
+    fn compare_line(&mut self, start: u64) -> Result<Ordering> {
+        self.read_line(start)?;
+        Ok(self.compare_loaded())
+    }
+
+    fn read_line(&mut self, start: u64) -> Result<usize> {
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.line.clear();
+        Ok(self.reader.read_until(b'\n', &mut self.line)?)
+    }
+
+    fn compare_loaded(&self) -> Ordering {
+        let content = self.line.strip_suffix(b"\n").unwrap_or(&self.line);
+        let head = &content[..content.len().min(self.prefix.len())];
+        fold(head, self.fold_case).cmp(&self.prefix)
+    }
+
+    /// Writes lines that begin with the prefix, returns if there were any
+    fn write_matches(&mut self, start: u64, writer: &mut impl Write) -> Result<bool> {
+        let mut found = false;
+        let mut read = self.read_line(start)?;
+
+        while read > 0 && self.compare_loaded() == Ordering::Equal {
+            writer.write_all(&self.line)?;
+            if !self.line.ends_with(b"\n") {
+                writer.write_all(b"\n")?;
+            }
+            found = true;
+
+            self.line.clear();
+            read = self.reader.read_until(b'\n', &mut self.line)?;
+        }
+
+        Ok(found)
+    }
+}
+
+// Uppercase the same as sort -f does, otherwise [\]^_` would be ordered before letters
+fn fold(text: &[u8], fold_case: bool) -> Vec<u8> {
+    if fold_case {
+        text.to_ascii_uppercase()
+    } else {
+        text.to_vec()
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::Searcher;
+    use std::io::Cursor;
+
+    fn look(text: &str, prefix: &str, fold_case: bool) -> String {
+        let mut searcher =
+            Searcher::new(Cursor::new(text.as_bytes()), prefix.as_bytes(), fold_case).unwrap();
+        let start = searcher.first_match().unwrap();
+        let mut output = Vec::new();
+        searcher.write_matches(start, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_look() {
+        let text = "a\nab\nabc\nabd\nb\nba\nc";
+        assert_eq!(look(text, "ab", false), "ab\nabc\nabd\n");
+        assert_eq!(look(text, "a", false), "a\nab\nabc\nabd\n");
+        assert_eq!(look(text, "c", false), "c\n");
+        assert_eq!(look(text, "abcd", false), "");
+        assert_eq!(look(text, "0", false), "");
+        assert_eq!(look(text, "d", false), "");
+        assert_eq!(look(text, "", false), format!("{text}\n"));
+        assert_eq!(look("", "a", false), "");
+    }
+
+    #[test]
+    fn test_look_fold_case() {
+        let text = "Apple\napple\nBanana\nband\nBAND\ncat\n";
+        assert_eq!(look(text, "band", false), "band\n");
+        assert_eq!(look(text, "BAND", true), "band\nBAND\n");
+        assert_eq!(look(text, "APP", true), "Apple\napple\n");
+
+        // Order of sort -f, _ goes after the letters
+        let text = "ab\nac\na_\nb\n";
+        assert_eq!(look(text, "a_", true), "a_\n");
+        assert_eq!(look(text, "AC", true), "ac\n");
+    }
+}
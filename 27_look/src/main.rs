@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = look::get_args().and_then(look::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,114 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "look";
+const WORDS: &str = "tests/inputs/words.txt";
+const FOLDED: &str = "tests/inputs/folded.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> TestResult {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["a", &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fails_not_found() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["zzz", WORDS])
+        .assert()
+        .code(1)
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fails_empty() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["a", EMPTY])
+        .assert()
+        .code(1)
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn words_a() -> TestResult {
+    run(&["a", WORDS], "tests/expected/words.txt.a.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_app() -> TestResult {
+    run(&["app", WORDS], "tests/expected/words.txt.app.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_ban() -> TestResult {
+    run(&["ban", WORDS], "tests/expected/words.txt.ban.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_case_sensitive() -> TestResult {
+    run(&["Cat", WORDS], "tests/expected/words.txt.Cat.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_last() -> TestResult {
+    run(&["zenith", WORDS], "tests/expected/words.txt.zenith.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn folded_ignore_case() -> TestResult {
+    run(&["-f", "cat", FOLDED], "tests/expected/folded.txt.f.cat.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn folded_ignore_case_upper() -> TestResult {
+    run(&["-f", "AMP", FOLDED], "tests/expected/folded.txt.f.AMP.out")
+}
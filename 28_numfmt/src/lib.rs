@@ -0,0 +1,169 @@
+use anyhow::Result;
+use clap::{arg, builder::PossibleValuesParser, Command};
+use common::ranges::parse as parse_ranges;
+use common::reader::open;
+use common::units::{self, Scale};
+use std::io::{BufRead, BufWriter, Write};
+use std::ops::RangeInclusive;
+
+#[derive(Debug)]
+pub struct Config {
+    numbers: Vec<String>,
+    from: Scale,
+    to: Scale,
+    fields: Vec<RangeInclusive<usize>>,
+    padding: Option<i64>,
+}
+
+pub fn get_args() -> Result<Config> {
+    // CLI arguments
+    let mut matches = Command::new("numfmt")
+        .version("1.0")
+        .author("FallenGameR")
+        .about("Converts numbers to and from human readable units, reads stdin if no numbers given")
+        .args([
+            arg!([NUMBERS] ... "Numbers to convert").allow_negative_numbers(true),
+            arg!(--from <UNIT> "Units of the input numbers")
+                .value_parser(PossibleValuesParser::new(["none", "si", "iec", "iec-i", "auto"]))
+                .default_value("none"),
+            arg!(--to <UNIT> "Units of the output numbers")
+                .value_parser(PossibleValuesParser::new(["none", "si", "iec", "iec-i"]))
+                .default_value("none"),
+            arg!(--field <FIELDS> "Whitespace separated fields to convert, like 2 or 1,3-4")
+                .value_parser(parse_ranges)
+                .default_value("1"),
+            arg!(--padding <WIDTH> "Pad converted numbers to the width, negative aligns left")
+                .value_parser(clap::value_parser!(i64))
+                .allow_hyphen_values(true),
+        ])
+        .get_matches_from(common::args::expand_args(std::env::args_os())?);
+
+    let from: String = matches.remove_one("from").expect("Default value is provided");
+    let to: String = matches.remove_one("to").expect("Default value is provided");
+
+    // Construct config
+    Ok(Config {
+        numbers: matches.remove_many("NUMBERS").unwrap_or_default().collect(),
+        from: parse_scale(&from),
+        to: parse_scale(&to),
+        fields: matches.remove_one("field").expect("Default value is provided"),
+        padding: matches.remove_one("padding"),
+    })
+}
+
+fn parse_scale(name: &str) -> Scale {
+    match name {
+        "none" => Scale::None,
+        "si" => Scale::Si,
+        "iec" => Scale::Iec,
+        "iec-i" => Scale::IecI,
+        "auto" => Scale::Auto,
+        unknown => unreachable!("Unsupported unit {unknown}"),
+    }
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let mut writer = BufWriter::new(std::io::stdout().lock());
+
+    if !config.numbers.is_empty() {
+        for number in &config.numbers {
+            writeln!(writer, "{}", pad(&convert(number, &config)?, 0, config.padding))?;
+        }
+    } else {
+        for line in open("-")?.lines() {
+            writeln!(writer, "{}", convert_line(&line?, &config)?)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn convert(number: &str, config: &Config) -> Result<String> {
+    Ok(units::format(units::parse(number, config.from)?, config.to))
+}
+
+/// Converts the selected fields and keeps the rest of the line as is
+///
+/// Whitespace before a field except the delimiter belongs to the field, so
+/// without --padding converted numbers keep the alignment of `ls -l` like columns.
+/// A number at the very start of the line is not aligned, the same as in GNU numfmt.
+fn convert_line(line: &str, config: &Config) -> Result<String> {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    let mut index = 0;
+
+    while !rest.is_empty() {
+        if index > 0 {
+            let delimiter = rest.chars().next().expect("Rest is not empty");
+            result.push(delimiter);
+            rest = &rest[delimiter.len_utf8()..];
+        }
+
+        let start = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+        let end = rest[start..].find(char::is_whitespace).map_or(rest.len(), |end| start + end);
+        let (field, tail) = rest.split_at(end);
+        let number = &field[start..];
+
+        if !number.is_empty() && config.fields.iter().any(|range| range.contains(&index)) {
+            let width = if index == 0 && start == 0 { 0 } else { field.chars().count() };
+            result.push_str(&pad(&convert(number, config)?, width, config.padding));
+        } else {
+            result.push_str(field);
+        }
+
+        rest = tail;
+        index += 1;
+    }
+
+    Ok(result)
+}
+
+// Explicit padding overrides the width of the original field
+fn pad(text: &str, width: usize, padding: Option<i64>) -> String {
+    match padding {
+        Some(padding) if padding < 0 => {
+            format!("{text:<width$}", width = padding.unsigned_abs() as usize)
+        }
+        Some(padding) => format!("{text:>width$}", width = padding.unsigned_abs() as usize),
+        None => format!("{text:>width$}"),
+    }
+}
+
+// --------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::{convert_line, parse_ranges, Config, Scale};
+
+    fn config(from: Scale, to: Scale, fields: &str, padding: Option<i64>) -> Config {
+        Config {
+            numbers: Vec::new(),
+            from,
+            to,
+            fields: parse_ranges(fields).unwrap(),
+            padding,
+        }
+    }
+
+    #[test]
+    fn test_convert_line() {
+        let to_si = config(Scale::None, Scale::Si, "1", None);
+        assert_eq!(convert_line("  1500   x", &to_si).unwrap(), "  1.5K   x");
+        assert_eq!(convert_line("x 1500", &to_si).unwrap_err().to_string(), "Invalid number: 'x'");
+
+        let from_si = config(Scale::Si, Scale::None, "2", None);
+        assert_eq!(convert_line("x 1K y", &from_si).unwrap(), "x 1000 y");
+        assert_eq!(convert_line("x  1K y", &from_si).unwrap(), "x 1000 y");
+        assert_eq!(convert_line("x", &from_si).unwrap(), "x");
+        assert_eq!(convert_line("2000000 b", &to_si).unwrap(), "2.0M b");
+
+        let to_iec = config(Scale::None, Scale::Iec, "2", None);
+        assert_eq!(convert_line("g 1048576", &to_iec).unwrap(), "g    1.0M");
+
+        let padded = config(Scale::None, Scale::Iec, "2-3", Some(6));
+        assert_eq!(convert_line("a    1536 2048 b", &padded).unwrap(), "a   1.5K   2.0K b");
+
+        let left = config(Scale::None, Scale::Iec, "2", Some(-6));
+        assert_eq!(convert_line("a 1536 b", &left).unwrap(), "a 1.5K   b");
+    }
+}
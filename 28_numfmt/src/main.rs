@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = numfmt::get_args().and_then(numfmt::run) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
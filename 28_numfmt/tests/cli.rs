@@ -0,0 +1,112 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "numfmt";
+const SIZES: &str = "tests/inputs/sizes.txt";
+const LISTING: &str = "tests/inputs/listing.txt";
+const HUMAN: &str = "tests/inputs/human.txt";
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_number() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--to", "si", "abc"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid number: 'abc'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_suffix_without_from() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("1K")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Rejecting suffix in input: '1K' (consider using --from)",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_field() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--field", "0", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid range '0'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], input_file: &str, expected_file: &str) -> TestResult {
+    let input = fs::read_to_string(input_file)?;
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn args_to_si() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/args.to_si.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--to=si", "1000", "1500", "999999", "-2500"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sizes_to_si() -> TestResult {
+    run(&["--to=si"], SIZES, "tests/expected/sizes.txt.to_si.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn listing_to_iec() -> TestResult {
+    run(
+        &["--to=iec", "--field=5"],
+        LISTING,
+        "tests/expected/listing.txt.to_iec.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn listing_to_iec_padding() -> TestResult {
+    run(
+        &["--to=iec", "--field=5", "--padding=8"],
+        LISTING,
+        "tests/expected/listing.txt.to_iec.padding8.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn human_from_si() -> TestResult {
+    run(&["--from=si"], HUMAN, "tests/expected/human.txt.from_si.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn human_from_si_to_iec_i() -> TestResult {
+    run(
+        &["--from=si", "--to=iec-i"],
+        HUMAN,
+        "tests/expected/human.txt.from_si.to_iec-i.out",
+    )
+}